@@ -4,17 +4,33 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use oxyd_collectors::UnifiedCollector;
+use oxyd_core::alerts::AlertEngine;
 use oxyd_core::engine::Engine;
-use oxyd_domain::{traits::ProcessManager, ProcessSignal};
-use oxyd_tui::{app::Action, event::map_key_to_action, App, Event, EventHandler};
+use oxyd_domain::{
+    errors::ProcessError, threshold_alert_rules, traits::ProcessManager, AlertEvent, AlertRule,
+    ProcessActionResult, ProcessSignal, SnapshotOptions, StopOptions,
+    DEFAULT_ALERT_THRESHOLD_PERCENT,
+};
+use oxyd_tui::{
+    app::Action, event::map_key_to_action, App, BatchOutcome, Event, EventHandler, ManagedCommand,
+    ProcessBatch, ProcessCommand, ProcessRequest, Tab,
+};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinSet;
 use tokio::time::{interval, Duration};
 
+// How many audit records the Audit tab loads.
+const AUDIT_VIEW_LIMIT: usize = 1000;
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::args().skip(1).any(|arg| arg == "--headless") {
+        return run_headless().await;
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -22,13 +38,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let engine = Engine::new_default();
+    let engine = engine();
     let process_manager = engine.process_manager().clone();
+    let stop_options = engine.config().process_manager.stop_options();
+    let collect_smaps = engine.config().collectors.process.collect_smaps;
+    let alert_dispatcher = engine.alert_dispatcher().clone();
 
     let collector = UnifiedCollector::new(process_manager.clone(), true);
     engine.add_collector(Box::new(collector)).await;
 
-    let mut app = App::new().with_process_manager(process_manager.clone());
+    let mut app = App::new()
+        .with_process_manager(process_manager.clone())
+        .with_memory_accounting(collect_smaps)
+        .with_alert_channels(alert_dispatcher.is_active());
+    let mut alert_engine = alert_engine(app.threshold_rules(), &engine.config().alerts.rules);
 
     let mut event_handler = EventHandler::new();
     event_handler.start_polling().await;
@@ -61,6 +84,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    // Audit records are written in the background, so a broken log only
+    // shows up here.
+    let action_tx_clone = action_tx.clone();
+    let pm = process_manager.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3));
+        loop {
+            interval.tick().await;
+            let _ = action_tx_clone.send(Action::AuditLogStatus(pm.audit_failure().await));
+        }
+    });
+
     // Main loop
     loop {
         // Render
@@ -72,7 +107,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Some(event) = event_handler.next() => {
                 match event {
                     Event::Key(key) => {
-                        if let Some(action) = map_key_to_action(key, app.state.input_mode, app.state.current_tab) {
+                        if let Some(action) = map_key_to_action(key, &app.state) {
                             let _ = action_tx.send(action);
                         }
                     }
@@ -85,11 +120,29 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             Some(action) = action_rx.recv() => {
                 match action.clone() {
+                    Action::UpdateMetrics(metrics) => {
+                        for event in alert_engine.evaluate(&metrics, &app.state.process_list) {
+                            let dispatcher = alert_dispatcher.clone();
+                            let tx = action_tx.clone();
+                            let delivered = event.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = dispatcher.send_event(&delivered).await {
+                                    let _ = tx.send(Action::AlertDeliveryFailed(e));
+                                }
+                            });
+                            let _ = action_tx.send(Action::AlertStateChanged(event));
+                        }
+                    }
+                    Action::SetAlertRule(rule) => alert_engine.set_rule(rule),
                     Action::LoadProcessList => {
                         let pm = process_manager.clone();
                         let tx = action_tx.clone();
+                        let options = SnapshotOptions {
+                            include_smaps: app.state.memory_accounting,
+                            ..SnapshotOptions::full()
+                        };
                         tokio::spawn(async move {
-                            match load_process_list(pm).await {
+                            match load_process_list(pm, options).await {
                                 Ok(processes) => {
                                     let _ = tx.send(Action::ProcessListLoaded(processes));
                                 }
@@ -101,107 +154,98 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                         });
                     }
-                    Action::KillSelectedProcess => {
-                        if let Some(process) = app.get_selected_process() {
-                            let pid = process.pid;
-                            let name = process.name.clone();
-                            let pm = process_manager.clone();
-                            let tx = action_tx.clone();
-
-                            tokio::spawn(async move {
-                                match pm.kill_process(pid).await {
-                                    Ok(_) => {
-                                        let _ = tx.send(Action::ProcessActionComplete(
-                                            format!("Killed process {} (PID: {})", name, pid)
-                                        ));
-                                        let _ = tx.send(Action::LoadProcessList);
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(Action::ProcessActionFailed(
-                                            format!("Failed to kill {}: {}", name, e)
-                                        ));
-                                    }
-                                }
-                            });
-                        }
+                    Action::ExecuteProcessCommand(request) => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(execute_process_command(pm, request, stop_options, tx));
                     }
-                    Action::SuspendSelectedProcess => {
-                        if let Some(process) = app.get_selected_process() {
-                            let pid = process.pid;
-                            let name = process.name.clone();
-                            let pm = process_manager.clone();
-                            let tx = action_tx.clone();
-
-                            tokio::spawn(async move {
-                                match pm.suspend_process(pid).await {
-                                    Ok(_) => {
-                                        let _ = tx.send(Action::ProcessActionComplete(
-                                            format!("Suspended process {} (PID: {})", name, pid)
-                                        ));
-                                        let _ = tx.send(Action::LoadProcessList);
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(Action::ProcessActionFailed(
-                                            format!("Failed to suspend {}: {}", name, e)
-                                        ));
-                                    }
-                                }
-                            });
-                        }
+                    Action::ExecuteProcessBatch(batch) => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(execute_process_batch(pm, batch, stop_options, tx));
                     }
-                    Action::ContinueSelectedProcess => {
-                        if let Some(process) = app.get_selected_process() {
-                            let pid = process.pid;
-                            let name = process.name.clone();
-                            let pm = process_manager.clone();
-                            let tx = action_tx.clone();
-
-                            tokio::spawn(async move {
-                                match pm.continue_process(pid).await {
-                                    Ok(_) => {
-                                        let _ = tx.send(Action::ProcessActionComplete(
-                                            format!("Continued process {} (PID: {})", name, pid)
-                                        ));
-                                        let _ = tx.send(Action::LoadProcessList);
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(Action::ProcessActionFailed(
-                                            format!("Failed to continue {}: {}", name, e)
-                                        ));
-                                    }
+                    Action::LoadProcessDetails(target) => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(async move {
+                            let action = match pm.get_process_details(target.pid).await {
+                                Ok(details) if details.process.identity() == target => {
+                                    Action::ProcessDetailsLoaded(Box::new(details))
                                 }
-                            });
-                        }
+                                Ok(_) => Action::ProcessDetailsFailed(
+                                    "the process has exited".to_string(),
+                                ),
+                                Err(e) => Action::ProcessDetailsFailed(e.to_string()),
+                            };
+                            let _ = tx.send(action);
+                        });
                     }
-                    Action::TerminateSelectedProcess => {
-                        if let Some(process) = app.get_selected_process() {
-                            let pid = process.pid;
-                            let name = process.name.clone();
-                            let pm = process_manager.clone();
-                            let tx = action_tx.clone();
-
-                            tokio::spawn(async move {
-                                match pm.send_signal(pid, ProcessSignal::Terminate).await {
-                                    Ok(_) => {
-                                        let _ = tx.send(Action::ProcessActionComplete(
-                                            format!("Terminated process {} (PID: {})", name, pid)
-                                        ));
-                                        let _ = tx.send(Action::LoadProcessList);
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(Action::ProcessActionFailed(
-                                            format!("Failed to terminate {}: {}", name, e)
-                                        ));
-                                    }
+                    // Only read the log while the Audit tab is showing it.
+                    Action::LoadAuditLog if app.state.current_tab == Tab::Audit => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(async move {
+                            match pm.recent_actions(AUDIT_VIEW_LIMIT).await {
+                                Ok(records) => {
+                                    let _ = tx.send(Action::AuditLogLoaded(records));
                                 }
-                            });
-                        }
+                                Err(e) => {
+                                    let _ = tx.send(Action::ProcessActionFailed(
+                                        format!("Failed to read audit log: {}", e)
+                                    ));
+                                }
+                            }
+                        });
+                    }
+                    Action::LoadManagedProcesses if app.state.current_tab == Tab::Managed => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(async move {
+                            if let Ok(managed) = pm.managed_processes().await {
+                                let _ = tx.send(Action::ManagedProcessesLoaded(managed));
+                            }
+                        });
+                    }
+                    Action::WatchProcess(target) => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(async move {
+                            let result = pm.watch_process(&target.identity).await;
+                            let _ = tx.send(Action::ProcessExited(
+                                target.identity,
+                                result.map_err(|e| e.to_string()),
+                            ));
+                        });
+                    }
+                    Action::SendAlert(alert) => {
+                        let dispatcher = alert_dispatcher.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = dispatcher.send(&alert).await {
+                                let _ = tx.send(Action::AlertDeliveryFailed(e));
+                            }
+                        });
+                    }
+                    Action::RecordDryRun(pid, dry_run) => {
+                        let pm = process_manager.clone();
+                        tokio::spawn(async move {
+                            let _ = pm.record_dry_run(pid, dry_run).await;
+                        });
+                    }
+                    Action::ExecuteManagedCommand(command) => {
+                        let pm = process_manager.clone();
+                        let tx = action_tx.clone();
+                        tokio::spawn(execute_managed_command(pm, command, tx));
                     }
                     _ => {}
                 }
 
                 app.dispatch(action);
 
+                for pending in app.take_pending_actions() {
+                    let _ = action_tx.send(pending);
+                }
+
                 if app.should_quit() {
                     break;
                 }
@@ -223,18 +267,265 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn load_process_list(
-    process_manager: Arc<dyn ProcessManager>,
-) -> Result<Vec<oxyd_domain::models::Process>, Box<dyn std::error::Error>> {
-    let pids = process_manager.list_processes().await?;
+// `oxyd --headless`: no terminal UI. Alert events are printed and delivered
+// to the configured channels until Ctrl-C.
+async fn run_headless() -> Result<(), Box<dyn std::error::Error>> {
+    let engine = engine();
+    let process_manager = engine.process_manager().clone();
+    let alert_dispatcher = engine.alert_dispatcher().clone();
+    let mut alert_engine = alert_engine(
+        threshold_alert_rules(
+            DEFAULT_ALERT_THRESHOLD_PERCENT,
+            DEFAULT_ALERT_THRESHOLD_PERCENT,
+            DEFAULT_ALERT_THRESHOLD_PERCENT,
+        ),
+        &engine.config().alerts.rules,
+    );
+
+    let collector = UnifiedCollector::new(process_manager.clone(), true);
+    engine.add_collector(Box::new(collector)).await;
+    let mut metrics_rx = engine.subscribe_metrics();
+
+    let engine_handle = tokio::spawn(async move {
+        if let Err(e) = engine.run().await {
+            eprintln!("Engine error: {}", e);
+        }
+    });
+
+    println!(
+        "Evaluating {} alert rule(s); Ctrl-C to stop.",
+        alert_engine.rules().len()
+    );
+    for rule in alert_engine.rules() {
+        println!("  {}: {}", rule.id, rule.condition.describe());
+    }
+
+    loop {
+        let metrics = tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            received = metrics_rx.recv() => match received {
+                Ok(metrics) => metrics,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        // Only ProcessNotRunning rules need the process list.
+        let processes = if alert_engine.needs_processes() {
+            process_manager
+                .snapshot_all(&SnapshotOptions::basic())
+                .await
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        for event in alert_engine.evaluate(&metrics, &processes) {
+            println!("{}", headless_line(&event));
+
+            let dispatcher = alert_dispatcher.clone();
+            tokio::spawn(async move {
+                if let Err(e) = dispatcher.send_event(&event).await {
+                    eprintln!("Alert delivery failed: {}", e);
+                }
+            });
+        }
+    }
+
+    engine_handle.abort();
+    Ok(())
+}
+
+// The built-in threshold rules, replaced by configured rules with their id.
+fn alert_engine(builtin: Vec<AlertRule>, configured: &[AlertRule]) -> AlertEngine {
+    let mut engine = AlertEngine::new(builtin);
+    for rule in configured {
+        engine.set_rule(rule.clone());
+    }
+    engine
+}
+
+fn headless_line(event: &AlertEvent) -> String {
+    let alert = event.to_message();
+    format!(
+        "{} [{}] {}: {}{}",
+        alert.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+        alert.severity.name().to_uppercase(),
+        alert.title,
+        alert.message,
+        if event.notify { "" } else { " (not sent)" }
+    )
+}
+
+// Runs one confirmed process command against the identity it was issued
+// for, reporting progress and the outcome back as actions.
+async fn execute_process_command(
+    pm: Arc<dyn ProcessManager>,
+    request: ProcessRequest,
+    stop_options: StopOptions,
+    tx: mpsc::UnboundedSender<Action>,
+) {
+    let progress_tx = tx.clone();
+    let progress = move |result: ProcessActionResult| {
+        if let Some(message) = result.message {
+            let _ = progress_tx.send(Action::ProcessActionProgress(message));
+        }
+    };
+
+    match run_process_command(&*pm, &request, &stop_options, &progress).await {
+        Ok(result) => {
+            let message = result
+                .message
+                .unwrap_or_else(|| format!("Done: {}", request.describe()));
+            let _ = tx.send(if result.success {
+                Action::ProcessActionComplete(message)
+            } else {
+                Action::ProcessActionFailed(message)
+            });
+            let _ = tx.send(Action::LoadProcessList);
+        }
+        Err(e) => {
+            let _ = tx.send(Action::ProcessActionFailed(format!(
+                "Failed to {}: {}",
+                request.describe(),
+                e
+            )));
+        }
+    }
+    let _ = tx.send(Action::LoadAuditLog);
+}
+
+// Runs a confirmed batch concurrently (a batch of graceful stops would
+// otherwise wait out every grace period in turn) and reports one outcome
+// per target once all of them have finished.
+async fn execute_process_batch(
+    pm: Arc<dyn ProcessManager>,
+    batch: ProcessBatch,
+    stop_options: StopOptions,
+    tx: mpsc::UnboundedSender<Action>,
+) {
+    let mut tasks = JoinSet::new();
+    for (index, request) in batch.requests().enumerate() {
+        let pm = pm.clone();
+        tasks.spawn(async move {
+            let result = match run_process_command(&*pm, &request, &stop_options, &|_| {}).await {
+                Ok(result) if result.success => Ok(result
+                    .message
+                    .unwrap_or_else(|| format!("Done: {}", request.describe()))),
+                Ok(result) => Err(result
+                    .message
+                    .unwrap_or_else(|| format!("Failed to {}", request.describe()))),
+                Err(e) => Err(e.to_string()),
+            };
+            let outcome = BatchOutcome {
+                target: request.target,
+                result,
+            };
+            (index, outcome)
+        });
+    }
+
+    let mut outcomes = Vec::with_capacity(batch.targets.len());
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(outcome) = joined {
+            outcomes.push(outcome);
+        }
+    }
+    outcomes.sort_by_key(|(index, _)| *index);
 
-    let mut processes = Vec::new();
+    let _ = tx.send(Action::ProcessBatchComplete(
+        batch.describe(),
+        outcomes.into_iter().map(|(_, outcome)| outcome).collect(),
+    ));
+    let _ = tx.send(Action::LoadProcessList);
+    let _ = tx.send(Action::LoadAuditLog);
+}
+
+async fn run_process_command(
+    pm: &dyn ProcessManager,
+    request: &ProcessRequest,
+    stop_options: &StopOptions,
+    progress: &(dyn Fn(ProcessActionResult) + Send + Sync),
+) -> Result<ProcessActionResult, ProcessError> {
+    let target = &request.target.identity;
+
+    // Signals re-check the identity themselves through a pidfd; checking up
+    // front as well keeps the other commands off a reused PID.
+    pm.verify_identity(target).await?;
 
-    for pid in pids.iter().take(500) {
-        if let Ok(process) = process_manager.get_process(*pid).await {
-            processes.push(process);
+    match &request.command {
+        ProcessCommand::Kill => pm.send_signal_to(target, ProcessSignal::Kill).await,
+        ProcessCommand::Terminate => pm.send_signal_to(target, ProcessSignal::Terminate).await,
+        ProcessCommand::Suspend => pm.send_signal_to(target, ProcessSignal::Stop).await,
+        ProcessCommand::Continue => pm.send_signal_to(target, ProcessSignal::Continue).await,
+        ProcessCommand::Stop => pm.stop_process(target, stop_options, progress).await,
+        ProcessCommand::Renice(nice) => pm.set_nice(target.pid, *nice).await,
+        ProcessCommand::SetScheduler(policy, priority) => {
+            pm.set_scheduler(target.pid, *policy, *priority).await
         }
+        ProcessCommand::SetAffinity(cpus) => pm.set_affinity(target.pid, cpus).await,
+        ProcessCommand::SetIoPriority(io_priority) => {
+            pm.set_io_priority(target.pid, *io_priority).await
+        }
+        ProcessCommand::MoveToCgroup(cgroup) => pm.move_to_cgroup(target.pid, cgroup).await,
+        ProcessCommand::SetCgroupLimit(limit) => pm.set_cgroup_limit(target.pid, limit).await,
+        ProcessCommand::SetLimit(change) => pm.set_limit(target.pid, change).await,
+        ProcessCommand::SetOomScoreAdj(adj) => pm.set_oom_score_adj(target.pid, *adj).await,
+    }
+}
+
+async fn execute_managed_command(
+    pm: Arc<dyn ProcessManager>,
+    command: ManagedCommand,
+    tx: mpsc::UnboundedSender<Action>,
+) {
+    let result = match &command {
+        ManagedCommand::Spawn(spec) => pm.spawn(spec.clone()).await.map(|managed| {
+            let message = format!(
+                "Started #{} {} (PID: {})",
+                managed.id,
+                spec.command_line(),
+                managed.pid.unwrap_or_default()
+            );
+            (true, message)
+        }),
+        ManagedCommand::Stop(id) => pm.stop_managed(*id).await.map(outcome),
+        ManagedCommand::Restart(id) => pm.restart_managed(*id).await.map(outcome),
+        ManagedCommand::Remove(id) => pm
+            .remove_managed(*id)
+            .await
+            .map(|()| (true, format!("Removed managed process #{}", id))),
+    };
+
+    let _ = tx.send(match result {
+        Ok((true, message)) => Action::ProcessActionComplete(message),
+        Ok((false, message)) => Action::ProcessActionFailed(message),
+        Err(e) => Action::ProcessActionFailed(format!("Failed to {}: {}", command.describe(), e)),
+    });
+    let _ = tx.send(Action::LoadManagedProcesses);
+    let _ = tx.send(Action::LoadAuditLog);
+}
+
+fn outcome(result: ProcessActionResult) -> (bool, String) {
+    (result.success, result.message.unwrap_or_default())
+}
+
+// Process actions need root by default. `--allow-non-root` lifts that, so
+// an unprivileged user can act on their own processes; the kernel still
+// refuses anything they have no permission for.
+fn engine() -> Engine {
+    let mut config = Engine::default_config();
+    if std::env::args().skip(1).any(|arg| arg == "--allow-non-root") {
+        config.process_manager.require_sudo = false;
     }
+    Engine::new(config)
+}
+
+async fn load_process_list(
+    process_manager: Arc<dyn ProcessManager>,
+    options: SnapshotOptions,
+) -> Result<Vec<oxyd_domain::models::Process>, Box<dyn std::error::Error>> {
+    let mut processes = process_manager.snapshot_all(&options).await?;
 
     processes.sort_by(|a, b| {
         b.cpu_usage_percent
@@ -0,0 +1,422 @@
+use oxyd_domain::{CgroupStats, CollectorError, Pressure, PressureLine, SystemMetrics};
+use oxyd_domain::Collector;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::fs;
+use tokio::sync::Mutex;
+use chrono::Utc;
+
+const DEFAULT_ROOT: &str = "/sys/fs/cgroup";
+// Hybrid (v1 + v2) hosts mount the unified hierarchy here instead.
+const HYBRID_ROOT: &str = "/sys/fs/cgroup/unified";
+
+// Walks a cgroup v2 hierarchy. The root is configurable so the collector
+// can run against a fixture directory instead of the live cgroupfs.
+pub struct CgroupCollector {
+    root: PathBuf,
+    // cpu.stat usage_usec of each cgroup at the previous collection.
+    previous_usage: Arc<Mutex<HashMap<String, (u64, Instant)>>>,
+}
+
+impl Default for CgroupCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CgroupCollector {
+    pub fn new() -> Self {
+        let root = [DEFAULT_ROOT, HYBRID_ROOT]
+            .into_iter()
+            .find(|root| Path::new(root).join("cgroup.controllers").exists())
+            .unwrap_or(DEFAULT_ROOT);
+        Self::with_root(root)
+    }
+
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            previous_usage: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // Every cgroup below the root, parents before children, siblings by name.
+    pub async fn read_all(&self) -> Result<Vec<CgroupStats>, CollectorError> {
+        let mut cgroups = Vec::new();
+        let mut pending = vec![self.root.clone()];
+
+        while let Some(dir) = pending.pop() {
+            // Cgroups can be removed while we walk; skip the ones that vanish.
+            let Ok(mut entries) = fs::read_dir(&dir).await else {
+                if dir == self.root {
+                    return Err(CollectorError::AccessError(
+                        self.root.display().to_string(),
+                        "cannot read cgroup root".to_string(),
+                    ));
+                }
+                continue;
+            };
+
+            let mut children = Vec::new();
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                    children.push(entry.path());
+                }
+            }
+            children.sort();
+            pending.extend(children.into_iter().rev());
+
+            cgroups.push(self.read_cgroup(&dir).await);
+        }
+
+        self.update_cpu_usage(&mut cgroups).await;
+        Ok(cgroups)
+    }
+
+    async fn read_cgroup(&self, dir: &Path) -> CgroupStats {
+        let relative = dir.strip_prefix(&self.root).unwrap_or(dir);
+        let path = format!("/{}", relative.display());
+
+        let read = |name: &str| {
+            let file = dir.join(name);
+            async move { fs::read_to_string(file).await.ok() }
+        };
+
+        let cpu_usage_usec = read("cpu.stat")
+            .await
+            .and_then(|content| parse_flat_keyed(&content, "usage_usec"))
+            .unwrap_or(0);
+        let (io_read_bytes, io_write_bytes) = read("io.stat")
+            .await
+            .map(|content| parse_io_stat(&content))
+            .unwrap_or((0, 0));
+
+        CgroupStats {
+            path,
+            cpu_usage_usec,
+            cpu_usage_percent: 0.0,
+            cpu_limit: read("cpu.max").await.and_then(|content| parse_cpu_max(&content)),
+            memory_current_bytes: read("memory.current").await.and_then(|content| parse_limit(&content)),
+            memory_max_bytes: read("memory.max").await.and_then(|content| parse_limit(&content)),
+            io_read_bytes,
+            io_write_bytes,
+            pids_current: read("pids.current").await.and_then(|content| parse_limit(&content)),
+            pids_max: read("pids.max").await.and_then(|content| parse_limit(&content)),
+            cpu_pressure: read("cpu.pressure").await.and_then(|content| parse_pressure(&content)),
+        }
+    }
+
+    // CPU usage since the previous collection. The first sample of a cgroup
+    // reports 0.
+    async fn update_cpu_usage(&self, cgroups: &mut [CgroupStats]) {
+        let now = Instant::now();
+        let mut previous = self.previous_usage.lock().await;
+        let mut current = HashMap::with_capacity(cgroups.len());
+
+        for cgroup in cgroups.iter_mut() {
+            if let Some(&(usage, at)) = previous.get(&cgroup.path) {
+                let elapsed_usec = now.duration_since(at).as_micros() as f64;
+                if elapsed_usec > 0.0 {
+                    let delta = cgroup.cpu_usage_usec.saturating_sub(usage) as f64;
+                    cgroup.cpu_usage_percent = delta / elapsed_usec * 100.0;
+                }
+            }
+            current.insert(cgroup.path.clone(), (cgroup.cpu_usage_usec, now));
+        }
+
+        // Replacing the map also forgets removed cgroups.
+        *previous = current;
+    }
+}
+
+#[async_trait]
+impl Collector for CgroupCollector {
+    fn id(&self) -> &str {
+        "cgroup"
+    }
+
+    async fn collect(&self) -> Result<SystemMetrics, CollectorError> {
+        let cgroups = self.read_all().await?;
+
+        Ok(SystemMetrics {
+            timestamp: Utc::now(),
+            system_info: Default::default(),
+            cpu: Default::default(),
+            memory: Default::default(),
+            disks: vec![],
+            network: Default::default(),
+            processes: Default::default(),
+            cgroups,
+        })
+    }
+
+    // Only the unified (v2) hierarchy is supported; it has cgroup.controllers
+    // at its root.
+    fn is_available(&self) -> bool {
+        self.root.join("cgroup.controllers").exists()
+    }
+}
+
+// Value of `key` in a "key value" per line file such as cpu.stat.
+fn parse_flat_keyed(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        if name == key {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+// Single value files (memory.current, memory.max, pids.max); "max" means
+// unlimited.
+fn parse_limit(content: &str) -> Option<u64> {
+    match content.trim() {
+        "max" => None,
+        value => value.parse().ok(),
+    }
+}
+
+// cpu.max is "<quota> <period>" in microseconds, or "max <period>".
+fn parse_cpu_max(content: &str) -> Option<f64> {
+    let mut parts = content.split_whitespace();
+    let quota: f64 = parts.next()?.parse().ok()?;
+    let period: f64 = parts.next()?.parse().ok()?;
+    (period > 0.0).then(|| quota / period)
+}
+
+// io.stat has one line per device: "8:0 rbytes=1 wbytes=2 rios=3 ...".
+// Returns the (read, write) bytes summed over all devices.
+fn parse_io_stat(content: &str) -> (u64, u64) {
+    let mut read = 0;
+    let mut write = 0;
+
+    for field in content.lines().flat_map(|line| line.split_whitespace().skip(1)) {
+        match field.split_once('=') {
+            Some(("rbytes", value)) => read += value.parse::<u64>().unwrap_or(0),
+            Some(("wbytes", value)) => write += value.parse::<u64>().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    (read, write)
+}
+
+// "some avg10=0.00 avg60=0.00 avg300=0.00 total=0" plus an optional "full"
+// line.
+fn parse_pressure(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut parsed = PressureLine::default();
+        for field in fields {
+            match field.split_once('=') {
+                Some(("avg10", value)) => parsed.avg10 = value.parse().unwrap_or(0.0),
+                Some(("avg60", value)) => parsed.avg60 = value.parse().unwrap_or(0.0),
+                Some(("avg300", value)) => parsed.avg300 = value.parse().unwrap_or(0.0),
+                Some(("total", value)) => parsed.total_usec = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => some = Some(parsed),
+            Some("full") => full = Some(parsed),
+            _ => {}
+        }
+    }
+
+    Some(Pressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A cgroup hierarchy under the temp directory, removed on drop.
+    struct Fixture {
+        root: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("oxyd-cgroup-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(&root).unwrap();
+            Self { root }
+        }
+
+        fn write(&self, cgroup: &str, file: &str, content: &str) {
+            let dir = self.root.join(cgroup.trim_start_matches('/'));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn hierarchy(name: &str) -> Fixture {
+        let fixture = Fixture::new(name);
+        fixture.write("/", "cgroup.controllers", "cpu io memory pids\n");
+        fixture.write(
+            "/",
+            "cpu.stat",
+            "usage_usec 9000000\nuser_usec 6000000\nsystem_usec 3000000\n",
+        );
+
+        fixture.write(
+            "/system.slice",
+            "cpu.stat",
+            "usage_usec 4000000\nuser_usec 3000000\n",
+        );
+        fixture.write("/system.slice", "cpu.max", "max 100000\n");
+        fixture.write("/system.slice", "memory.current", "1048576\n");
+        fixture.write("/system.slice", "memory.max", "max\n");
+        fixture.write("/system.slice", "pids.current", "12\n");
+        fixture.write("/system.slice", "pids.max", "max\n");
+        fixture.write(
+            "/system.slice",
+            "io.stat",
+            "8:0 rbytes=1000 wbytes=2000 rios=10 wios=20 dbytes=0 dios=0\n\
+             253:0 rbytes=500 wbytes=700 rios=5 wios=7 dbytes=0 dios=0\n",
+        );
+        fixture.write(
+            "/system.slice",
+            "cpu.pressure",
+            "some avg10=1.50 avg60=0.75 avg300=0.25 total=123456\n\
+             full avg10=0.50 avg60=0.10 avg300=0.00 total=6543\n",
+        );
+
+        fixture.write(
+            "/system.slice/sshd.service",
+            "cpu.stat",
+            "usage_usec 250000\n",
+        );
+        fixture.write("/system.slice/sshd.service", "cpu.max", "50000 100000\n");
+        fixture.write("/system.slice/sshd.service", "memory.current", "524288\n");
+        fixture.write("/system.slice/sshd.service", "memory.max", "2097152\n");
+        fixture.write("/system.slice/sshd.service", "pids.current", "3\n");
+        fixture.write("/system.slice/sshd.service", "pids.max", "64\n");
+        fixture.write(
+            "/system.slice/sshd.service",
+            "cpu.pressure",
+            "some avg10=0.00 avg60=0.00 avg300=0.00 total=42\n",
+        );
+
+        // No control files at all.
+        fixture.write("/user.slice", "cgroup.type", "domain\n");
+        fixture
+    }
+
+    #[tokio::test]
+    async fn reads_the_tree_parents_first() {
+        let fixture = hierarchy("tree");
+        let collector = CgroupCollector::with_root(&fixture.root);
+        assert!(collector.is_available());
+
+        let cgroups = collector.read_all().await.unwrap();
+        let paths: Vec<&str> = cgroups.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/",
+                "/system.slice",
+                "/system.slice/sshd.service",
+                "/user.slice"
+            ]
+        );
+
+        let depths: Vec<usize> = cgroups.iter().map(|c| c.depth()).collect();
+        assert_eq!(depths, [0, 1, 2, 1]);
+        assert_eq!(cgroups[2].name(), "sshd.service");
+        assert_eq!(cgroups[0].cpu_usage_usec, 9_000_000);
+
+        let user = &cgroups[3];
+        assert_eq!(user.cpu_usage_usec, 0);
+        assert_eq!(user.memory_current_bytes, None);
+        assert!(user.cpu_pressure.is_none());
+    }
+
+    #[tokio::test]
+    async fn reads_limits_with_max_as_unlimited() {
+        let fixture = hierarchy("limits");
+        let cgroups = CgroupCollector::with_root(&fixture.root)
+            .read_all()
+            .await
+            .unwrap();
+
+        let system = &cgroups[1];
+        assert_eq!(system.cpu_limit, None);
+        assert_eq!(system.memory_current_bytes, Some(1_048_576));
+        assert_eq!(system.memory_max_bytes, None);
+        assert_eq!(system.memory_percent_of_limit(), None);
+        assert_eq!(system.pids_current, Some(12));
+        assert_eq!(system.pids_max, None);
+
+        let sshd = &cgroups[2];
+        assert_eq!(sshd.cpu_limit, Some(0.5));
+        assert_eq!(sshd.memory_max_bytes, Some(2_097_152));
+        assert_eq!(sshd.memory_percent_of_limit(), Some(25.0));
+        assert_eq!((sshd.pids_current, sshd.pids_max), (Some(3), Some(64)));
+    }
+
+    #[tokio::test]
+    async fn sums_io_over_devices_and_reads_pressure() {
+        let fixture = hierarchy("io");
+        let cgroups = CgroupCollector::with_root(&fixture.root)
+            .read_all()
+            .await
+            .unwrap();
+
+        let system = &cgroups[1];
+        assert_eq!(system.io_read_bytes, 1500);
+        assert_eq!(system.io_write_bytes, 2700);
+
+        let pressure = system.cpu_pressure.unwrap();
+        assert_eq!(pressure.some.avg10, 1.5);
+        assert_eq!(pressure.some.avg300, 0.25);
+        assert_eq!(pressure.some.total_usec, 123_456);
+        assert_eq!(pressure.full.unwrap().total_usec, 6543);
+
+        let sshd = cgroups[2].cpu_pressure.unwrap();
+        assert_eq!(sshd.some.total_usec, 42);
+        assert!(sshd.full.is_none());
+    }
+
+    #[tokio::test]
+    async fn cpu_usage_is_the_delta_between_collections() {
+        let fixture = hierarchy("usage");
+        let collector = CgroupCollector::with_root(&fixture.root);
+
+        let first = collector.read_all().await.unwrap();
+        assert!(first.iter().all(|c| c.cpu_usage_percent == 0.0));
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        fixture.write("/system.slice", "cpu.stat", "usage_usec 4010000\n");
+
+        let second = collector.read_all().await.unwrap();
+        assert!(second[1].cpu_usage_percent > 0.0);
+        // The delta is 10ms of CPU over at least 20ms of wall time.
+        assert!(second[1].cpu_usage_percent <= 50.0);
+        assert_eq!(second[2].cpu_usage_percent, 0.0);
+    }
+
+    #[tokio::test]
+    async fn missing_root_is_an_error() {
+        let fixture = Fixture::new("missing");
+        let collector = CgroupCollector::with_root(fixture.root.join("absent"));
+        assert!(!collector.is_available());
+        assert!(collector.read_all().await.is_err());
+    }
+}
@@ -25,9 +25,9 @@ impl CpuCollector {
         let mut stats: Vec<CpuStates> = Vec::new();
 
         for line in content.lines() {
-            if line.starts_with("cpu") && line.chars().nth(3).map_or(false, |c| c.is_whitespace() || c.is_numeric()) {
+            if line.starts_with("cpu") && line.chars().nth(3).is_some_and(|c| c.is_whitespace() || c.is_numeric()) {
                 let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() > 0 {
+                if !parts.is_empty() {
                     stats.push(CpuStates {
                         user: parts[1].parse().unwrap_or(0),
                         nice: parts[2].parse().unwrap_or(0),
@@ -75,7 +75,7 @@ impl CpuCollector {
         let parts: Vec<&str> = content.split_whitespace().collect();
         
         Ok(LoadAverage {
-            one_minute: parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0.0),
+            one_minute: parts.first().and_then(|s| s.parse().ok()).unwrap_or(0.0),
             five_minutes: parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0.0),
             fifteen_minutes: parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0.0),
         })
@@ -136,6 +136,7 @@ impl Collector for CpuCollector {
             disks: vec![],
             network: Default::default(),
             processes: Default::default(),
+            cgroups: vec![],
         })
     }
 
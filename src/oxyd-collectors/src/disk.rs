@@ -10,6 +10,12 @@ pub struct DiskCollector {
     mount_points: Vec<String>,
 }
 
+impl Default for DiskCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DiskCollector {
     pub fn new() -> Self {
         Self {
@@ -96,6 +102,7 @@ impl Collector for DiskCollector {
             disks,
             network: Default::default(),
             processes: Default::default(),
+            cgroups: vec![],
         })
     }
 
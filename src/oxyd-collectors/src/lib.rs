@@ -4,6 +4,7 @@ pub mod process;
 pub mod unified;
 pub mod disk;
 pub mod network;
+pub mod cgroup;
 
 pub use cpu::CpuCollector;
 pub use memory::MemoryCollector;
@@ -11,3 +12,4 @@ pub use process::ProcessCollector;
 pub use unified::UnifiedCollector;
 pub use network::NetworkCollector;
 pub use disk::DiskCollector;
+pub use cgroup::CgroupCollector;
@@ -6,11 +6,26 @@ use chrono::Utc;
 
 pub struct MemoryCollector;
 
+impl Default for MemoryCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemoryCollector {
     pub fn new() -> Self {
         Self
     }
 
+    // Missing before Linux 4.13, and unreadable in some sandboxes.
+    async fn read_oom_kills(&self) -> Option<u64> {
+        let content = fs::read_to_string("/proc/vmstat").await.ok()?;
+        content.lines().find_map(|line| {
+            line.strip_prefix("oom_kill ")
+                .and_then(|count| count.trim().parse().ok())
+        })
+    }
+
     async fn parse_meminfo(&self) -> Result<oxyd_domain::models::MemoryInfo, CollectorError> {
         let content = fs::read_to_string("/proc/meminfo")
             .await
@@ -69,6 +84,7 @@ impl MemoryCollector {
             swap_free_bytes: swap_free,
             usage_percent,
             swap_usage_percent,
+            oom_kills: self.read_oom_kills().await,
         })
     }
 }
@@ -90,6 +106,7 @@ impl Collector for MemoryCollector {
             disks: vec![],
             network: Default::default(),
             processes: Default::default(),
+            cgroups: vec![],
         })
     }
 
@@ -9,6 +9,12 @@ use tokio::fs;
 
 pub struct NetworkCollector {}
 
+impl Default for NetworkCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NetworkCollector {
     pub fn new() -> Self {
         Self {}
@@ -74,6 +80,7 @@ impl Collector for NetworkCollector {
                 active_connections: vec![],
             },
             processes: Default::default(),
+            cgroups: vec![],
         })
     }
 
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use oxyd_domain::{
     traits::{Collector, ProcessManager},
-    models::{SystemMetrics, SystemInfo, ProcessMetrics, ProcessState},
+    models::{SystemMetrics, SystemInfo, ProcessMetrics, ProcessState, SnapshotOptions},
     errors::CollectorError,
 };
 use std::sync::Arc;
@@ -9,27 +9,27 @@ use chrono::Utc;
 
 pub struct ProcessCollector {
     process_manager: Arc<dyn ProcessManager>,
-    max_processes: Option<usize>,
+    options: SnapshotOptions,
 }
 
 impl ProcessCollector {
     pub fn new(process_manager: Arc<dyn ProcessManager>) -> Self {
         Self {
             process_manager,
-            max_processes: None,
+            options: SnapshotOptions::basic(),
         }
     }
 
-    pub fn with_limit(process_manager: Arc<dyn ProcessManager>, limit: usize) -> Self {
+    pub fn with_options(process_manager: Arc<dyn ProcessManager>, options: SnapshotOptions) -> Self {
         Self {
             process_manager,
-            max_processes: Some(limit),
+            options,
         }
     }
 
     async fn collect_process_metrics(&self) -> Result<ProcessMetrics, CollectorError> {
-        let pids = self.process_manager
-            .list_processes()
+        let processes = self.process_manager
+            .snapshot_all(&self.options)
             .await
             .map_err(|e| CollectorError::SystemInfoError(e.to_string()))?;
 
@@ -38,25 +38,20 @@ impl ProcessCollector {
         let mut stopped_count = 0;
         let mut zombie_count = 0;
 
-        // Collect detailed info for processes (can be limited)
-        let process_limit = self.max_processes.unwrap_or(pids.len());
-        
-        for pid in pids.iter().take(process_limit) {
-            if let Ok(process) = self.process_manager.get_process(*pid).await {
-                match process.state {
-                    ProcessState::Running => running_count += 1,
-                    ProcessState::Sleeping | ProcessState::Waiting | ProcessState::Idle => {
-                        sleeping_count += 1
-                    }
-                    ProcessState::Stopped => stopped_count += 1,
-                    ProcessState::Zombie => zombie_count += 1,
-                    _ => {}
+        for process in &processes {
+            match process.state {
+                ProcessState::Running => running_count += 1,
+                ProcessState::Sleeping | ProcessState::Waiting | ProcessState::Idle => {
+                    sleeping_count += 1
                 }
+                ProcessState::Stopped => stopped_count += 1,
+                ProcessState::Zombie => zombie_count += 1,
+                _ => {}
             }
         }
 
         Ok(ProcessMetrics {
-            total_count: pids.len(),
+            total_count: processes.len(),
             running_count,
             sleeping_count,
             stopped_count,
@@ -110,6 +105,7 @@ impl Collector for ProcessCollector {
                 swap_free_bytes: 0,
                 usage_percent: 0.0,
                 swap_usage_percent: 0.0,
+                oom_kills: None,
             },
             disks: vec![],
             network: oxyd_domain::models::NetworkMetrics {
@@ -120,6 +116,7 @@ impl Collector for ProcessCollector {
                 active_connections: vec![],
             },
             processes: process_metrics,
+            cgroups: vec![],
         })
     }
 
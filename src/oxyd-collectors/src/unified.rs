@@ -7,7 +7,7 @@ use oxyd_domain::{
 use std::sync::Arc;
 use chrono::Utc;
 
-use crate::{CpuCollector, MemoryCollector, ProcessCollector, NetworkCollector, DiskCollector};
+use crate::{CpuCollector, MemoryCollector, ProcessCollector, NetworkCollector, DiskCollector, CgroupCollector};
 
 pub struct UnifiedCollector {
     cpu_collector: CpuCollector,
@@ -15,6 +15,7 @@ pub struct UnifiedCollector {
     process_collector: ProcessCollector,
     network_collector: NetworkCollector,  
     disk_collector: DiskCollector,        
+    cgroup_collector: CgroupCollector,
 }
 
 impl UnifiedCollector {
@@ -25,6 +26,7 @@ impl UnifiedCollector {
             process_collector: ProcessCollector::new(process_manager),
             network_collector: NetworkCollector::new(),  
             disk_collector: DiskCollector::new(),        
+            cgroup_collector: CgroupCollector::new(),
         }
     }
 
@@ -86,6 +88,15 @@ impl Collector for UnifiedCollector {
 
         let system_info = self.get_system_info().await;
 
+        // Without a cgroup v2 mount (e.g. cgroup v1 hosts or minimal
+        // containers) the Cgroups tab stays empty rather than failing
+        // every other metric.
+        let cgroups = if self.cgroup_collector.is_available() {
+            self.cgroup_collector.read_all().await.unwrap_or_default()
+        } else {
+            vec![]
+        };
+
         let cpu_metrics = cpu_result?.cpu;
         let memory_metrics = memory_result?.memory;
         let process_metrics = process_result?.processes;
@@ -100,6 +111,7 @@ impl Collector for UnifiedCollector {
             disks: disk_metrics,        
             network: network_metrics,  
             processes: process_metrics,
+            cgroups,
         })
     }
 
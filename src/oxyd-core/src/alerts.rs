@@ -0,0 +1,591 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use oxyd_domain::models::{
+    AggregateFunction, AlertCondition, AlertEvent, AlertRule, AlertState, Process, SystemMetrics,
+};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, Default)]
+enum Phase {
+    #[default]
+    Inactive,
+    Pending(DateTime<Utc>),
+    Firing(DateTime<Utc>),
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    phase: Phase,
+    // Whether the current firing was announced; its resolve only is if so.
+    notified: bool,
+    last_notified: Option<DateTime<Utc>>,
+    // Readings within the rule's aggregation window, oldest first.
+    readings: VecDeque<(DateTime<Utc>, f64)>,
+    // The values compared with the threshold, newest last; as many as the
+    // rule's sample window looks at.
+    recent: VecDeque<f64>,
+}
+
+impl RuleState {
+    // Records a reading and returns the value to compare: the reading, or
+    // the aggregate over the rule's window.
+    fn observe(&mut self, rule: &AlertRule, at: DateTime<Utc>, reading: f64) -> f64 {
+        let value = match rule.aggregation {
+            Some(aggregation) => {
+                self.readings.push_back((at, reading));
+                let window = TimeDelta::seconds(aggregation.window_seconds as i64);
+                while self
+                    .readings
+                    .front()
+                    .is_some_and(|(then, _)| at - *then > window)
+                {
+                    self.readings.pop_front();
+                }
+                aggregate(aggregation.function, self.readings.iter().map(|(_, v)| *v))
+            }
+            None => reading,
+        };
+
+        let keep = rule.samples.map_or(1, |window| window.of);
+        self.recent.push_back(value);
+        while self.recent.len() > keep {
+            self.recent.pop_front();
+        }
+        value
+    }
+
+    // Starts firing and returns whether to tell anyone.
+    fn fire(&mut self, rule: &AlertRule, now: DateTime<Utc>) -> bool {
+        let cooling = self
+            .last_notified
+            .is_some_and(|last| (now - last).num_seconds() < rule.cooldown_seconds as i64);
+        self.phase = Phase::Firing(now);
+        self.notified = !cooling;
+        if !cooling {
+            self.last_notified = Some(now);
+        }
+        self.notified
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: HashMap<String, RuleState>,
+    // Time and total bytes of the previous sample, for traffic rates.
+    previous_traffic: Option<(DateTime<Utc>, u64)>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    // Adds `rule`, or replaces the rule with its id.
+    pub fn set_rule(&mut self, rule: AlertRule) {
+        match self.rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    // Whether any rule needs the process list passed to `evaluate`.
+    pub fn needs_processes(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| matches!(rule.condition, AlertCondition::ProcessNotRunning(_)))
+    }
+
+    // `processes` is only read by `ProcessNotRunning` rules; an empty list
+    // means it is not known yet.
+    pub fn evaluate(&mut self, metrics: &SystemMetrics, processes: &[Process]) -> Vec<AlertEvent> {
+        let traffic_rate = self.traffic_rate(metrics);
+        let now = metrics.timestamp;
+
+        let mut events = Vec::new();
+        for rule in &self.rules {
+            let Some(reading) = measure(&rule.condition, metrics, processes, traffic_rate) else {
+                continue;
+            };
+            let state = self.states.entry(rule.id.clone()).or_default();
+            let value = state.observe(rule, now, reading.value);
+
+            // Hysteresis: a firing rule holds until the value drops to the
+            // clear threshold.
+            let trigger = trigger_threshold(&rule.condition);
+            let firing = matches!(state.phase, Phase::Firing(_));
+            let threshold = match rule.clear_threshold {
+                Some(clear) if firing => clear,
+                _ => trigger,
+            };
+
+            let over = state.recent.iter().filter(|v| **v > threshold).count();
+            let breached = match rule.samples {
+                Some(window) => over >= window.required,
+                None => value > threshold,
+            };
+
+            let mut message = describe(rule, &reading, value, threshold, threshold != trigger);
+            if let Some(window) = rule.samples {
+                message.push_str(&format!(
+                    "; over the threshold in {} of the last {} samples",
+                    over, window.of
+                ));
+            }
+
+            match (state.phase, breached) {
+                (Phase::Inactive, true) if rule.for_seconds > 0 => {
+                    state.phase = Phase::Pending(now);
+                    message.push_str(&format!("; fires if it holds for {}s", rule.for_seconds));
+                    events.push(event(rule, AlertState::Pending, message, now, false, None));
+                }
+                (Phase::Inactive, true) => {
+                    let notify = state.fire(rule, now);
+                    events.push(event(rule, AlertState::Firing, message, now, notify, None));
+                }
+                (Phase::Pending(since), true)
+                    if (now - since).num_seconds() >= rule.for_seconds as i64 =>
+                {
+                    let notify = state.fire(rule, now);
+                    events.push(event(rule, AlertState::Firing, message, now, notify, None));
+                }
+                // Never fired, so there is nothing to recover from.
+                (Phase::Pending(_), false) => {
+                    state.phase = Phase::Inactive;
+                    events.push(event(rule, AlertState::Resolved, message, now, false, None));
+                }
+                (Phase::Firing(since), false) => {
+                    state.phase = Phase::Inactive;
+                    let notify = state.notified;
+                    events.push(event(
+                        rule,
+                        AlertState::Resolved,
+                        message,
+                        now,
+                        notify,
+                        Some(since),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        events
+    }
+
+    fn traffic_rate(&mut self, metrics: &SystemMetrics) -> Option<f64> {
+        let total = metrics.network.total_bytes_sent + metrics.network.total_bytes_received;
+        let previous = self.previous_traffic.replace((metrics.timestamp, total));
+
+        let (at, bytes) = previous?;
+        let seconds = (metrics.timestamp - at).num_milliseconds() as f64 / 1000.0;
+        (seconds > 0.0).then(|| total.saturating_sub(bytes) as f64 / seconds)
+    }
+}
+
+fn event(
+    rule: &AlertRule,
+    state: AlertState,
+    message: String,
+    timestamp: DateTime<Utc>,
+    notify: bool,
+    firing_since: Option<DateTime<Utc>>,
+) -> AlertEvent {
+    AlertEvent {
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        severity: rule.severity,
+        state,
+        message,
+        timestamp,
+        channels: rule.channels.clone(),
+        notify,
+        firing_since,
+    }
+}
+
+// What a condition measured in one sample. `ProcessNotRunning` measures 1
+// while no such process runs and 0 otherwise.
+struct Reading {
+    value: f64,
+    // What was measured, for messages.
+    subject: String,
+}
+
+// None when the condition cannot be judged.
+fn measure(
+    condition: &AlertCondition,
+    metrics: &SystemMetrics,
+    processes: &[Process],
+    traffic_rate: Option<f64>,
+) -> Option<Reading> {
+    let reading = |value: f64, subject: &str| {
+        Some(Reading {
+            value,
+            subject: subject.to_string(),
+        })
+    };
+
+    match condition {
+        AlertCondition::CpuUsageAbove(_) => {
+            reading(metrics.cpu.overall_usage_percent as f64, "CPU usage")
+        }
+        AlertCondition::MemoryUsageAbove(_) => {
+            reading(metrics.memory.usage_percent as f64, "Memory usage")
+        }
+        AlertCondition::DiskUsageAbove(_) => {
+            let fullest = metrics
+                .disks
+                .iter()
+                .max_by(|a, b| a.info.usage_percent.total_cmp(&b.info.usage_percent))?;
+            reading(
+                fullest.info.usage_percent as f64,
+                &format!("Disk {} usage", fullest.info.mount_point),
+            )
+        }
+        AlertCondition::ProcessCount(_) => {
+            reading(metrics.processes.total_count as f64, "Process count")
+        }
+        AlertCondition::ProcessNotRunning(name) => {
+            if processes.is_empty() {
+                return None;
+            }
+            let running = processes.iter().any(|process| &process.name == name);
+            reading(if running { 0.0 } else { 1.0 }, name)
+        }
+        AlertCondition::NetworkTrafficAbove(_) => reading(traffic_rate?, "Network traffic"),
+        AlertCondition::TemperatureAbove(_) => {
+            let hottest = metrics
+                .cpu
+                .cores
+                .iter()
+                .filter_map(|core| core.temperature_celsius)
+                .max_by(f32::total_cmp)?;
+            reading(hottest as f64, "CPU temperature")
+        }
+        AlertCondition::Custom(_) => None,
+    }
+}
+
+// The value a condition fires above.
+fn trigger_threshold(condition: &AlertCondition) -> f64 {
+    match condition {
+        AlertCondition::CpuUsageAbove(percent)
+        | AlertCondition::MemoryUsageAbove(percent)
+        | AlertCondition::DiskUsageAbove(percent) => *percent as f64,
+        AlertCondition::ProcessCount(count) => *count as f64,
+        AlertCondition::NetworkTrafficAbove(bytes) => *bytes as f64,
+        AlertCondition::TemperatureAbove(celsius) => *celsius as f64,
+        AlertCondition::ProcessNotRunning(_) | AlertCondition::Custom(_) => 0.0,
+    }
+}
+
+fn describe(
+    rule: &AlertRule,
+    reading: &Reading,
+    value: f64,
+    threshold: f64,
+    clearing: bool,
+) -> String {
+    if let AlertCondition::ProcessNotRunning(name) = &rule.condition {
+        return if reading.value > 0.0 {
+            format!("No process named {} is running", name)
+        } else {
+            format!("A process named {} is running", name)
+        };
+    }
+
+    let aggregation = rule
+        .aggregation
+        .map(|aggregation| {
+            format!(
+                " ({} over {}s)",
+                aggregate_name(aggregation.function),
+                aggregation.window_seconds
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "{}{} is at {} ({}: {})",
+        reading.subject,
+        aggregation,
+        format_value(&rule.condition, value),
+        if clearing { "clears at" } else { "threshold" },
+        format_value(&rule.condition, threshold)
+    )
+}
+
+fn format_value(condition: &AlertCondition, value: f64) -> String {
+    match condition {
+        AlertCondition::CpuUsageAbove(_)
+        | AlertCondition::MemoryUsageAbove(_)
+        | AlertCondition::DiskUsageAbove(_) => format!("{:.1}%", value),
+        AlertCondition::NetworkTrafficAbove(_) => format!("{:.0} B/s", value),
+        AlertCondition::TemperatureAbove(_) => format!("{:.1}°C", value),
+        _ => format!("{:.0}", value),
+    }
+}
+
+fn aggregate_name(function: AggregateFunction) -> &'static str {
+    match function {
+        AggregateFunction::Avg => "avg",
+        AggregateFunction::Max => "max",
+        AggregateFunction::P95 => "p95",
+    }
+}
+
+// Never called with an empty window: the newest reading is always in it.
+fn aggregate(function: AggregateFunction, values: impl Iterator<Item = f64>) -> f64 {
+    let mut values: Vec<f64> = values.collect();
+    match function {
+        AggregateFunction::Avg => values.iter().sum::<f64>() / values.len().max(1) as f64,
+        AggregateFunction::Max => values.iter().copied().fold(f64::MIN, f64::max),
+        // Nearest rank.
+        AggregateFunction::P95 => {
+            values.sort_by(f64::total_cmp);
+            let rank = (values.len() as f64 * 0.95).ceil() as usize;
+            values[rank.clamp(1, values.len()) - 1]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxyd_domain::models::{Aggregation, AlertSeverity, SampleWindow};
+
+    fn cpu_rule() -> AlertRule {
+        AlertRule::new(
+            "cpu",
+            "High CPU",
+            AlertCondition::CpuUsageAbove(90.0),
+            AlertSeverity::Warning,
+        )
+    }
+
+    fn at(second: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap()
+    }
+
+    // A sample `second`s into the test with the given CPU usage.
+    fn cpu(second: i64, percent: f32) -> SystemMetrics {
+        let mut metrics = SystemMetrics {
+            timestamp: at(second),
+            ..SystemMetrics::default()
+        };
+        metrics.cpu.overall_usage_percent = percent;
+        metrics
+    }
+
+    // Feeds one sample per (second, percent) and returns every event.
+    fn run(engine: &mut AlertEngine, samples: &[(i64, f32)]) -> Vec<AlertEvent> {
+        samples
+            .iter()
+            .flat_map(|(second, percent)| engine.evaluate(&cpu(*second, *percent), &[]))
+            .collect()
+    }
+
+    fn states(events: &[AlertEvent]) -> Vec<AlertState> {
+        events.iter().map(|event| event.state).collect()
+    }
+
+    #[test]
+    fn a_spike_shorter_than_for_seconds_does_not_fire() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            for_seconds: 10,
+            ..cpu_rule()
+        }]);
+
+        let events = run(&mut engine, &[(0, 95.0), (5, 95.0), (6, 50.0)]);
+        assert_eq!(states(&events), [AlertState::Pending, AlertState::Resolved]);
+        assert!(events.iter().all(|event| !event.notify));
+        assert_eq!(events[1].firing_since, None);
+
+        let events = run(&mut engine, &[(10, 95.0), (15, 95.0), (20, 95.0)]);
+        assert_eq!(states(&events), [AlertState::Pending, AlertState::Firing]);
+        assert_eq!(events[1].timestamp, at(20));
+        assert!(events[1].notify);
+    }
+
+    #[test]
+    fn fires_when_required_of_the_last_samples_breach() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            samples: Some(SampleWindow::new(3, 5).unwrap()),
+            ..cpu_rule()
+        }]);
+
+        let events = run(&mut engine, &[(0, 95.0), (1, 50.0), (2, 95.0), (3, 50.0)]);
+        assert!(events.is_empty(), "{:?}", events);
+
+        let events = run(&mut engine, &[(4, 95.0)]);
+        assert_eq!(states(&events), [AlertState::Firing]);
+        assert!(events[0].message.ends_with("in 3 of the last 5 samples"));
+
+        // The first breach leaves the window; two of five still breach.
+        let events = run(&mut engine, &[(5, 50.0)]);
+        assert_eq!(states(&events), [AlertState::Resolved]);
+    }
+
+    #[test]
+    fn keeps_firing_between_the_clear_and_trigger_thresholds() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            clear_threshold: Some(70.0),
+            ..cpu_rule()
+        }]);
+
+        assert_eq!(
+            states(&run(&mut engine, &[(0, 95.0)])),
+            [AlertState::Firing]
+        );
+        assert!(run(&mut engine, &[(1, 85.0), (2, 71.0)]).is_empty());
+
+        let events = run(&mut engine, &[(3, 70.0)]);
+        assert_eq!(states(&events), [AlertState::Resolved]);
+        assert!(events[0].message.ends_with("(clears at: 70.0%)"));
+
+        // Once resolved, the trigger threshold applies again.
+        assert!(run(&mut engine, &[(4, 85.0)]).is_empty());
+    }
+
+    #[test]
+    fn resolving_emits_a_recovery_event() {
+        let mut engine = AlertEngine::new(vec![cpu_rule()]);
+
+        run(&mut engine, &[(0, 95.0), (10, 95.0)]);
+        let events = run(&mut engine, &[(30, 40.0)]);
+        assert_eq!(states(&events), [AlertState::Resolved]);
+
+        let recovery = &events[0];
+        assert!(recovery.notify);
+        assert_eq!(recovery.firing_since, Some(at(0)));
+        let message = recovery.to_message();
+        assert_eq!(message.title, "Recovered: High CPU");
+        assert_eq!(message.severity, AlertSeverity::Info);
+
+        assert!(run(&mut engine, &[(40, 40.0)]).is_empty());
+    }
+
+    #[test]
+    fn p95_aggregation_ignores_a_single_spike() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            aggregation: Some(Aggregation {
+                function: AggregateFunction::P95,
+                window_seconds: 60,
+            }),
+            ..cpu_rule()
+        }]);
+
+        // 19 quiet samples and one spike: the 95th percentile of 20 is the
+        // 19th smallest.
+        let mut samples: Vec<(i64, f32)> = (0..19).map(|second| (second, 10.0)).collect();
+        samples.push((19, 100.0));
+        assert!(run(&mut engine, &samples).is_empty());
+
+        // A second spike in 21 samples is.
+        let events = run(&mut engine, &[(20, 100.0)]);
+        assert_eq!(states(&events), [AlertState::Firing]);
+        assert!(
+            events[0]
+                .message
+                .starts_with("CPU usage (p95 over 60s) is at 100.0%")
+        );
+
+        // Both spikes age out of the window.
+        let events = run(&mut engine, &[(81, 10.0)]);
+        assert_eq!(states(&events), [AlertState::Resolved]);
+    }
+
+    #[test]
+    fn cooldown_suppresses_notifying_again() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            cooldown_seconds: 300,
+            ..cpu_rule()
+        }]);
+
+        let events = run(&mut engine, &[(0, 95.0), (10, 50.0)]);
+        assert_eq!(states(&events), [AlertState::Firing, AlertState::Resolved]);
+        assert!(events.iter().all(|event| event.notify));
+
+        // Fires again within the cooldown: the state changes, nobody is
+        // told, and so nobody is told it resolved either.
+        let events = run(&mut engine, &[(20, 95.0), (30, 50.0)]);
+        assert_eq!(states(&events), [AlertState::Firing, AlertState::Resolved]);
+        assert!(events.iter().all(|event| !event.notify));
+
+        // The cooldown counts from the last notification, at 0s.
+        let events = run(&mut engine, &[(300, 95.0)]);
+        assert_eq!(states(&events), [AlertState::Firing]);
+        assert!(events[0].notify);
+    }
+
+    #[test]
+    fn rules_keep_their_own_state() {
+        let memory = AlertRule::new(
+            "memory",
+            "High Memory",
+            AlertCondition::MemoryUsageAbove(80.0),
+            AlertSeverity::Critical,
+        );
+        let mut engine = AlertEngine::new(vec![
+            AlertRule {
+                cooldown_seconds: 300,
+                ..cpu_rule()
+            },
+            AlertRule {
+                for_seconds: 10,
+                ..memory
+            },
+        ]);
+        let sample = |second: i64, cpu_percent: f32, memory_percent: f32| {
+            let mut metrics = cpu(second, cpu_percent);
+            metrics.memory.usage_percent = memory_percent;
+            metrics
+        };
+        let events = |engine: &mut AlertEngine, metrics: SystemMetrics| {
+            engine
+                .evaluate(&metrics, &[])
+                .into_iter()
+                .map(|event| (event.rule_id, event.state, event.notify))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            events(&mut engine, sample(0, 95.0, 90.0)),
+            [
+                ("cpu".to_string(), AlertState::Firing, true),
+                ("memory".to_string(), AlertState::Pending, false),
+            ]
+        );
+        // Memory fires after its for_seconds while CPU keeps firing quietly.
+        assert_eq!(
+            events(&mut engine, sample(10, 95.0, 90.0)),
+            [("memory".to_string(), AlertState::Firing, true)]
+        );
+        assert_eq!(
+            events(&mut engine, sample(20, 50.0, 50.0)),
+            [
+                ("cpu".to_string(), AlertState::Resolved, true),
+                ("memory".to_string(), AlertState::Resolved, true),
+            ]
+        );
+        // CPU's cooldown keeps it quiet; it does not hold back memory,
+        // which waits out its own for_seconds again.
+        assert_eq!(
+            events(&mut engine, sample(30, 95.0, 90.0)),
+            [
+                ("cpu".to_string(), AlertState::Firing, false),
+                ("memory".to_string(), AlertState::Pending, false),
+            ]
+        );
+        assert_eq!(
+            events(&mut engine, sample(40, 95.0, 90.0)),
+            [("memory".to_string(), AlertState::Firing, true)]
+        );
+    }
+}
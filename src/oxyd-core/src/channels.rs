@@ -0,0 +1,343 @@
+use chrono::Local;
+use oxyd_domain::models::{AlertChannel, AlertConfig, AlertEvent, AlertMessage, EmailConfig};
+use oxyd_process_manager::ensure_private_dir;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::time::{Duration, timeout};
+
+const LOG_FILE_NAME: &str = "alerts.log";
+
+// Commands, webhooks and email are given up on after this long.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Delivers alerts to the channels configured in `AlertConfig`:
+//
+// - `Log` appends a line to `<data_dir>/alerts.log`
+// - `File` appends the alert as a JSON line
+// - `Command` runs through `sh -c` with the alert in OXYD_ALERT_* variables
+//   and as JSON on stdin
+// - `Webhook` POSTs the JSON, `Email` sends it over SMTP, both through
+//   curl(1) so oxyd needs no HTTP or SMTP stack of its own
+pub struct AlertDispatcher {
+    enabled: bool,
+    channels: Vec<AlertChannel>,
+    data_dir: PathBuf,
+    log_path: PathBuf,
+    // Names the netrc file of each email delivery.
+    next_netrc: AtomicU64,
+}
+
+impl AlertDispatcher {
+    pub fn new(config: &AlertConfig, data_dir: impl AsRef<Path>) -> Self {
+        Self {
+            enabled: config.enabled,
+            channels: config.channels.clone(),
+            data_dir: data_dir.as_ref().to_path_buf(),
+            log_path: data_dir.as_ref().join(LOG_FILE_NAME),
+            next_netrc: AtomicU64::new(1),
+        }
+    }
+
+    // Whether `send` would deliver anywhere.
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.channels.is_empty()
+    }
+
+    // Sends `alert` to every channel. One failing channel does not stop the
+    // others; the error lists all that failed.
+    pub async fn send(&self, alert: &AlertMessage) -> Result<(), String> {
+        self.send_to(&[], alert).await
+    }
+
+    // Sends a rule's event to the channels the rule names, or to all of them
+    // when it names none. Events within the rule's cooldown are not sent.
+    pub async fn send_event(&self, event: &AlertEvent) -> Result<(), String> {
+        if !event.notify {
+            return Ok(());
+        }
+        self.send_to(&event.channels, &event.to_message()).await
+    }
+
+    // `kinds` as in `AlertRule::channels`.
+    async fn send_to(&self, kinds: &[String], alert: &AlertMessage) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let mut failures = Vec::new();
+        for channel in self
+            .channels
+            .iter()
+            .filter(|channel| kinds.is_empty() || kinds.iter().any(|k| k == channel.kind()))
+        {
+            if let Err(e) = self.deliver(channel, alert).await {
+                failures.push(format!("{}: {}", channel_name(channel), e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("; "))
+        }
+    }
+
+    async fn deliver(&self, channel: &AlertChannel, alert: &AlertMessage) -> io::Result<()> {
+        let json = serde_json::to_string(alert).map_err(io::Error::other)?;
+
+        match channel {
+            AlertChannel::Log => {
+                let (path, line) = (self.log_path.clone(), log_line(alert));
+                blocking(move || {
+                    if let Some(dir) = path.parent() {
+                        ensure_private_dir(dir)?;
+                    }
+                    append_line(&path, &line)
+                })
+                .await
+            }
+            AlertChannel::File(path) => {
+                let path = PathBuf::from(path);
+                blocking(move || {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    append_line(&path, &json)
+                })
+                .await
+            }
+            AlertChannel::Command(command) => {
+                let mut process = Command::new("sh");
+                process
+                    .arg("-c")
+                    .arg(command)
+                    .env("OXYD_ALERT_TITLE", &alert.title)
+                    .env("OXYD_ALERT_MESSAGE", &alert.message)
+                    .env("OXYD_ALERT_SEVERITY", alert.severity.name())
+                    .env("OXYD_ALERT_TIMESTAMP", alert.timestamp.to_rfc3339());
+                run(process, json.as_bytes()).await
+            }
+            AlertChannel::Webhook(url) => {
+                let mut curl = curl();
+                curl.args(["-X", "POST", "-H", "Content-Type: application/json"])
+                    .args(["--data-binary", "@-"])
+                    .arg(url);
+                run(curl, json.as_bytes()).await
+            }
+            AlertChannel::Email(email) => {
+                let mut curl = curl();
+                curl.arg("--ssl-reqd")
+                    .arg("--url")
+                    .arg(smtp_url(email))
+                    .arg("--mail-from")
+                    .arg(&email.from);
+                for to in &email.to {
+                    curl.arg("--mail-rcpt").arg(to);
+                }
+                curl.args(["--upload-file", "-"]);
+                if email.username.is_empty() {
+                    return run(curl, email_body(email, alert).as_bytes()).await;
+                }
+
+                // Any user can read curl's argv, so the credentials go in a
+                // netrc file in the private data dir for this delivery only.
+                let netrc = self.data_dir.join(format!(
+                    "smtp-{}-{}.netrc",
+                    std::process::id(),
+                    self.next_netrc.fetch_add(1, Ordering::Relaxed)
+                ));
+                let (path, entry) = (netrc.clone(), netrc_entry(email));
+                blocking(move || {
+                    if let Some(dir) = path.parent() {
+                        ensure_private_dir(dir)?;
+                    }
+                    write_private(&path, &entry)
+                })
+                .await?;
+
+                curl.arg("--netrc-file").arg(&netrc);
+                let result = run(curl, email_body(email, alert).as_bytes()).await;
+                let _ = tokio::fs::remove_file(&netrc).await;
+                result
+            }
+        }
+    }
+}
+
+fn channel_name(channel: &AlertChannel) -> String {
+    match channel {
+        AlertChannel::File(path) => format!("file {}", path),
+        AlertChannel::Webhook(url) => format!("webhook {}", url),
+        _ => channel.kind().to_string(),
+    }
+}
+
+fn log_line(alert: &AlertMessage) -> String {
+    format!(
+        "{} [{}] {}: {}",
+        alert
+            .timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S"),
+        alert.severity.name().to_uppercase(),
+        alert.title,
+        alert.message
+    )
+}
+
+// Runs file I/O on the blocking pool.
+async fn blocking(write: impl FnOnce() -> io::Result<()> + Send + 'static) -> io::Result<()> {
+    tokio::task::spawn_blocking(write)
+        .await
+        .map_err(io::Error::other)?
+}
+
+// A link in place of the file is refused rather than followed.
+fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)?;
+    writeln!(file, "{}", line)
+}
+
+// A new file only we can read; an existing file or link is refused.
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)?;
+    file.write_all(content.as_bytes())
+}
+
+fn curl() -> Command {
+    let mut curl = Command::new("curl");
+    curl.args(["-fsS", "-m", &DELIVERY_TIMEOUT.as_secs().to_string()]);
+    curl
+}
+
+// Port 465 speaks TLS from the start; anything else upgrades with STARTTLS.
+fn smtp_url(email: &EmailConfig) -> String {
+    let scheme = if email.smtp_port == 465 {
+        "smtps"
+    } else {
+        "smtp"
+    };
+    format!("{}://{}:{}", scheme, email.smtp_server, email.smtp_port)
+}
+
+// Quoted, so credentials may hold spaces; curl reads \" and \\ escapes.
+fn netrc_entry(email: &EmailConfig) -> String {
+    let quote = |value: &str| {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("\"{}\"", escaped)
+    };
+    format!(
+        "machine {} login {} password {}\n",
+        email.smtp_server,
+        quote(&email.username),
+        quote(&email.password)
+    )
+}
+
+// Line breaks in the title would end the Subject header and start others.
+fn email_body(email: &EmailConfig, alert: &AlertMessage) -> String {
+    format!(
+        "From: {}\r\nTo: {}\r\nSubject: [oxyd {}] {}\r\nDate: {}\r\n\r\n{}\r\n",
+        email.from,
+        email.to.join(", "),
+        alert.severity.name(),
+        alert.title.replace(['\r', '\n'], " "),
+        alert.timestamp.to_rfc2822(),
+        alert.message
+    )
+}
+
+// Runs `command` with `input` on stdin and fails unless it exits with 0
+// within DELIVERY_TIMEOUT.
+async fn run(mut command: Command, input: &[u8]) -> io::Result<()> {
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores its input may exit before reading it.
+        let _ = stdin.write_all(input).await;
+    }
+
+    let output = timeout(DELIVERY_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "timed out"))??;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(match stderr.trim() {
+            "" => output.status.to_string(),
+            stderr => format!("{}: {}", output.status, stderr),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxyd_domain::models::AlertSeverity;
+
+    fn email() -> EmailConfig {
+        EmailConfig {
+            smtp_server: "smtp.example.com".to_string(),
+            smtp_port: 587,
+            username: "ops bot".to_string(),
+            password: "p\"a\\ss\nword".to_string(),
+            from: "oxyd@example.com".to_string(),
+            to: vec!["ops@example.com".to_string()],
+        }
+    }
+
+    #[test]
+    fn netrc_entry_quotes_credentials() {
+        assert_eq!(
+            netrc_entry(&email()),
+            "machine smtp.example.com login \"ops bot\" password \"p\\\"a\\\\ss\\nword\"\n"
+        );
+    }
+
+    #[test]
+    fn email_body_keeps_the_title_on_the_subject_line() {
+        let alert = AlertMessage::new(
+            "High CPU\r\nBcc: someone@example.com",
+            "cpu at 97%",
+            AlertSeverity::Warning,
+        );
+        let body = email_body(&email(), &alert);
+
+        let headers: Vec<&str> = body
+            .split("\r\n\r\n")
+            .next()
+            .unwrap()
+            .split("\r\n")
+            .collect();
+        assert_eq!(headers.len(), 4);
+        assert_eq!(
+            headers[2],
+            "Subject: [oxyd warning] High CPU  Bcc: someone@example.com"
+        );
+    }
+}
@@ -3,7 +3,10 @@ use oxyd_domain::{
     models::{Config, SystemMetrics},
     traits::{Collector, ProcessManager},
 };
-use oxyd_process_manager::LinuxProcessManager;
+use oxyd_process_manager::{AuditLog, LinuxProcessManager};
+
+use crate::channels::AlertDispatcher;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{RwLock, broadcast};
 use tokio::time::{Duration, interval};
@@ -11,6 +14,7 @@ use tokio::time::{Duration, interval};
 pub struct Engine {
     collectors: Arc<RwLock<Vec<Box<dyn Collector>>>>,
     process_manager: Arc<dyn ProcessManager>,
+    alert_dispatcher: Arc<AlertDispatcher>,
     metrics_tx: broadcast::Sender<SystemMetrics>,
     config: Config,
     running: Arc<RwLock<bool>>,
@@ -20,12 +24,16 @@ impl Engine {
     pub fn new(config: Config) -> Self {
         let (metrics_tx, _) = broadcast::channel(100);
 
-        let process_manager =
-            LinuxProcessManager::with_config(config.process_manager.protected_processes.clone());
+        let process_manager = LinuxProcessManager::from_config(&config.process_manager)
+            .with_audit_log(AuditLog::in_dir(&config.general.data_dir));
 
         Self {
             collectors: Arc::new(RwLock::new(Vec::new())),
             process_manager: Arc::new(process_manager),
+            alert_dispatcher: Arc::new(AlertDispatcher::new(
+                &config.alerts,
+                &config.general.data_dir,
+            )),
             metrics_tx,
             config,
             running: Arc::new(RwLock::new(false)),
@@ -88,12 +96,19 @@ impl Engine {
     }
 
     pub fn new_default() -> Self {
-        let config = Config {
+        Self::new(Self::default_config())
+    }
+
+    // Process actions need root unless the caller turns `require_sudo` off;
+    // oxyd's `--allow-non-root` does that for users acting on their own
+    // processes.
+    pub fn default_config() -> Config {
+        Config {
             general: oxyd_domain::models::GeneralConfig {
                 update_interval_ms: 1000,
                 history_size: 100,
                 log_level: oxyd_domain::models::LogLevel::Info,
-                data_dir: String::from("/tmp/oxyd"),
+                data_dir: default_data_dir(),
             },
             collectors: oxyd_domain::models::CollectorConfig {
                 enabled_collectors: vec![String::from("cpu"), String::from("memory")],
@@ -108,6 +123,7 @@ impl Engine {
                 process: oxyd_domain::models::ProcessCollectorConfig {
                     enabled: true,
                     command_line_max_length: 256,
+                    collect_smaps: false,
                 },
             },
             ui: oxyd_domain::models::UIConfig {
@@ -122,27 +138,55 @@ impl Engine {
                 allow_kill: true,
                 allow_priority_change: true,
                 require_sudo: true,
+                allow_spawn: true,
                 protected_processes: vec![
                     String::from("systemd"),
                     String::from("init"),
                     String::from("kernel"),
                 ],
+                stop_signal: oxyd_domain::models::ProcessSignal::Terminate,
+                stop_grace_period_ms: 5000,
             },
             alerts: oxyd_domain::models::AlertConfig {
                 enabled: false,
                 rules: vec![],
                 channels: vec![],
             },
-        };
+        }
+    }
 
-        Self::new(config)
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn process_manager(&self) -> &Arc<dyn ProcessManager> {
         &self.process_manager
     }
 
+    pub fn alert_dispatcher(&self) -> &Arc<AlertDispatcher> {
+        &self.alert_dispatcher
+    }
+
     pub fn subscribe_metrics(&self) -> broadcast::Receiver<SystemMetrics> {
         self.metrics_tx.subscribe()
     }
 }
+
+// Root keeps its data in /var/lib/oxyd, other users under $XDG_STATE_HOME
+// (~/.local/state by default). A shared path such as /tmp/oxyd could be
+// created by another user first.
+fn default_data_dir() -> String {
+    let euid = unsafe { libc::geteuid() };
+    if euid == 0 {
+        return String::from("/var/lib/oxyd");
+    }
+
+    let state_dir = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")));
+    match state_dir {
+        Some(dir) => dir.join("oxyd").to_string_lossy().into_owned(),
+        None => format!("/tmp/oxyd-{}", euid),
+    }
+}
@@ -1 +1,3 @@
+pub mod alerts;
+pub mod channels;
 pub mod engine;
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::config::{AlertChannel, AlertCondition, AlertRule, AlertSeverity};
+
+impl AlertSeverity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+}
+
+// One alert on its way to the configured `AlertChannel`s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertMessage {
+    pub title: String,
+    pub message: String,
+    pub severity: AlertSeverity,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AlertMessage {
+    pub fn new(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        severity: AlertSeverity,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            severity,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+// Default thresholds for the built-in CPU, memory and disk rules.
+pub const DEFAULT_ALERT_THRESHOLD_PERCENT: f32 = 90.0;
+
+// Ids of the built-in rules, whose thresholds the TUI edits on its
+// Settings tab.
+pub const CPU_ALERT_RULE: &str = "cpu";
+pub const MEMORY_ALERT_RULE: &str = "memory";
+pub const DISK_ALERT_RULE: &str = "disk";
+
+// The built-in usage rules at the given thresholds.
+pub fn threshold_alert_rules(cpu: f32, memory: f32, disk: f32) -> Vec<AlertRule> {
+    vec![
+        AlertRule::new(
+            CPU_ALERT_RULE,
+            "High CPU Usage",
+            AlertCondition::CpuUsageAbove(cpu),
+            AlertSeverity::Warning,
+        ),
+        AlertRule::new(
+            MEMORY_ALERT_RULE,
+            "High Memory Usage",
+            AlertCondition::MemoryUsageAbove(memory),
+            AlertSeverity::Warning,
+        ),
+        AlertRule::new(
+            DISK_ALERT_RULE,
+            "High Disk Usage",
+            AlertCondition::DiskUsageAbove(disk),
+            AlertSeverity::Warning,
+        ),
+    ]
+}
+
+impl AlertRule {
+    // A rule that goes to every channel and fires on the first sample the
+    // condition holds in, with no cooldown.
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        condition: AlertCondition,
+        severity: AlertSeverity,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            condition,
+            severity,
+            channels: Vec::new(),
+            cooldown_seconds: 0,
+            for_seconds: 0,
+            samples: None,
+            clear_threshold: None,
+            aggregation: None,
+        }
+    }
+}
+
+impl AlertCondition {
+    pub fn describe(&self) -> String {
+        match self {
+            AlertCondition::CpuUsageAbove(percent) => format!("CPU usage above {:.0}%", percent),
+            AlertCondition::MemoryUsageAbove(percent) => {
+                format!("memory usage above {:.0}%", percent)
+            }
+            AlertCondition::DiskUsageAbove(percent) => format!("disk usage above {:.0}%", percent),
+            AlertCondition::ProcessCount(count) => format!("more than {} processes", count),
+            AlertCondition::ProcessNotRunning(name) => format!("{} not running", name),
+            AlertCondition::NetworkTrafficAbove(bytes) => {
+                format!("network traffic above {} B/s", bytes)
+            }
+            AlertCondition::TemperatureAbove(celsius) => {
+                format!("CPU temperature above {:.0}°C", celsius)
+            }
+            AlertCondition::Custom(expression) => format!("custom: {}", expression),
+        }
+    }
+}
+
+impl AlertChannel {
+    // The name an `AlertRule::channels` entry uses to pick this channel.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertChannel::Log => "log",
+            AlertChannel::File(_) => "file",
+            AlertChannel::Command(_) => "command",
+            AlertChannel::Webhook(_) => "webhook",
+            AlertChannel::Email(_) => "email",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertState {
+    // The condition holds but not yet for as long as the rule asks.
+    Pending,
+    Firing,
+    Resolved,
+}
+
+impl AlertState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertState::Pending => "pending",
+            AlertState::Firing => "firing",
+            AlertState::Resolved => "resolved",
+        }
+    }
+}
+
+// A rule changing state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub severity: AlertSeverity,
+    pub state: AlertState,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+    // Channel kinds from the rule; empty means all of them.
+    pub channels: Vec<String>,
+    // False when nobody is told: while pending, within the rule's cooldown,
+    // and when an alert nobody was told about resolves.
+    pub notify: bool,
+    // When a resolved alert started firing.
+    #[serde(default)]
+    pub firing_since: Option<DateTime<Utc>>,
+}
+
+impl AlertEvent {
+    pub fn to_message(&self) -> AlertMessage {
+        let (title, severity) = match self.state {
+            AlertState::Pending => (format!("Pending: {}", self.rule_name), self.severity),
+            AlertState::Firing => (self.rule_name.clone(), self.severity),
+            // A pending alert that cleared never fired.
+            AlertState::Resolved if self.firing_since.is_none() => {
+                (format!("Cleared: {}", self.rule_name), AlertSeverity::Info)
+            }
+            AlertState::Resolved => (
+                format!("Recovered: {}", self.rule_name),
+                AlertSeverity::Info,
+            ),
+        };
+        let message = match self.firing_since {
+            Some(since) => format!(
+                "{} (firing since {})",
+                self.message,
+                since.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            None => self.message.clone(),
+        };
+        AlertMessage {
+            title,
+            message,
+            severity,
+            timestamp: self.timestamp,
+        }
+    }
+}
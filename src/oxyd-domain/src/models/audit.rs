@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::process::ProcessAction;
+
+// One line of the process action audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub actor: String, // user running oxyd, with the sudo caller if any
+    pub actor_uid: u32,
+    pub pid: u32,
+    pub name: String,
+    pub command: String,
+    pub action: ProcessAction,
+    pub success: bool,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    // Logged in dry-run mode; nothing was sent to the process.
+    #[serde(default)]
+    pub dry_run: bool,
+}
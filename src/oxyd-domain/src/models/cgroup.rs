@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+
+// Usage of one cgroup v2 directory. The kernel counts every descendant in a
+// cgroup's own files, so a slice already includes all of its scopes.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CgroupStats {
+    pub path: String, // relative to the cgroup root, "/" for the root itself
+    pub cpu_usage_usec: u64,
+    pub cpu_usage_percent: f64, // since the previous collection, 100 = one CPU
+    pub cpu_limit: Option<f64>, // cpu.max quota in CPUs, None when unlimited
+    pub memory_current_bytes: Option<u64>,
+    pub memory_max_bytes: Option<u64>, // None when unlimited
+    pub io_read_bytes: u64,
+    pub io_write_bytes: u64,
+    pub pids_current: Option<u64>,
+    pub pids_max: Option<u64>,
+    pub cpu_pressure: Option<Pressure>,
+}
+
+impl CgroupStats {
+    // Last path component, or "/" for the root.
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, name)) if !name.is_empty() => name,
+            _ => "/",
+        }
+    }
+
+    // Nesting level below the root, which is 0.
+    pub fn depth(&self) -> usize {
+        self.path.split('/').filter(|part| !part.is_empty()).count()
+    }
+
+    pub fn memory_percent_of_limit(&self) -> Option<f64> {
+        match (self.memory_current_bytes, self.memory_max_bytes) {
+            (Some(current), Some(max)) if max > 0 => Some(current as f64 / max as f64 * 100.0),
+            _ => None,
+        }
+    }
+}
+
+// A pressure stall information file (cpu.pressure, memory.pressure, ...).
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct Pressure {
+    pub some: PressureLine,
+    pub full: Option<PressureLine>, // not reported for CPU on older kernels
+}
+
+// Share of wall time some (or all) tasks were stalled, in percent.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct PressureLine {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_usec: u64,
+}
+
+// A resource limit written to a cgroup v2 control file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CgroupLimit {
+    // cpu.max: `quota_usec` of CPU time per `period_usec`, None = unlimited.
+    CpuMax {
+        quota_usec: Option<u64>,
+        period_usec: u64,
+    },
+    MemoryHigh(Option<u64>), // throttle and reclaim above this many bytes
+    MemoryMax(Option<u64>),  // OOM-kill above this many bytes
+    IoMax(IoLimit),
+}
+
+// One device line of io.max. Unset fields are written as "max", so a new
+// limit replaces all four of the device's previous ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoLimit {
+    pub device: String, // "major:minor", e.g. "8:0"
+    pub read_bps: Option<u64>,
+    pub write_bps: Option<u64>,
+    pub read_iops: Option<u64>,
+    pub write_iops: Option<u64>,
+}
+
+const DEFAULT_CPU_PERIOD_USEC: u64 = 100_000;
+
+impl CgroupLimit {
+    // Parses "cpu 0.5", "cpu max", "high 512M", "max 2G", "max max" or
+    // "io 8:0 rbps=10M wbps=max riops=100 wiops=100".
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut parts = input.split_whitespace();
+        let kind = parts.next()?.to_lowercase();
+
+        let limit = match kind.as_str() {
+            "cpu" | "cpu.max" => {
+                let quota_usec = match parts.next()? {
+                    "max" => None,
+                    cpus => {
+                        let cpus: f64 = cpus.parse().ok()?;
+                        if !cpus.is_finite() || cpus <= 0.0 {
+                            return None;
+                        }
+                        Some((cpus * DEFAULT_CPU_PERIOD_USEC as f64).round().max(1000.0) as u64)
+                    }
+                };
+                CgroupLimit::CpuMax {
+                    quota_usec,
+                    period_usec: DEFAULT_CPU_PERIOD_USEC,
+                }
+            }
+            "high" | "memory.high" => CgroupLimit::MemoryHigh(parse_limit_value(parts.next()?)?),
+            "max" | "memory.max" => CgroupLimit::MemoryMax(parse_limit_value(parts.next()?)?),
+            "io" | "io.max" => {
+                let device = parts.next()?;
+                let (major, minor) = device.split_once(':')?;
+                major.parse::<u32>().ok()?;
+                minor.parse::<u32>().ok()?;
+
+                let mut limit = IoLimit {
+                    device: device.to_string(),
+                    read_bps: None,
+                    write_bps: None,
+                    read_iops: None,
+                    write_iops: None,
+                };
+                for setting in parts.by_ref() {
+                    let (key, value) = setting.split_once('=')?;
+                    let value = parse_limit_value(value)?;
+                    match key {
+                        "rbps" => limit.read_bps = value,
+                        "wbps" => limit.write_bps = value,
+                        "riops" => limit.read_iops = value,
+                        "wiops" => limit.write_iops = value,
+                        _ => return None,
+                    }
+                }
+                CgroupLimit::IoMax(limit)
+            }
+            _ => return None,
+        };
+
+        parts.next().is_none().then_some(limit)
+    }
+
+    // Control file the limit is written to.
+    pub fn file(&self) -> &'static str {
+        match self {
+            CgroupLimit::CpuMax { .. } => "cpu.max",
+            CgroupLimit::MemoryHigh(_) => "memory.high",
+            CgroupLimit::MemoryMax(_) => "memory.max",
+            CgroupLimit::IoMax(_) => "io.max",
+        }
+    }
+
+    // What gets written to `file()`.
+    pub fn value(&self) -> String {
+        let or_max =
+            |value: Option<u64>| value.map_or_else(|| "max".to_string(), |v| v.to_string());
+        match self {
+            CgroupLimit::CpuMax {
+                quota_usec,
+                period_usec,
+            } => format!("{} {}", or_max(*quota_usec), period_usec),
+            CgroupLimit::MemoryHigh(bytes) | CgroupLimit::MemoryMax(bytes) => or_max(*bytes),
+            CgroupLimit::IoMax(io) => format!(
+                "{} rbps={} wbps={} riops={} wiops={}",
+                io.device,
+                or_max(io.read_bps),
+                or_max(io.write_bps),
+                or_max(io.read_iops),
+                or_max(io.write_iops)
+            ),
+        }
+    }
+
+    // Short human-readable form, e.g. "cpu.max 0.50 CPUs".
+    pub fn label(&self) -> String {
+        match self {
+            CgroupLimit::CpuMax {
+                quota_usec: Some(quota),
+                period_usec,
+            } => format!(
+                "cpu.max {:.2} CPUs",
+                *quota as f64 / (*period_usec).max(1) as f64
+            ),
+            other => format!("{} {}", other.file(), other.value()),
+        }
+    }
+}
+
+// "max", a plain number, or a number with a K/M/G/T suffix (powers of 1024).
+pub(crate) fn parse_limit_value(value: &str) -> Option<Option<u64>> {
+    if value.eq_ignore_ascii_case("max") {
+        return Some(None);
+    }
+
+    let (number, multiplier) = match value.char_indices().last()? {
+        (i, 'k' | 'K') => (&value[..i], 1u64 << 10),
+        (i, 'm' | 'M') => (&value[..i], 1 << 20),
+        (i, 'g' | 'G') => (&value[..i], 1 << 30),
+        (i, 't' | 'T') => (&value[..i], 1 << 40),
+        _ => (value, 1),
+    };
+    number
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(multiplier)
+        .map(Some)
+}
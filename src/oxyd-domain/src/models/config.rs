@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::errors::ConfigError;
+use super::process::{ProcessSignal, StopOptions};
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     pub general: GeneralConfig,
@@ -63,6 +66,9 @@ pub struct NetworkCollectorConfig {
 pub struct ProcessCollectorConfig {
     pub enabled: bool,
     pub command_line_max_length: usize,
+    // Read PSS, USS and swap from smaps_rollup for the process list.
+    #[serde(default)]
+    pub collect_smaps: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,7 +104,35 @@ pub struct ProcessManagerConfig {
     pub allow_kill: bool,
     pub allow_priority_change: bool,
     pub require_sudo: bool,
+    // Whether oxyd may launch and supervise commands.
+    #[serde(default = "default_allow_spawn")]
+    pub allow_spawn: bool,
     pub protected_processes: Vec<String>,
+    #[serde(default = "default_stop_signal")]
+    pub stop_signal: ProcessSignal,
+    #[serde(default = "default_stop_grace_period_ms")]
+    pub stop_grace_period_ms: u64,
+}
+
+impl ProcessManagerConfig {
+    pub fn stop_options(&self) -> StopOptions {
+        StopOptions {
+            signal: self.stop_signal,
+            grace_period_ms: self.stop_grace_period_ms,
+        }
+    }
+}
+
+fn default_allow_spawn() -> bool {
+    true
+}
+
+fn default_stop_signal() -> ProcessSignal {
+    StopOptions::default().signal
+}
+
+fn default_stop_grace_period_ms() -> u64 {
+    StopOptions::default().grace_period_ms
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,6 +156,70 @@ pub struct AlertRule {
     pub severity: AlertSeverity,
     pub channels: Vec<String>,
     pub cooldown_seconds: u64,
+    // The condition has to hold this long before the rule fires; until then
+    // it is pending.
+    #[serde(default)]
+    pub for_seconds: u64,
+    // Fire when the condition held in `required` of the last `of` samples
+    // rather than in the latest one.
+    #[serde(default)]
+    pub samples: Option<SampleWindow>,
+    // A firing rule resolves once the value drops to this, below the
+    // trigger threshold in the condition. Numeric conditions only.
+    #[serde(default)]
+    pub clear_threshold: Option<f64>,
+    // Compare an aggregate over a time window instead of the latest value.
+    #[serde(default)]
+    pub aggregation: Option<Aggregation>,
+}
+
+// Checked when it is deserialized, so a config cannot hold a window that
+// never or always fires.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "SampleCounts")]
+pub struct SampleWindow {
+    pub required: usize,
+    pub of: usize,
+}
+
+impl SampleWindow {
+    // Fails unless 1 <= required <= of.
+    pub fn new(required: usize, of: usize) -> Result<Self, ConfigError> {
+        if required == 0 || required > of {
+            return Err(ConfigError::Invalid(format!(
+                "sample window needs 1 <= required <= of, got {} of {}",
+                required, of
+            )));
+        }
+        Ok(Self { required, of })
+    }
+}
+
+#[derive(Deserialize)]
+struct SampleCounts {
+    required: usize,
+    of: usize,
+}
+
+impl TryFrom<SampleCounts> for SampleWindow {
+    type Error = ConfigError;
+
+    fn try_from(counts: SampleCounts) -> Result<Self, Self::Error> {
+        Self::new(counts.required, counts.of)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Aggregation {
+    pub function: AggregateFunction,
+    pub window_seconds: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggregateFunction {
+    Avg,
+    Max,
+    P95,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -136,7 +234,7 @@ pub enum AlertCondition {
     Custom(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AlertSeverity {
     Info,
     Warning,
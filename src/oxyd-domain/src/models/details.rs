@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use super::limits::ResourceKind;
+use super::process::{Process, ProcessState};
+
+// Everything shown in the process detail view. Sections the caller may not
+// read (another user's environment or fds without root) are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessDetails {
+    pub process: Process,
+    pub environment: Option<Vec<(String, String)>>,
+    pub limits: Option<Vec<ResourceLimit>>,
+    pub cgroups: Vec<String>, // lines of /proc/<pid>/cgroup
+    pub namespaces: Option<Vec<Namespace>>,
+    // SELinux context or AppArmor profile from /proc/<pid>/attr/current;
+    // None without an active LSM.
+    pub security_label: Option<String>,
+    pub open_files: Option<Vec<OpenFile>>,
+    pub memory_maps: Option<MemoryMapSummary>,
+    pub threads: Vec<ThreadInfo>,
+}
+
+// One row of /proc/<pid>/limits; `None` means unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLimit {
+    pub name: String,
+    pub resource: Option<ResourceKind>, // None for rows this build does not know
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Namespace {
+    pub kind: String, // "net", "pid", "mnt", ...
+    pub inode: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub target: String, // readlink of /proc/<pid>/fd/<fd>, e.g. "socket:[1234]"
+}
+
+// /proc/<pid>/maps grouped by what backs each region, largest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryMapSummary {
+    pub regions: usize,
+    pub total_bytes: u64,
+    pub groups: Vec<MemoryMapGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryMapGroup {
+    pub name: String, // file path, "[heap]", "[stack]", "[anon]", ...
+    pub regions: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    pub state: ProcessState,
+    pub cpu_usage_percent: f64,
+    pub last_cpu: Option<u32>, // CPU the thread last ran on
+    pub nice: i32,
+    pub priority: i32,
+}
@@ -61,6 +61,27 @@ pub enum ProcessError {
 
     #[error("Failed to parse process info: {0}")]
     ParseError(String),
+
+    #[error("Not allowed to {0} process {1}: {2}")]
+    NotAllowed(String, u32, String),
+
+    #[error("Process {0} is no longer the selected process (PID was reused)")]
+    IdentityMismatch(u32),
+
+    #[error("Failed to spawn {0}: {1}")]
+    SpawnFailed(String, String),
+
+    #[error("Not allowed to spawn {0}: {1}")]
+    SpawnNotAllowed(String, String),
+
+    #[error("Managed process #{0} not found")]
+    ManagedNotFound(u64),
+
+    #[error("Managed process #{0} is still running; stop it first")]
+    ManagedActive(u64),
+
+    #[error("Managed process #{0} is already being started")]
+    ManagedStarting(u64),
 }
 
 #[derive(Error, Debug)]
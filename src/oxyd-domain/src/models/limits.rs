@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use super::cgroup::parse_limit_value;
+
+// A resource governed by setrlimit/prlimit (RLIMIT_*).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceKind {
+    Cpu,
+    FileSize,
+    Data,
+    Stack,
+    Core,
+    Rss,
+    Processes,
+    OpenFiles,
+    LockedMemory,
+    AddressSpace,
+    FileLocks,
+    PendingSignals,
+    MessageQueue,
+    Nice,
+    RealtimePriority,
+    RealtimeTimeout,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 16] = [
+        ResourceKind::Cpu,
+        ResourceKind::FileSize,
+        ResourceKind::Data,
+        ResourceKind::Stack,
+        ResourceKind::Core,
+        ResourceKind::Rss,
+        ResourceKind::Processes,
+        ResourceKind::OpenFiles,
+        ResourceKind::LockedMemory,
+        ResourceKind::AddressSpace,
+        ResourceKind::FileLocks,
+        ResourceKind::PendingSignals,
+        ResourceKind::MessageQueue,
+        ResourceKind::Nice,
+        ResourceKind::RealtimePriority,
+        ResourceKind::RealtimeTimeout,
+    ];
+
+    // Name used by prlimit(1) and ulimit, e.g. "nofile".
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceKind::Cpu => "cpu",
+            ResourceKind::FileSize => "fsize",
+            ResourceKind::Data => "data",
+            ResourceKind::Stack => "stack",
+            ResourceKind::Core => "core",
+            ResourceKind::Rss => "rss",
+            ResourceKind::Processes => "nproc",
+            ResourceKind::OpenFiles => "nofile",
+            ResourceKind::LockedMemory => "memlock",
+            ResourceKind::AddressSpace => "as",
+            ResourceKind::FileLocks => "locks",
+            ResourceKind::PendingSignals => "sigpending",
+            ResourceKind::MessageQueue => "msgqueue",
+            ResourceKind::Nice => "nice",
+            ResourceKind::RealtimePriority => "rtprio",
+            ResourceKind::RealtimeTimeout => "rttime",
+        }
+    }
+
+    // Row label in /proc/<pid>/limits.
+    pub fn proc_label(&self) -> &'static str {
+        match self {
+            ResourceKind::Cpu => "Max cpu time",
+            ResourceKind::FileSize => "Max file size",
+            ResourceKind::Data => "Max data size",
+            ResourceKind::Stack => "Max stack size",
+            ResourceKind::Core => "Max core file size",
+            ResourceKind::Rss => "Max resident set",
+            ResourceKind::Processes => "Max processes",
+            ResourceKind::OpenFiles => "Max open files",
+            ResourceKind::LockedMemory => "Max locked memory",
+            ResourceKind::AddressSpace => "Max address space",
+            ResourceKind::FileLocks => "Max file locks",
+            ResourceKind::PendingSignals => "Max pending signals",
+            ResourceKind::MessageQueue => "Max msgqueue size",
+            ResourceKind::Nice => "Max nice priority",
+            ResourceKind::RealtimePriority => "Max realtime priority",
+            ResourceKind::RealtimeTimeout => "Max realtime timeout",
+        }
+    }
+
+    pub fn from_proc_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.proc_label() == label)
+    }
+
+    // "nofile", "NOFILE" or "RLIMIT_NOFILE".
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let name = name.strip_prefix("rlimit_").unwrap_or(&name);
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitValue {
+    Unlimited,
+    Value(u64),
+}
+
+impl LimitValue {
+    // "unlimited" (or "infinity", "max"), a number, or a number with a
+    // K/M/G/T suffix (powers of 1024).
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("unlimited") || input.eq_ignore_ascii_case("infinity") {
+            return Some(LimitValue::Unlimited);
+        }
+        Some(Self::from_option(parse_limit_value(input)?))
+    }
+
+    // `None` is unlimited, as in `ResourceLimit`.
+    pub fn from_option(value: Option<u64>) -> Self {
+        value.map_or(LimitValue::Unlimited, LimitValue::Value)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            LimitValue::Unlimited => "unlimited".to_string(),
+            LimitValue::Value(value) => value.to_string(),
+        }
+    }
+}
+
+// New limits for one resource of a process.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitChange {
+    pub resource: ResourceKind,
+    pub soft: LimitValue,
+    pub hard: Option<LimitValue>, // None keeps the current hard limit
+}
+
+impl LimitChange {
+    // "nofile 65536" or "nofile 65536 1048576", "core unlimited", ...
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut parts = input.split_whitespace();
+        let resource = ResourceKind::parse(parts.next()?)?;
+        let soft = LimitValue::parse(parts.next()?)?;
+        let hard = match parts.next() {
+            Some(hard) => Some(LimitValue::parse(hard)?),
+            None => None,
+        };
+
+        parts.next().is_none().then_some(Self {
+            resource,
+            soft,
+            hard,
+        })
+    }
+
+    // Short human-readable form, e.g. "nofile 65536/unlimited".
+    pub fn label(&self) -> String {
+        match self.hard {
+            Some(hard) => format!(
+                "{} {}/{}",
+                self.resource.name(),
+                self.soft.label(),
+                hard.label()
+            ),
+            None => format!("{} {}", self.resource.name(), self.soft.label()),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use super::{
     SystemInfo, CpuCore, MemoryInfo, DiskInfo, DiskIoStats,
-    NetworkInterface, NetworkStats
+    NetworkInterface, NetworkStats, CgroupStats
 };
 
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
@@ -14,6 +14,7 @@ pub struct SystemMetrics {
     pub disks: Vec<DiskMetrics>,
     pub network: NetworkMetrics,
     pub processes: ProcessMetrics,
+    pub cgroups: Vec<CgroupStats>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
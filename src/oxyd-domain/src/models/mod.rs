@@ -4,6 +4,13 @@ pub mod metrics;
 pub mod config;
 pub mod errors;
 pub mod plugin;
+pub mod audit;
+pub mod details;
+pub mod cgroup;
+pub mod security;
+pub mod limits;
+pub mod supervisor;
+pub mod alert;
 
 pub use system::*;
 pub use process::*;
@@ -11,3 +18,10 @@ pub use metrics::*;
 pub use config::*;
 pub use errors::*;
 pub use plugin::*;
+pub use audit::*;
+pub use details::*;
+pub use cgroup::*;
+pub use security::*;
+pub use limits::*;
+pub use supervisor::*;
+pub use alert::*;
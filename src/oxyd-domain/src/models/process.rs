@@ -1,6 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::cgroup::CgroupLimit;
+use super::limits::LimitChange;
+use super::security::SecurityContext;
+use super::supervisor::ExitInfo;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Process {
     pub pid: u32,
@@ -10,13 +15,23 @@ pub struct Process {
     pub arguments: Vec<String>,
     pub executable_path: Option<String>,
     pub working_dir: Option<String>,
+    // cgroup v2 path, e.g. "/system.slice/sshd.service".
+    pub cgroup: Option<String>,
+    pub container: Option<ContainerInfo>,
     pub state: ProcessState,
     pub user: String,
     pub group: String,
+    pub uids: ProcessIds,
+    pub gids: ProcessIds,
+    pub security: SecurityContext,
     pub priority: i32,
     pub nice: i32,
+    pub scheduling_policy: SchedulingPolicy,
+    pub cpu_affinity: Vec<usize>,
+    pub io_priority: Option<IoPriority>,
     pub threads: u32,
     pub start_time: DateTime<Utc>,
+    pub start_ticks: u64,
     pub cpu_usage_percent: f64,
     pub memory_usage_bytes: u64,
     pub memory_usage_percent: f64,
@@ -25,8 +40,80 @@ pub struct Process {
     pub disk_read_bytes: u64,
     pub open_files: u32,
     pub open_connections: u32,
+    // Only collected with `SnapshotOptions::include_smaps`.
+    pub memory_accounting: Option<MemoryAccounting>,
+    // How likely the OOM killer is to pick this process (0..=1000 plus the
+    // adjustment) and the adjustment itself (-1000 never, 1000 first). Only
+    // collected with `SnapshotOptions::include_oom`.
+    pub oom_score: Option<u32>,
+    pub oom_score_adj: Option<i32>,
+}
+
+// Memory use from /proc/<pid>/smaps_rollup. Unlike RSS, PSS splits shared
+// pages between the processes mapping them, so it adds up across processes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccounting {
+    pub pss_bytes: u64,
+    pub uss_bytes: u64, // private clean + private dirty
+    pub shared_bytes: u64,
+    pub swap_bytes: u64,
+}
+
+// Container a process runs in, detected from its cgroup path or, failing
+// that, from living in other PID and mount namespaces than oxyd.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContainerInfo {
+    pub runtime: ContainerRuntime,
+    pub id: String, // 64 hex digits, the LXC name, or "ns:<pid namespace inode>"
+    pub name: Option<String>, // from the runtime's on-disk state, when readable
 }
 
+impl ContainerInfo {
+    // The 12 character form `docker ps` shows.
+    pub fn short_id(&self) -> &str {
+        if self.id.len() == 64 && self.id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            &self.id[..12]
+        } else {
+            &self.id
+        }
+    }
+
+    // Name when known, short ID otherwise.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| self.short_id())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    Containerd,
+    Crio,
+    Lxc,
+    Unknown,
+}
+
+impl ContainerRuntime {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Containerd => "containerd",
+            ContainerRuntime::Crio => "cri-o",
+            ContainerRuntime::Lxc => "lxc",
+            ContainerRuntime::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessIds {
+    pub real: u32,
+    pub effective: u32,
+    pub saved: u32,
+    pub filesystem: u32,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcessState {
@@ -40,10 +127,28 @@ pub enum ProcessState {
     Unknown,
 }
 
+impl Process {
+    pub fn identity(&self) -> ProcessIdentity {
+        ProcessIdentity {
+            pid: self.pid,
+            start_ticks: self.start_ticks,
+        }
+    }
+}
+
+// A PID can be reused once its process exits; PID plus start time (clock
+// ticks since boot) names exactly one process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    pub start_ticks: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProcessSignal {
     Kill,       // SIGKILL (9)
     Terminate,  // SIGTERM (15)
-    Stop,       // SIGTOP (19)
+    Stop,       // SIGSTOP (19)
     Continue,   // SIGCONT (18)
     Interrupt,  // SIGINT (2)
     Quit,       // SIGQUIT (3)
@@ -51,15 +156,259 @@ pub enum ProcessSignal {
     Custom(i32),
 }
 
+impl ProcessSignal {
+    // Linux signal number.
+    pub fn number(&self) -> i32 {
+        match self {
+            ProcessSignal::Kill => 9,
+            ProcessSignal::Terminate => 15,
+            ProcessSignal::Stop => 19,
+            ProcessSignal::Continue => 18,
+            ProcessSignal::Interrupt => 2,
+            ProcessSignal::Quit => 3,
+            ProcessSignal::Hangup => 1,
+            ProcessSignal::Custom(signal) => *signal,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            ProcessSignal::Kill => "SIGKILL".to_string(),
+            ProcessSignal::Terminate => "SIGTERM".to_string(),
+            ProcessSignal::Stop => "SIGSTOP".to_string(),
+            ProcessSignal::Continue => "SIGCONT".to_string(),
+            ProcessSignal::Interrupt => "SIGINT".to_string(),
+            ProcessSignal::Quit => "SIGQUIT".to_string(),
+            ProcessSignal::Hangup => "SIGHUP".to_string(),
+            ProcessSignal::Custom(signal) => format!("signal {}", signal),
+        }
+    }
+
+    // The action recorded when this signal is delivered.
+    pub fn action(&self) -> ProcessAction {
+        match self {
+            ProcessSignal::Kill => ProcessAction::Kill,
+            ProcessSignal::Terminate => ProcessAction::Terminate,
+            ProcessSignal::Stop => ProcessAction::Suspend,
+            ProcessSignal::Continue => ProcessAction::Resume,
+            other => ProcessAction::Signal(other.number()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcessAction {
     Kill,
     Terminate,
     Suspend,
     Resume,
+    Signal(i32),
     SetPriority(i32),
     SetNice(i32),
+    SetScheduler(SchedulingPolicy, i32),
     SetAffinity(Vec<usize>),
+    SetIoPriority(IoPriority),
+    Stop,
+    MoveToCgroup(String),
+    // Limit written to the cgroup the target process was in.
+    SetCgroupLimit(String, CgroupLimit),
+    SetLimit(LimitChange),
+    SetOomScoreAdj(i32),
+    // Started by oxyd's supervisor; the command line.
+    Spawn(String),
+    Restart,
+}
+
+impl ProcessAction {
+    // Short human-readable form, e.g. "SIGKILL" or "nice 5".
+    pub fn label(&self) -> String {
+        match self {
+            ProcessAction::Kill => "SIGKILL".to_string(),
+            ProcessAction::Terminate => "SIGTERM".to_string(),
+            ProcessAction::Suspend => "SIGSTOP".to_string(),
+            ProcessAction::Resume => "SIGCONT".to_string(),
+            ProcessAction::Signal(signal) => format!("signal {}", signal),
+            ProcessAction::SetPriority(priority) => format!("priority {}", priority),
+            ProcessAction::SetNice(nice) => format!("nice {}", nice),
+            ProcessAction::SetScheduler(policy, priority) => {
+                format!("policy {} {}", policy.name(), priority)
+            }
+            ProcessAction::SetAffinity(cpus) => format!("affinity {}", format_cpu_list(cpus)),
+            ProcessAction::SetIoPriority(io) => format!("I/O {}", io.label()),
+            ProcessAction::Stop => "stop".to_string(),
+            ProcessAction::MoveToCgroup(cgroup) => format!("move to {}", cgroup),
+            ProcessAction::SetCgroupLimit(cgroup, limit) => {
+                format!("{} on {}", limit.label(), cgroup)
+            }
+            ProcessAction::SetLimit(change) => format!("limit {}", change.label()),
+            ProcessAction::SetOomScoreAdj(adj) => format!("oom_score_adj {}", adj),
+            ProcessAction::Spawn(command) => format!("spawn {}", command),
+            ProcessAction::Restart => "restart".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SchedulingPolicy {
+    #[default]
+    Other,      // SCHED_OTHER (0)
+    Fifo,       // SCHED_FIFO (1)
+    RoundRobin, // SCHED_RR (2)
+    Batch,      // SCHED_BATCH (3)
+    Idle,       // SCHED_IDLE (5)
+    Deadline,   // SCHED_DEADLINE (6), read-only here
+}
+
+impl SchedulingPolicy {
+    pub fn from_number(policy: i32) -> Self {
+        match policy {
+            1 => SchedulingPolicy::Fifo,
+            2 => SchedulingPolicy::RoundRobin,
+            3 => SchedulingPolicy::Batch,
+            5 => SchedulingPolicy::Idle,
+            6 => SchedulingPolicy::Deadline,
+            _ => SchedulingPolicy::Other,
+        }
+    }
+
+    pub fn number(&self) -> i32 {
+        match self {
+            SchedulingPolicy::Other => 0,
+            SchedulingPolicy::Fifo => 1,
+            SchedulingPolicy::RoundRobin => 2,
+            SchedulingPolicy::Batch => 3,
+            SchedulingPolicy::Idle => 5,
+            SchedulingPolicy::Deadline => 6,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "other" | "normal" => Some(SchedulingPolicy::Other),
+            "fifo" => Some(SchedulingPolicy::Fifo),
+            "rr" | "roundrobin" => Some(SchedulingPolicy::RoundRobin),
+            "batch" => Some(SchedulingPolicy::Batch),
+            "idle" => Some(SchedulingPolicy::Idle),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchedulingPolicy::Other => "other",
+            SchedulingPolicy::Fifo => "fifo",
+            SchedulingPolicy::RoundRobin => "rr",
+            SchedulingPolicy::Batch => "batch",
+            SchedulingPolicy::Idle => "idle",
+            SchedulingPolicy::Deadline => "deadline",
+        }
+    }
+
+    pub fn is_realtime(&self) -> bool {
+        matches!(self, SchedulingPolicy::Fifo | SchedulingPolicy::RoundRobin)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoClass {
+    None,       // IOPRIO_CLASS_NONE (0), derived from the nice value
+    RealTime,   // IOPRIO_CLASS_RT (1)
+    BestEffort, // IOPRIO_CLASS_BE (2)
+    Idle,       // IOPRIO_CLASS_IDLE (3)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoPriority {
+    pub class: IoClass,
+    pub level: u8, // 0 (highest) ..= 7 (lowest); unused for Idle and None
+}
+
+impl IoPriority {
+    pub fn new(class: IoClass, level: u8) -> Self {
+        Self { class, level }
+    }
+
+    // Parses "rt 0", "be 7", "idle" or "none" (a "/" separator also works).
+    pub fn parse(input: &str) -> Option<Self> {
+        let normalized = input.replace('/', " ");
+        let mut parts = normalized.split_whitespace();
+
+        let class = match parts.next()?.to_lowercase().as_str() {
+            "none" => IoClass::None,
+            "rt" | "realtime" => IoClass::RealTime,
+            "be" | "best-effort" => IoClass::BestEffort,
+            "idle" => IoClass::Idle,
+            _ => return None,
+        };
+
+        let level = match (class, parts.next()) {
+            (IoClass::RealTime | IoClass::BestEffort, Some(level)) => level.parse().ok()?,
+            (IoClass::RealTime | IoClass::BestEffort, None) => 4,
+            (_, _) => 0,
+        };
+
+        if level > 7 {
+            return None;
+        }
+
+        Some(Self { class, level })
+    }
+
+    pub fn label(&self) -> String {
+        match self.class {
+            IoClass::None => "none".to_string(),
+            IoClass::RealTime => format!("rt/{}", self.level),
+            IoClass::BestEffort => format!("be/{}", self.level),
+            IoClass::Idle => "idle".to_string(),
+        }
+    }
+}
+
+// Parses a kernel CPU list such as "0-3,6,8-9".
+pub fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+
+    for part in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start: usize = start.trim().parse().ok()?;
+                let end: usize = end.trim().parse().ok()?;
+                if start > end {
+                    return None;
+                }
+                cpus.extend(start..=end);
+            }
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+
+    cpus.sort_unstable();
+    cpus.dedup();
+    Some(cpus)
+}
+
+// Formats CPUs back into the compact kernel list form.
+pub fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut sorted = cpus.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut ranges: Vec<String> = Vec::new();
+    let mut iter = sorted.into_iter().peekable();
+
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap_or(end);
+        }
+        if start == end {
+            ranges.push(start.to_string());
+        } else {
+            ranges.push(format!("{}-{}", start, end));
+        }
+    }
+
+    ranges.join(",")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,3 +419,96 @@ pub struct ProcessActionResult {
     pub message: Option<String>,
     pub timestamp: DateTime<Utc>,
 }
+
+// How `stop_process` asks a process to exit before resorting to SIGKILL.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StopOptions {
+    pub signal: ProcessSignal,
+    pub grace_period_ms: u64,
+}
+
+impl Default for StopOptions {
+    fn default() -> Self {
+        Self {
+            signal: ProcessSignal::Terminate,
+            grace_period_ms: 5000,
+        }
+    }
+}
+
+// How a watched process ended. The exit status is only known for commands
+// oxyd started itself; any other process is reaped by its own parent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessExit {
+    pub pid: u32,
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub exited_at: DateTime<Utc>,
+    pub status: Option<ExitInfo>,
+}
+
+impl ProcessExit {
+    pub fn runtime_seconds(&self) -> u64 {
+        (self.exited_at - self.started_at).num_seconds().max(0) as u64
+    }
+}
+
+// Which parts of /proc a bulk snapshot reads. Stat and status are always read;
+// everything else costs at least one extra syscall per process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotOptions {
+    pub include_cmdline: bool,
+    pub include_paths: bool,
+    // cgroup path and container membership.
+    #[serde(default)]
+    pub include_cgroup: bool,
+    pub include_fds: bool,
+    pub include_io: bool,
+    pub include_connections: bool,
+    // smaps_rollup walks every mapping of the process; much slower than
+    // the rest.
+    #[serde(default)]
+    pub include_smaps: bool,
+    // oom_score and oom_score_adj.
+    #[serde(default)]
+    pub include_oom: bool,
+    pub max_concurrency: usize,
+}
+
+impl SnapshotOptions {
+    // State, CPU and memory only. Enough for summary counts.
+    pub fn basic() -> Self {
+        Self {
+            include_cmdline: false,
+            include_paths: false,
+            include_cgroup: false,
+            include_fds: false,
+            include_io: false,
+            include_connections: false,
+            include_smaps: false,
+            include_oom: false,
+            max_concurrency: 32,
+        }
+    }
+
+    // Everything `get_process` returns.
+    pub fn full() -> Self {
+        Self {
+            include_cmdline: true,
+            include_paths: true,
+            include_cgroup: true,
+            include_fds: true,
+            include_io: true,
+            include_connections: true,
+            include_smaps: true,
+            include_oom: true,
+            max_concurrency: 32,
+        }
+    }
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self::full()
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+// Capability bit numbers from linux/capability.h, lowercased without the
+// CAP_ prefix.
+pub const CAPABILITY_NAMES: [&str; 41] = [
+    "chown",
+    "dac_override",
+    "dac_read_search",
+    "fowner",
+    "fsetid",
+    "kill",
+    "setgid",
+    "setuid",
+    "setpcap",
+    "linux_immutable",
+    "net_bind_service",
+    "net_broadcast",
+    "net_admin",
+    "net_raw",
+    "ipc_lock",
+    "ipc_owner",
+    "sys_module",
+    "sys_rawio",
+    "sys_chroot",
+    "sys_ptrace",
+    "sys_pacct",
+    "sys_admin",
+    "sys_boot",
+    "sys_nice",
+    "sys_resource",
+    "sys_time",
+    "sys_tty_config",
+    "mknod",
+    "lease",
+    "audit_write",
+    "audit_control",
+    "setfcap",
+    "mac_override",
+    "mac_admin",
+    "syslog",
+    "wake_alarm",
+    "block_suspend",
+    "audit_read",
+    "perfmon",
+    "bpf",
+    "checkpoint_restore",
+];
+
+// Every capability this build knows about.
+const ALL_CAPABILITIES: u64 = (1 << CAPABILITY_NAMES.len()) - 1;
+
+// What a process is allowed to do, from /proc/<pid>/status. Capability sets
+// are bit masks indexed by `CAPABILITY_NAMES`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityContext {
+    pub cap_effective: u64,
+    pub cap_permitted: u64,
+    pub cap_inheritable: u64,
+    pub cap_bounding: u64,
+    pub cap_ambient: u64,
+    pub no_new_privs: bool,
+    pub seccomp: SeccompMode,
+    pub seccomp_filters: u32,
+}
+
+impl SecurityContext {
+    pub fn has_capability(&self, capability: usize) -> bool {
+        capability < 64 && self.cap_effective & (1 << capability) != 0
+    }
+
+    // Any effective capability at all; usually root, or a binary with file
+    // capabilities.
+    pub fn is_elevated(&self) -> bool {
+        self.cap_effective != 0
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SeccompMode {
+    #[default]
+    Disabled,
+    Strict,
+    Filter,
+}
+
+impl SeccompMode {
+    // The number in the Seccomp: line of /proc/<pid>/status.
+    pub fn from_number(mode: u32) -> Self {
+        match mode {
+            1 => SeccompMode::Strict,
+            2 => SeccompMode::Filter,
+            _ => SeccompMode::Disabled,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeccompMode::Disabled => "disabled",
+            SeccompMode::Strict => "strict",
+            SeccompMode::Filter => "filter",
+        }
+    }
+}
+
+// Bit number of a capability name, with or without the "cap_" prefix.
+pub fn capability_from_name(name: &str) -> Option<usize> {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_prefix("cap_").unwrap_or(&name);
+    CAPABILITY_NAMES.iter().position(|&known| known == name)
+}
+
+// "all", "none", the names in the mask ("net_admin,net_raw") or, for a
+// mostly full mask, the missing ones ("all except sys_module"). Bits newer
+// than this build are shown by number.
+pub fn format_capabilities(mask: u64) -> String {
+    if mask == 0 {
+        return "none".to_string();
+    }
+    if mask & ALL_CAPABILITIES == ALL_CAPABILITIES {
+        return "all".to_string();
+    }
+
+    let names = |mask: u64| {
+        (0..64)
+            .filter(|bit| mask & (1 << bit) != 0)
+            .map(|bit| match CAPABILITY_NAMES.get(bit) {
+                Some(name) => name.to_string(),
+                None => format!("cap_{}", bit),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    let missing = !mask & ALL_CAPABILITIES;
+    if missing.count_ones() * 2 < CAPABILITY_NAMES.len() as u32 {
+        format!("all except {}", names(missing))
+    } else {
+        names(mask)
+    }
+}
@@ -0,0 +1,256 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// What happens when a managed command exits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    OnFailure, // non-zero exit or killed by a signal
+    Always,
+}
+
+impl RestartPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RestartPolicy::Never => "never",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::Always => "always",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "never" | "no" => Some(RestartPolicy::Never),
+            "on-failure" | "on_failure" | "failure" => Some(RestartPolicy::OnFailure),
+            "always" => Some(RestartPolicy::Always),
+            _ => None,
+        }
+    }
+
+    // Whether a command that exited with `exit` is started again.
+    pub fn restarts_after(&self, exit: Option<&ExitInfo>) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => !exit.is_some_and(|exit| exit.success()),
+            RestartPolicy::Always => true,
+        }
+    }
+}
+
+// A command for oxyd to start and, depending on `restart`, keep running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnSpec {
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>, // on top of oxyd's own environment
+    pub cwd: Option<String>,
+    pub nice: Option<i32>,
+    pub cgroup: Option<String>, // existing cgroup v2 path
+    pub restart: RestartPolicy,
+}
+
+impl SpawnSpec {
+    pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            command: command.into(),
+            args,
+            env: Vec::new(),
+            cwd: None,
+            nice: None,
+            cgroup: None,
+            restart: RestartPolicy::Never,
+        }
+    }
+
+    // Shell-like command line: options, then NAME=value environment
+    // variables, then the command and its arguments. Quotes group words.
+    //
+    //   restart=on-failure nice=10 cwd=/srv/app RUST_LOG=debug ./server --port 8080
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let words = split_words(input)?;
+        let mut spec = Self::new(String::new(), Vec::new());
+
+        let mut rest = words.into_iter().peekable();
+        while let Some(word) = rest.next_if(|word| is_assignment(word)) {
+            let (key, value) = word.split_once('=').unwrap_or_default();
+            match key {
+                "restart" => {
+                    spec.restart = RestartPolicy::parse(value).ok_or_else(|| {
+                        format!(
+                            "restart must be never, on-failure or always, not '{}'",
+                            value
+                        )
+                    })?;
+                }
+                "nice" => {
+                    spec.nice = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|nice| (-20..=19).contains(nice))
+                            .ok_or_else(|| format!("nice must be -20..19, not '{}'", value))?,
+                    );
+                }
+                "cwd" => spec.cwd = Some(value.to_string()),
+                "cgroup" => spec.cgroup = Some(value.to_string()),
+                name => spec.env.push((name.to_string(), value.to_string())),
+            }
+        }
+
+        spec.command = rest.next().ok_or("no command given")?;
+        spec.args = rest.collect();
+        Ok(spec)
+    }
+
+    // The command and its arguments, quoted where needed.
+    pub fn command_line(&self) -> String {
+        std::iter::once(&self.command)
+            .chain(&self.args)
+            .map(|word| {
+                if word.is_empty() || word.contains(char::is_whitespace) {
+                    format!("'{}'", word)
+                } else {
+                    word.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// Words before the command that set an option or an environment variable.
+const SPAWN_OPTIONS: [&str; 4] = ["restart", "nice", "cwd", "cgroup"];
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=')
+        .is_some_and(|(key, _)| SPAWN_OPTIONS.contains(&key) || is_env_name(key))
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Splits on whitespace, keeping 'single' and "double" quoted parts
+// together. No escapes or expansion.
+fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("unterminated quote".to_string());
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ManagedState {
+    Running,
+    Starting,   // being started again from oxyd
+    BackingOff, // waiting to restart
+    Exited,     // ended and the restart policy says to leave it
+    Stopped,    // stopped from oxyd
+    Failed,     // could not be started again
+}
+
+impl ManagedState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ManagedState::Running => "running",
+            ManagedState::Starting => "starting",
+            ManagedState::BackingOff => "backoff",
+            ManagedState::Exited => "exited",
+            ManagedState::Stopped => "stopped",
+            ManagedState::Failed => "failed",
+        }
+    }
+
+    // Whether oxyd still has a monitor on it, i.e. it may run again on its
+    // own.
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            ManagedState::Running | ManagedState::Starting | ManagedState::BackingOff
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitInfo {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub at: DateTime<Utc>,
+}
+
+impl ExitInfo {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    // "exit 0", "exit 1" or "signal 9".
+    pub fn label(&self) -> String {
+        match (self.code, self.signal) {
+            (Some(code), _) => format!("exit {}", code),
+            (None, Some(signal)) => format!("signal {}", signal),
+            (None, None) => "exited".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub text: String,
+}
+
+// A command started through `ProcessManager::spawn`, as last seen by its
+// supervisor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedProcess {
+    pub id: u64,
+    pub spec: SpawnSpec,
+    pub state: ManagedState,
+    pub pid: Option<u32>, // while running
+    pub started_at: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    pub next_restart: Option<DateTime<Utc>>, // while backing off
+    pub last_exit: Option<ExitInfo>,
+    pub error: Option<String>,   // why the last start failed
+    pub output: Vec<OutputLine>, // newest last, a bounded tail
+}
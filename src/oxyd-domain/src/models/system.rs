@@ -58,6 +58,10 @@ pub struct MemoryInfo {
     pub swap_free_bytes: u64,
     pub usage_percent: f32,
     pub swap_usage_percent: f32,
+    // Processes killed by the OOM killer since boot (oom_kill in
+    // /proc/vmstat); None on kernels before 4.13.
+    #[serde(default)]
+    pub oom_kills: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2,7 +2,10 @@ use async_trait::async_trait;
 use std::result::Result;
 use crate::models::{SystemMetrics};
 use crate::errors::{CollectorError, ProcessError};
-use crate::{PluginError, Process, ProcessActionResult, ProcessSignal};
+use crate::{
+    AuditRecord, CgroupLimit, IoPriority, LimitChange, ManagedProcess, ResourceLimit, SpawnSpec, PluginError, Process, ProcessAction, ProcessActionResult, ProcessDetails, ProcessExit, ProcessIdentity, ProcessSignal,
+    SchedulingPolicy, SnapshotOptions, StopOptions, ThreadInfo,
+};
 
 #[async_trait]
 pub trait Collector: Send + Sync {
@@ -29,15 +32,133 @@ pub trait ProcessManager: Send + Sync {
     // Get detailed information about a specific process.
     async fn get_process(&self, pid: u32) -> Result<Process, ProcessError>;
 
+    // Everything the detail view shows about one process: environment,
+    // limits, cgroups, namespaces, open files, memory maps and threads.
+    async fn get_process_details(&self, pid: u32) -> Result<ProcessDetails, ProcessError>;
+
+    // Threads of a process from /proc/<pid>/task. CPU usage is measured
+    // since the previous call for the same process, so the first call
+    // reports 0.
+    async fn list_threads(&self, pid: u32) -> Result<Vec<ThreadInfo>, ProcessError>;
+
+    // Capture every running process in one pass. `options` selects how much
+    // of /proc is read per process; the default falls back to `get_process`.
+    async fn snapshot_all(&self, options: &SnapshotOptions) -> Result<Vec<Process>, ProcessError> {
+        let _ = options;
+        let mut processes = Vec::new();
+        for pid in self.list_processes().await? {
+            if let Ok(process) = self.get_process(pid).await {
+                processes.push(process);
+            }
+        }
+        Ok(processes)
+    }
+
     // Send a signal to kill a process (SIGKILL).
     async fn kill_process(&self, pid: u32) -> Result<Process, ProcessError>;
 
     // Send a signal to a process.
     async fn send_signal(&self, pid:u32, signal: ProcessSignal) -> Result<ProcessActionResult, ProcessError>;
 
-    // Set process priority.
+    // Send a signal only if `target` is still the same process (PID and start
+    // time); a reused PID gets `ProcessError::IdentityMismatch`.
+    async fn send_signal_to(&self, target: &ProcessIdentity, signal: ProcessSignal) -> Result<ProcessActionResult, ProcessError>;
+
+    // Fails with `ProcessError::IdentityMismatch` if the PID now belongs to a
+    // different process.
+    async fn verify_identity(&self, target: &ProcessIdentity) -> Result<(), ProcessError>;
+
+    // Send `options.signal` to `target`, wait up to the grace period for it
+    // to exit, then escalate to SIGKILL. Each step is reported through
+    // `progress`; the returned result describes how the process ended.
+    async fn stop_process(
+        &self,
+        target: &ProcessIdentity,
+        options: &StopOptions,
+        progress: &(dyn Fn(ProcessActionResult) + Send + Sync),
+    ) -> Result<ProcessActionResult, ProcessError>;
+
+    // Wait for `target` to exit, however long that takes. Fails straight
+    // away if it is already gone or its PID has been reused.
+    async fn watch_process(&self, target: &ProcessIdentity) -> Result<ProcessExit, ProcessError>;
+
+    // Set process priority (renice).
     async fn send_priority(&self, pid: u32, priority: i32) -> Result<ProcessActionResult, ProcessError>;
 
+    // Set the nice value of a process (-20..=19).
+    async fn set_nice(&self, pid: u32, nice: i32) -> Result<ProcessActionResult, ProcessError>;
+
+    // Change scheduling policy; `priority` is the real-time priority for
+    // FIFO/RR and must be 0 for the other policies.
+    async fn set_scheduler(&self, pid: u32, policy: SchedulingPolicy, priority: i32) -> Result<ProcessActionResult, ProcessError>;
+
+    // Pin a process to the given CPUs.
+    async fn set_affinity(&self, pid: u32, cpus: &[usize]) -> Result<ProcessActionResult, ProcessError>;
+
+    // CPUs a process is allowed to run on.
+    async fn get_affinity(&self, pid: u32) -> Result<Vec<usize>, ProcessError>;
+
+    // I/O scheduling class and level (ioprio_get).
+    async fn get_io_priority(&self, pid: u32) -> Result<IoPriority, ProcessError>;
+
+    // Change I/O scheduling class and level (ioprio_set).
+    async fn set_io_priority(&self, pid: u32, priority: IoPriority) -> Result<ProcessActionResult, ProcessError>;
+
+    // Move a process into the cgroup v2 directory `cgroup` (relative to the
+    // cgroup root), creating it if needed.
+    async fn move_to_cgroup(&self, pid: u32, cgroup: &str) -> Result<ProcessActionResult, ProcessError>;
+
+    // Write `limit` to the cgroup `pid` is in. The limit applies to every
+    // process in that cgroup, so each of them must pass the safety policy.
+    async fn set_cgroup_limit(&self, pid: u32, limit: &CgroupLimit) -> Result<ProcessActionResult, ProcessError>;
+
+    // Start `spec` under oxyd's supervisor, which keeps a tail of its output
+    // and restarts it according to `spec.restart`. Fails if the first start
+    // does.
+    async fn spawn(&self, spec: SpawnSpec) -> Result<ManagedProcess, ProcessError>;
+
+    // Everything started with `spawn` and not yet removed, oldest first.
+    async fn managed_processes(&self) -> Result<Vec<ManagedProcess>, ProcessError>;
+
+    // Stop a managed command for good: no restart follows.
+    async fn stop_managed(&self, id: u64) -> Result<ProcessActionResult, ProcessError>;
+
+    // Restart a managed command now, running or not, skipping any backoff.
+    async fn restart_managed(&self, id: u64) -> Result<ProcessActionResult, ProcessError>;
+
+    // Forget a managed command that is no longer running.
+    async fn remove_managed(&self, id: u64) -> Result<(), ProcessError>;
+
+    // Write /proc/<pid>/oom_score_adj (-1000..=1000). Lowering it below the
+    // current value needs CAP_SYS_RESOURCE.
+    async fn set_oom_score_adj(&self, pid: u32, adj: i32) -> Result<ProcessActionResult, ProcessError>;
+
+    // Soft and hard resource limits, from /proc/<pid>/limits.
+    async fn get_limits(&self, pid: u32) -> Result<Vec<ResourceLimit>, ProcessError>;
+
+    // Change one resource limit of a running process (prlimit). Raising a
+    // hard limit needs CAP_SYS_RESOURCE.
+    async fn set_limit(&self, pid: u32, change: &LimitChange) -> Result<ProcessActionResult, ProcessError>;
+
+    // The newest `limit` entries of the action audit log, oldest first.
+    async fn recent_actions(&self, limit: usize) -> Result<Vec<AuditRecord>, ProcessError> {
+        let _ = limit;
+        Ok(Vec::new())
+    }
+
+    // Why the audit log is not being written, while its last write failed.
+    async fn audit_failure(&self) -> Option<String> {
+        None
+    }
+
+    // Record in the audit log what `action` on `pid` would have done, marked
+    // as a dry run. The cgroup of a `SetCgroupLimit` may be left empty; it
+    // is filled in from `pid`.
+    async fn record_dry_run(&self, pid: u32, action: ProcessAction) -> Result<(), ProcessError> {
+        let _ = (pid, action);
+        Ok(())
+    }
+
     // Suspend a process (SIGSTOP).
     async fn suspend_process(&self, pid: u32) -> Result<ProcessActionResult, ProcessError>;
 
@@ -1,3 +1,5 @@
 pub mod linux;
+pub mod policy;
 
-pub use linux::LinuxProcessManager;
+pub use linux::{AuditLog, LinuxProcessManager, ensure_private_dir};
+pub use policy::{ActionKind, ActionPolicy, ProtectedRule};
@@ -0,0 +1,343 @@
+use chrono::Utc;
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{AuditRecord, ProcessAction, ProcessActionResult},
+};
+use std::collections::VecDeque;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const DEFAULT_FILE_NAME: &str = "audit.jsonl";
+const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+
+// Append-only JSON lines log of process actions. When the active file grows
+// past `max_bytes` it is renamed to `audit.jsonl.1` (older files shift up)
+// and at most `max_files` rotated files are kept. The directory has to be
+// private to us (see `ensure_private_dir`) and links are never followed, so
+// nobody else can redirect the writes or the rotation.
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    lock: Mutex<()>,
+    // Records handed to `submit` and not written yet, oldest first.
+    pending: Mutex<VecDeque<AuditRecord>>,
+    // Why the last write failed; cleared by the next one that succeeds.
+    failure: Mutex<Option<String>>,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+            lock: Mutex::new(()),
+            pending: Mutex::new(VecDeque::new()),
+            failure: Mutex::new(None),
+        }
+    }
+
+    // `<data_dir>/audit.jsonl`
+    pub fn in_dir(data_dir: impl AsRef<Path>) -> Self {
+        Self::new(data_dir.as_ref().join(DEFAULT_FILE_NAME))
+    }
+
+    pub fn with_rotation(mut self, max_bytes: u64, max_files: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self.max_files = max_files;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, record: &AuditRecord) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.write_tracked(record)
+    }
+
+    // Why records are not being written, as long as the most recent write
+    // failed.
+    pub fn failure(&self) -> Option<String> {
+        self.failure
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    // Queues `record` and writes it on the blocking pool, keeping the file
+    // I/O off the async runtime. Records are written in the order they were
+    // submitted; a write error is kept for `failure` instead of returned.
+    pub fn submit(self: &Arc<Self>, record: AuditRecord) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(record);
+
+        let log = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            let _guard = log.lock.lock().unwrap_or_else(|e| e.into_inner());
+            loop {
+                let next = log
+                    .pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .pop_front();
+                let Some(record) = next else {
+                    break;
+                };
+                let _ = log.write_tracked(&record);
+            }
+        });
+    }
+
+    // Callers hold `lock`.
+    fn write_tracked(&self, record: &AuditRecord) -> io::Result<()> {
+        let result = self.write(record);
+        *self.failure.lock().unwrap_or_else(|e| e.into_inner()) = result
+            .as_ref()
+            .err()
+            .map(|e| format!("{}: {}", self.path.display(), e));
+        result
+    }
+
+    // Callers hold `lock`.
+    fn write(&self, record: &AuditRecord) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            ensure_private_dir(parent)?;
+        }
+        if regular_file(&self.path)?.is_some_and(|m| m.len() >= self.max_bytes) {
+            self.rotate()?;
+        }
+
+        let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .custom_flags(libc::O_NOFOLLOW)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+
+    // The newest `limit` records across the active and rotated files, oldest
+    // first. Lines that fail to parse are skipped.
+    pub fn recent(&self, limit: usize) -> io::Result<Vec<AuditRecord>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut newest_first = Vec::new();
+        for index in 0..=self.max_files {
+            let content = match fs::read_to_string(self.rotated_path(index)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            for line in content.lines().rev() {
+                if let Ok(record) = serde_json::from_str::<AuditRecord>(line) {
+                    newest_first.push(record);
+                    if newest_first.len() >= limit {
+                        newest_first.reverse();
+                        return Ok(newest_first);
+                    }
+                }
+            }
+        }
+
+        newest_first.reverse();
+        Ok(newest_first)
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if regular_file(&from)?.is_some() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)
+        } else {
+            fs::rename(&self.path, self.rotated_path(1))
+        }
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            return self.path.clone();
+        }
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+}
+
+// Creates `dir` (mode 0700) if it is missing. An existing directory has to
+// be a real directory owned by the effective user with no group or other
+// access: anyone else able to write there could swap our files for links
+// between the checks and the writes.
+pub fn ensure_private_dir(dir: &Path) -> io::Result<()> {
+    DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() {
+        return Err(io::Error::other(format!(
+            "{} is not a directory",
+            dir.display()
+        )));
+    }
+    let euid = unsafe { libc::geteuid() };
+    if metadata.uid() != euid {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is owned by UID {}, not {}",
+                dir.display(),
+                metadata.uid(),
+                euid
+            ),
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} has mode {:o}, expected 700",
+                dir.display(),
+                metadata.mode() & 0o777
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// The metadata of `path` if it exists; an error if it is anything but a
+// regular file.
+fn regular_file(path: &Path) -> io::Result<Option<fs::Metadata>> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_file() => Ok(Some(metadata)),
+        Ok(_) => Err(io::Error::other(format!(
+            "{} is not a regular file",
+            path.display()
+        ))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// Who and what an action was aimed at, captured before it runs: a killed
+// process can no longer be read afterwards.
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    pub actor: String,
+    pub actor_uid: u32,
+    pub pid: u32,
+    pub name: String,
+    pub command: String,
+}
+
+impl AuditContext {
+    pub fn record(
+        &self,
+        action: ProcessAction,
+        result: Result<&ProcessActionResult, &ProcessError>,
+    ) -> AuditRecord {
+        let (success, message, error) = match result {
+            Ok(result) => (result.success, result.message.clone(), None),
+            Err(e) => (false, None, Some(e.to_string())),
+        };
+
+        AuditRecord {
+            timestamp: Utc::now(),
+            actor: self.actor.clone(),
+            actor_uid: self.actor_uid,
+            pid: self.pid,
+            name: self.name.clone(),
+            command: self.command.clone(),
+            action,
+            success,
+            message,
+            error,
+            dry_run: false,
+        }
+    }
+}
+
+// "alice", or "root (sudo: alice)" when oxyd was started through sudo.
+pub fn describe_actor(user_name: String) -> String {
+    match std::env::var("SUDO_USER") {
+        Ok(sudo_user) if !sudo_user.is_empty() && sudo_user != user_name => {
+            format!("{} (sudo: {})", user_name, sudo_user)
+        }
+        _ => user_name,
+    }
+}
+
+// Best-effort name and command line of `pid`; empty if it is already gone.
+pub fn read_target(pid: u32) -> (String, String) {
+    let name = fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let command = fs::read(format!("/proc/{}/cmdline", pid))
+        .map(|data| {
+            data.split(|&b| b == 0)
+                .filter(|s| !s.is_empty())
+                .map(|s| String::from_utf8_lossy(s).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+
+    (name, command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn record() -> AuditRecord {
+        let context = AuditContext {
+            actor: "test".to_string(),
+            actor_uid: 0,
+            pid: 1,
+            name: "init".to_string(),
+            command: String::new(),
+        };
+        context.record(ProcessAction::Kill, Err(&ProcessError::NotFound(1)))
+    }
+
+    #[test]
+    fn failure_is_kept_until_a_write_succeeds() {
+        let dir =
+            TempDir(std::env::temp_dir().join(format!("oxyd-audit-test-{}", std::process::id())));
+        fs::create_dir_all(&dir.0).unwrap();
+        fs::set_permissions(&dir.0, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let log = AuditLog::in_dir(&dir.0);
+        assert!(log.append(&record()).is_err());
+        let failure = log.failure().expect("the failed write is reported");
+        assert!(failure.contains("expected 700"), "{}", failure);
+
+        fs::set_permissions(&dir.0, fs::Permissions::from_mode(0o700)).unwrap();
+        log.append(&record()).unwrap();
+        assert_eq!(log.failure(), None);
+        assert_eq!(log.recent(10).unwrap().len(), 1);
+    }
+}
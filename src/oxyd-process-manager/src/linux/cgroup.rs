@@ -0,0 +1,181 @@
+use oxyd_domain::{errors::ProcessError, models::CgroupLimit};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+
+use super::signals::map_errno;
+
+// Hybrid (v1 + v2) hosts mount the unified hierarchy at the second path.
+const CGROUP_ROOTS: [&str; 2] = ["/sys/fs/cgroup", "/sys/fs/cgroup/unified"];
+
+pub fn cgroup_root(pid: u32, action: &str) -> Result<PathBuf, ProcessError> {
+    CGROUP_ROOTS
+        .iter()
+        .map(PathBuf::from)
+        .find(|root| root.join("cgroup.controllers").exists())
+        .ok_or_else(|| {
+            ProcessError::ActionFailed(
+                action.to_string(),
+                pid,
+                "no cgroup v2 hierarchy is mounted".to_string(),
+            )
+        })
+}
+
+// "/a/b" form of a user supplied cgroup path. Anything that could escape
+// the cgroup root ("..", "/a/./b") is rejected.
+pub fn normalize(cgroup: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in Path::new(cgroup).components() {
+        match component {
+            Component::RootDir => {}
+            Component::Normal(part) => parts.push(part.to_str()?),
+            _ => return None,
+        }
+    }
+    Some(format!("/{}", parts.join("/")))
+}
+
+fn directory(root: &Path, cgroup: &str) -> PathBuf {
+    root.join(cgroup.trim_start_matches('/'))
+}
+
+// PIDs in `cgroup` and all of its descendants. Cgroups removed while we
+// walk are skipped.
+pub async fn members(root: &Path, cgroup: &str) -> Vec<u32> {
+    let mut pids = Vec::new();
+    let mut pending = vec![directory(root, cgroup)];
+
+    while let Some(dir) = pending.pop() {
+        if let Ok(content) = fs::read_to_string(dir.join("cgroup.procs")).await {
+            pids.extend(
+                content
+                    .lines()
+                    .filter_map(|line| line.trim().parse::<u32>().ok()),
+            );
+        }
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                pending.push(entry.path());
+            }
+        }
+    }
+
+    pids
+}
+
+// The directory of `cgroup`, which has to exist already: a mistyped path
+// must not quietly become a new cgroup.
+async fn existing(
+    root: &Path,
+    pid: u32,
+    action: &str,
+    cgroup: &str,
+) -> Result<PathBuf, ProcessError> {
+    let dir = directory(root, cgroup);
+    match fs::metadata(&dir).await {
+        Ok(metadata) if metadata.is_dir() => Ok(dir),
+        Ok(_) => Err(ProcessError::ActionFailed(
+            action.to_string(),
+            pid,
+            format!("{} is not a cgroup", cgroup),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(ProcessError::ActionFailed(
+            action.to_string(),
+            pid,
+            format!("cgroup {} does not exist", cgroup),
+        )),
+        Err(e) => Err(cgroup_error(e, pid, action, cgroup)),
+    }
+}
+
+pub async fn move_process(root: &Path, pid: u32, cgroup: &str) -> Result<(), ProcessError> {
+    let dir = existing(root, pid, "move", cgroup).await?;
+    fs::write(dir.join("cgroup.procs"), pid.to_string())
+        .await
+        .map_err(|e| cgroup_error(e, pid, "move", cgroup))
+}
+
+// Opens the cgroup.procs of an existing `cgroup`, for a child to write
+// "0" to between fork and exec so it starts inside the cgroup.
+pub async fn open_procs(root: &Path, cgroup: &str) -> Result<std::fs::File, ProcessError> {
+    let dir = existing(root, 0, "spawn into", cgroup).await?;
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .open(dir.join("cgroup.procs"))
+        .await
+        .map_err(|e| cgroup_error(e, 0, "spawn into", cgroup))?;
+    Ok(file.into_std().await)
+}
+
+pub async fn write_limit(
+    root: &Path,
+    pid: u32,
+    cgroup: &str,
+    limit: &CgroupLimit,
+) -> Result<(), ProcessError> {
+    let file = directory(root, cgroup).join(limit.file());
+    if !file.exists() {
+        // The controller has to be enabled in the parent's
+        // cgroup.subtree_control for its files to show up.
+        return Err(ProcessError::ActionFailed(
+            "limit".to_string(),
+            pid,
+            format!(
+                "{} has no {} (controller not enabled for it)",
+                cgroup,
+                limit.file()
+            ),
+        ));
+    }
+
+    fs::write(&file, limit.value())
+        .await
+        .map_err(|e| cgroup_error(e, pid, "limit", cgroup))
+}
+
+fn cgroup_error(err: io::Error, pid: u32, action: &str, cgroup: &str) -> ProcessError {
+    let reason = match err.raw_os_error() {
+        // A cgroup with controllers enabled for its children cannot hold
+        // processes itself.
+        Some(libc::EBUSY) => format!("{} only accepts processes in its child cgroups", cgroup),
+        Some(libc::EINVAL) => format!("{} rejected the value", cgroup),
+        Some(libc::EOPNOTSUPP) => format!("{} is a threaded cgroup", cgroup),
+        _ => return map_errno(err, pid, action),
+    };
+    ProcessError::ActionFailed(action.to_string(), pid, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_cgroups_are_not_created() {
+        let root =
+            TempDir(std::env::temp_dir().join(format!("oxyd-cgroup-test-{}", std::process::id())));
+        std::fs::create_dir_all(root.0.join("oxyd/throttled")).unwrap();
+
+        let err = move_process(&root.0, 1, "/oxyd/throtled")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{}", err);
+        assert!(open_procs(&root.0, "/oxyd/throtled").await.is_err());
+        assert!(!root.0.join("oxyd/throtled").exists());
+
+        move_process(&root.0, 1, "/oxyd/throttled").await.unwrap();
+        let procs = std::fs::read_to_string(root.0.join("oxyd/throttled/cgroup.procs")).unwrap();
+        assert_eq!(procs, "1");
+    }
+}
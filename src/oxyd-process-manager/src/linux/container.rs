@@ -0,0 +1,315 @@
+use oxyd_domain::models::{ContainerInfo, ContainerRuntime};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use super::parsers::parse_namespace_inode;
+use super::users::UserResolver;
+
+const DOCKER_ROOT: &str = "/var/lib/docker/containers";
+const PODMAN_ROOT: &str = "/var/lib/containers/storage";
+// Relative to the owner's home for rootless podman.
+const PODMAN_ROOTLESS_STORAGE: &str = ".local/share/containers/storage";
+const PODMAN_CONTAINERS_FILE: &str = "overlay-containers/containers.json";
+
+// A container whose state was not readable yet (still being created, or
+// the store was unavailable) is looked up again after this long.
+const MISSING_NAME_TTL: Duration = Duration::from_secs(30);
+
+// Labels processes with the container they run in. Names come from the
+// runtimes' state on disk, never from a daemon, and are cached per ID.
+pub struct ContainerResolver {
+    // oxyd's own (pid, mnt) namespace inodes, the reference for "the host".
+    own_namespaces: Option<(u64, u64)>,
+    // Missing names are kept with the time of the lookup.
+    names: Mutex<HashMap<String, (Option<String>, Instant)>>,
+    // Finds the home, and so the store, of rootless podman containers.
+    users: Arc<UserResolver>,
+}
+
+impl ContainerResolver {
+    pub fn new(users: Arc<UserResolver>) -> Self {
+        let own = |kind: &str| {
+            std::fs::read_link(format!("/proc/self/ns/{}", kind))
+                .ok()
+                .and_then(|link| parse_namespace_inode(&link.to_string_lossy()))
+        };
+        Self {
+            own_namespaces: own("pid").zip(own("mnt")),
+            names: Mutex::new(HashMap::new()),
+            users,
+        }
+    }
+
+    pub async fn resolve(&self, pid: u32, cgroup: Option<&str>, uid: u32) -> Option<ContainerInfo> {
+        let (runtime, id) = match cgroup.and_then(container_from_cgroup) {
+            Some(found) => found,
+            None => (
+                ContainerRuntime::Unknown,
+                self.namespace_sandbox(pid).await?,
+            ),
+        };
+
+        let name = match runtime {
+            ContainerRuntime::Lxc => Some(id.clone()),
+            ContainerRuntime::Docker | ContainerRuntime::Podman => {
+                self.lookup_name(runtime, &id, uid).await
+            }
+            _ => None,
+        };
+
+        Some(ContainerInfo { runtime, id, name })
+    }
+
+    // Services often get a private mount namespace (PrivateTmp=) and some
+    // sandboxes a private PID namespace, so only a process with both
+    // differing from ours counts as containerised. Needs ptrace access to
+    // the target, so mostly root only.
+    async fn namespace_sandbox(&self, pid: u32) -> Option<String> {
+        let (own_pid, own_mnt) = self.own_namespaces?;
+        let read = |kind: &'static str| async move {
+            fs::read_link(format!("/proc/{}/ns/{}", pid, kind))
+                .await
+                .ok()
+                .and_then(|link| parse_namespace_inode(&link.to_string_lossy()))
+        };
+
+        let pid_ns = read("pid").await?;
+        let mnt_ns = read("mnt").await?;
+        (pid_ns != own_pid && mnt_ns != own_mnt).then(|| format!("ns:{}", pid_ns))
+    }
+
+    // The lock is not held while the state files are read, so one slow
+    // lookup does not stall the rest of a scan.
+    async fn lookup_name(&self, runtime: ContainerRuntime, id: &str, uid: u32) -> Option<String> {
+        if let Some((name, looked_up)) = self.names.lock().await.get(id)
+            && (name.is_some() || looked_up.elapsed() < MISSING_NAME_TTL)
+        {
+            return name.clone();
+        }
+
+        let name = match runtime {
+            ContainerRuntime::Docker => docker_name(id).await,
+            ContainerRuntime::Podman => {
+                let home = match uid {
+                    0 => None,
+                    _ => self.users.home_dir(uid).await,
+                };
+                podman_name(id, home).await
+            }
+            _ => None,
+        };
+        self.names
+            .lock()
+            .await
+            .insert(id.to_string(), (name.clone(), Instant::now()));
+        name
+    }
+}
+
+// Recognises the cgroup layouts of the common runtimes, both with the
+// systemd driver ("docker-<id>.scope") and the cgroupfs one ("/docker/<id>").
+pub fn container_from_cgroup(path: &str) -> Option<(ContainerRuntime, String)> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    for (index, segment) in segments.iter().enumerate().rev() {
+        let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+        let parent = index.checked_sub(1).map(|i| segments[i]);
+
+        // conmon monitors a podman/cri-o container from outside of it.
+        if segment.starts_with("libpod-conmon-") || segment.starts_with("crio-conmon-") {
+            return None;
+        }
+
+        let prefixed = [
+            ("docker-", ContainerRuntime::Docker),
+            ("libpod-", ContainerRuntime::Podman),
+            ("cri-containerd-", ContainerRuntime::Containerd),
+            ("crio-", ContainerRuntime::Crio),
+        ];
+        for (prefix, runtime) in prefixed {
+            if let Some(id) = segment
+                .strip_prefix(prefix)
+                .filter(|id| is_container_id(id))
+            {
+                return Some((runtime, id.to_string()));
+            }
+        }
+
+        if let Some(name) = segment.strip_prefix("lxc.payload.") {
+            return Some((ContainerRuntime::Lxc, name.to_string()));
+        }
+        if parent == Some("lxc") {
+            return Some((ContainerRuntime::Lxc, segment.to_string()));
+        }
+
+        if is_container_id(segment) {
+            let runtime = match parent {
+                Some("docker") => ContainerRuntime::Docker,
+                Some("libpod_parent") => ContainerRuntime::Podman,
+                _ if path.contains("kubepods") => ContainerRuntime::Containerd,
+                _ => ContainerRuntime::Unknown,
+            };
+            return Some((runtime, segment.to_string()));
+        }
+    }
+
+    None
+}
+
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[derive(Deserialize)]
+struct DockerConfig {
+    #[serde(rename = "Name")]
+    name: String,
+}
+
+async fn docker_name(id: &str) -> Option<String> {
+    let path = format!("{}/{}/config.v2.json", DOCKER_ROOT, id);
+    let content = fs::read_to_string(path).await.ok()?;
+    let config: DockerConfig = serde_json::from_str(&content).ok()?;
+    Some(config.name.trim_start_matches('/').to_string())
+}
+
+#[derive(Deserialize)]
+struct PodmanContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+// Rootful containers live under /var/lib/containers, rootless ones under
+// the owner's `home`.
+async fn podman_name(id: &str, home: Option<PathBuf>) -> Option<String> {
+    let mut stores = vec![PathBuf::from(PODMAN_ROOT)];
+    stores.extend(home.map(|home| home.join(PODMAN_ROOTLESS_STORAGE)));
+
+    for store in stores {
+        let Ok(content) = fs::read_to_string(store.join(PODMAN_CONTAINERS_FILE)).await else {
+            continue;
+        };
+        let Ok(containers) = serde_json::from_str::<Vec<PodmanContainer>>(&content) else {
+            continue;
+        };
+        if let Some(container) = containers.into_iter().find(|c| c.id == id) {
+            return container.names.into_iter().next();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f1c0a3b9d2e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a";
+
+    #[test]
+    fn container_from_cgroup_recognises_runtime_layouts() {
+        let cases = [
+            // docker, systemd and cgroupfs drivers
+            (
+                format!("/system.slice/docker-{ID}.scope"),
+                ContainerRuntime::Docker,
+            ),
+            (
+                format!("/system.slice/docker-{ID}.scope/init.scope"),
+                ContainerRuntime::Docker,
+            ),
+            (format!("/docker/{ID}"), ContainerRuntime::Docker),
+            // podman, rootful and rootless, systemd and cgroupfs managers
+            (
+                format!("/machine.slice/libpod-{ID}.scope"),
+                ContainerRuntime::Podman,
+            ),
+            (
+                format!("/machine.slice/libpod-{ID}.scope/container"),
+                ContainerRuntime::Podman,
+            ),
+            (
+                format!(
+                    "/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{ID}.scope"
+                ),
+                ContainerRuntime::Podman,
+            ),
+            (
+                format!("/libpod_parent/libpod-{ID}"),
+                ContainerRuntime::Podman,
+            ),
+            (format!("/libpod_parent/{ID}"), ContainerRuntime::Podman),
+            // cri-o and containerd under the kubelet's systemd driver
+            (
+                format!(
+                    "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod7d0b8c1e_2f3a_4b5c_9d8e_1f2a3b4c5d6e.slice/crio-{ID}.scope"
+                ),
+                ContainerRuntime::Crio,
+            ),
+            (
+                format!(
+                    "/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod7d0b8c1e_2f3a_4b5c_9d8e_1f2a3b4c5d6e.slice/cri-containerd-{ID}.scope"
+                ),
+                ContainerRuntime::Containerd,
+            ),
+            // The cgroupfs driver leaves no runtime prefix to go by.
+            (
+                format!("/kubepods/burstable/pod7d0b8c1e-2f3a-4b5c-9d8e-1f2a3b4c5d6e/{ID}"),
+                ContainerRuntime::Containerd,
+            ),
+        ];
+
+        for (path, runtime) in cases {
+            assert_eq!(
+                container_from_cgroup(&path),
+                Some((runtime, ID.to_string())),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn container_from_cgroup_skips_conmon_and_host_cgroups() {
+        let paths = [
+            format!("/machine.slice/libpod-conmon-{ID}.scope"),
+            format!(
+                "/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-conmon-{ID}.scope"
+            ),
+            format!(
+                "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod7d0b8c1e_2f3a_4b5c_9d8e_1f2a3b4c5d6e.slice/crio-conmon-{ID}.scope"
+            ),
+            "/system.slice/docker.service".to_string(),
+            "/system.slice/containerd.service".to_string(),
+            "/user.slice/user-1000.slice/session-2.scope".to_string(),
+            // Not a full container ID.
+            "/docker/3f2a".to_string(),
+            "/".to_string(),
+        ];
+
+        for path in paths {
+            assert_eq!(container_from_cgroup(&path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn container_from_cgroup_names_lxc_containers() {
+        for path in [
+            "/lxc.payload.web",
+            "/lxc.payload.web/system.slice",
+            "/lxc/web",
+        ] {
+            assert_eq!(
+                container_from_cgroup(path),
+                Some((ContainerRuntime::Lxc, "web".to_string())),
+                "{}",
+                path
+            );
+        }
+    }
+}
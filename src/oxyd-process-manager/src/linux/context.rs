@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use oxyd_domain::errors::ProcessError;
+use std::time::Instant;
+use tokio::fs;
+
+// System-wide values every process in a scan needs. Captured once per refresh
+// instead of once per PID.
+#[derive(Debug, Clone)]
+pub struct ScanContext {
+    pub clock_ticks: u64,
+    pub page_size: u64,
+    pub num_cpus: usize,
+    pub boot_time: DateTime<Utc>,
+    pub total_memory_bytes: u64,
+    pub total_cpu_jiffies: u64,
+    pub captured_at: Instant,
+}
+
+impl ScanContext {
+    pub async fn capture() -> Result<Self, ProcessError> {
+        let stat_content = fs::read_to_string("/proc/stat")
+            .await
+            .map_err(|e| ProcessError::ReadFailed(0, format!("Failed to read /proc/stat: {}", e)))?;
+        let meminfo = fs::read_to_string("/proc/meminfo").await.map_err(|e| {
+            ProcessError::ReadFailed(0, format!("Failed to read /proc/meminfo: {}", e))
+        })?;
+
+        Ok(Self {
+            clock_ticks: sysconf(libc::_SC_CLK_TCK).unwrap_or(100),
+            page_size: sysconf(libc::_SC_PAGESIZE).unwrap_or(4096),
+            num_cpus: num_cpus::get(),
+            boot_time: parse_boot_time(&stat_content)?,
+            total_memory_bytes: parse_mem_total(&meminfo),
+            total_cpu_jiffies: parse_total_cpu_jiffies(&stat_content)?,
+            captured_at: Instant::now(),
+        })
+    }
+
+    // `starttime` in /proc/<pid>/stat is measured in clock ticks since boot.
+    pub fn start_time(&self, starttime_ticks: u64) -> DateTime<Utc> {
+        let millis = starttime_ticks.saturating_mul(1000) / self.clock_ticks.max(1);
+        self.boot_time + chrono::Duration::milliseconds(millis as i64)
+    }
+
+    pub fn memory_percent(&self, rss_bytes: u64) -> f64 {
+        if self.total_memory_bytes == 0 {
+            return 0.0;
+        }
+        (rss_bytes as f64 / self.total_memory_bytes as f64) * 100.0
+    }
+}
+
+pub async fn read_total_cpu_jiffies() -> Result<u64, ProcessError> {
+    let stat_content = fs::read_to_string("/proc/stat")
+        .await
+        .map_err(|e| ProcessError::ReadFailed(0, format!("Failed to read /proc/stat: {}", e)))?;
+
+    parse_total_cpu_jiffies(&stat_content)
+}
+
+fn sysconf(name: libc::c_int) -> Option<u64> {
+    let value = unsafe { libc::sysconf(name) };
+    if value > 0 { Some(value as u64) } else { None }
+}
+
+fn parse_total_cpu_jiffies(stat_content: &str) -> Result<u64, ProcessError> {
+    if let Some(first_line) = stat_content.lines().next()
+        && first_line.starts_with("cpu ")
+    {
+        let total: u64 = first_line
+            .split_whitespace()
+            .skip(1)
+            .take(10)
+            .filter_map(|s| s.parse::<u64>().ok())
+            .sum();
+
+        return Ok(total);
+    }
+
+    Err(ProcessError::ParseError(
+        "Could not parse /proc/stat".to_string(),
+    ))
+}
+
+fn parse_boot_time(stat_content: &str) -> Result<DateTime<Utc>, ProcessError> {
+    let btime = stat_content
+        .lines()
+        .find_map(|line| line.strip_prefix("btime"))
+        .ok_or_else(|| ProcessError::ParseError("Could not find btime".to_string()))?;
+
+    let boot_timestamp = btime
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| ProcessError::ParseError("Invalid btime".to_string()))?;
+
+    Ok(DateTime::from_timestamp(boot_timestamp, 0).unwrap_or_else(Utc::now))
+}
+
+fn parse_mem_total(meminfo: &str) -> u64 {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+        .unwrap_or(0)
+}
@@ -1,10 +1,12 @@
 use oxyd_domain::errors::ProcessError;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::Arc;
 use tokio::fs;
 use tokio::sync::Mutex;
 use tokio::time::{Duration, sleep};
 
+use super::context::{ScanContext, read_total_cpu_jiffies};
 use super::parsers::{StatFields, parse_stat};
 
 #[derive(Debug, Clone)]
@@ -14,26 +16,26 @@ pub struct CpuMeasurement {
     pub timestamp: std::time::Instant,
 }
 
-pub async fn calculate_cpu_usage_cached(
-    pid: u32,
+// CPU usage since the previous sample stored under `key` (a PID, or a
+// (PID, TID) pair for threads).
+pub async fn calculate_cpu_usage_cached<K: Hash + Eq + Copy>(
+    key: K,
     stat_fields: &StatFields,
-    cache: &Arc<Mutex<HashMap<u32, CpuMeasurement>>>,
+    context: &ScanContext,
+    cache: &Arc<Mutex<HashMap<K, CpuMeasurement>>>,
 ) -> f64 {
     let process_time = stat_fields.utime + stat_fields.stime;
-    let system_time = match get_system_cpu_time().await {
-        Ok(time) => time,
-        Err(_) => return 0.0,
-    };
+    let system_time = context.total_cpu_jiffies;
+    let num_cpus = context.num_cpus as f64;
 
     let now = std::time::Instant::now();
     let mut cache_lock = cache.lock().await;
 
-    if let Some(prev) = cache_lock.get(&pid) {
+    if let Some(prev) = cache_lock.get(&key) {
         let time_delta = now.duration_since(prev.timestamp).as_secs_f64();
 
         if time_delta < 0.1 {
             if prev.system_time > 0 {
-                let num_cpus = num_cpus::get() as f64;
                 let process_delta = process_time.saturating_sub(prev.process_time) as f64;
                 let system_delta = system_time.saturating_sub(prev.system_time) as f64;
 
@@ -49,11 +51,10 @@ pub async fn calculate_cpu_usage_cached(
         let system_delta = system_time.saturating_sub(prev.system_time) as f64;
 
         if system_delta > 0.0 {
-            let num_cpus = num_cpus::get() as f64;
             let cpu_percent = (process_delta / system_delta) * 100.0 * num_cpus;
 
             cache_lock.insert(
-                pid,
+                key,
                 CpuMeasurement {
                     process_time,
                     system_time,
@@ -66,7 +67,7 @@ pub async fn calculate_cpu_usage_cached(
     }
 
     cache_lock.insert(
-        pid,
+        key,
         CpuMeasurement {
             process_time,
             system_time,
@@ -115,7 +116,7 @@ async fn take_cpu_measurement(pid: u32) -> Result<CpuMeasurement, ProcessError>
 
     let stat_fields = parse_stat(&stat_content)?;
     let process_time = stat_fields.utime + stat_fields.stime;
-    let system_time = get_system_cpu_time().await?;
+    let system_time = read_total_cpu_jiffies().await?;
 
     Ok(CpuMeasurement {
         process_time,
@@ -123,27 +124,3 @@ async fn take_cpu_measurement(pid: u32) -> Result<CpuMeasurement, ProcessError>
         timestamp: std::time::Instant::now(),
     })
 }
-
-async fn get_system_cpu_time() -> Result<u64, ProcessError> {
-    let stat_content = fs::read_to_string("/proc/stat")
-        .await
-        .map_err(|e| ProcessError::ReadFailed(0, format!("Failed to read /proc/stat: {}", e)))?;
-
-    if let Some(first_line) = stat_content.lines().next() {
-        if first_line.starts_with("cpu ") {
-            let parts: Vec<&str> = first_line.split_whitespace().collect();
-            let total: u64 = parts
-                .iter()
-                .skip(1)
-                .take(10)
-                .filter_map(|s| s.parse::<u64>().ok())
-                .sum();
-
-            return Ok(total);
-        }
-    }
-
-    Err(ProcessError::ParseError(
-        "Could not parse /proc/stat".to_string(),
-    ))
-}
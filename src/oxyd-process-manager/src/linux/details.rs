@@ -0,0 +1,95 @@
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{Namespace, OpenFile, Process, ProcessDetails, ThreadInfo},
+};
+use tokio::fs;
+
+use super::parsers::{parse_environ, parse_limits, parse_maps, parse_namespace_inode};
+
+// Reads the parts of /proc/<pid> only the detail view needs. Sections that
+// are unreadable (usually permissions) are left as `None` instead of failing
+// the whole lookup.
+pub async fn read_details(
+    process: Process,
+    threads: Vec<ThreadInfo>,
+) -> Result<ProcessDetails, ProcessError> {
+    let pid = process.pid;
+    let base = format!("/proc/{}", pid);
+
+    let environment = fs::read(format!("{}/environ", base))
+        .await
+        .ok()
+        .map(|data| parse_environ(&data));
+    let limits = fs::read_to_string(format!("{}/limits", base))
+        .await
+        .ok()
+        .map(|content| parse_limits(&content));
+    let cgroups = fs::read_to_string(format!("{}/cgroup", base))
+        .await
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+    let memory_maps = fs::read_to_string(format!("{}/maps", base))
+        .await
+        .ok()
+        .map(|content| parse_maps(&content));
+    // Fails with EINVAL when no LSM is active. SELinux NUL-terminates the
+    // context, AppArmor ends the profile with a newline.
+    let security_label = fs::read_to_string(format!("{}/attr/current", base))
+        .await
+        .ok()
+        .map(|label| label.trim_end_matches(['\0', '\n']).to_string())
+        .filter(|label| !label.is_empty());
+
+    Ok(ProcessDetails {
+        environment,
+        limits,
+        cgroups,
+        namespaces: read_namespaces(&base).await,
+        security_label,
+        open_files: read_open_files(&base).await,
+        memory_maps,
+        threads,
+        process,
+    })
+}
+
+async fn read_namespaces(base: &str) -> Option<Vec<Namespace>> {
+    let mut entries = fs::read_dir(format!("{}/ns", base)).await.ok()?;
+    let mut namespaces = Vec::new();
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(target) = fs::read_link(entry.path()).await else {
+            continue;
+        };
+        if let Some(inode) = parse_namespace_inode(&target.to_string_lossy()) {
+            namespaces.push(Namespace {
+                kind: entry.file_name().to_string_lossy().to_string(),
+                inode,
+            });
+        }
+    }
+
+    namespaces.sort_by(|a, b| a.kind.cmp(&b.kind));
+    Some(namespaces)
+}
+
+async fn read_open_files(base: &str) -> Option<Vec<OpenFile>> {
+    let mut entries = fs::read_dir(format!("{}/fd", base)).await.ok()?;
+    let mut files = Vec::new();
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(fd) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        // The fd may be closed between listing and reading the link.
+        if let Ok(target) = fs::read_link(entry.path()).await {
+            files.push(OpenFile {
+                fd,
+                target: target.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    files.sort_by_key(|file| file.fd);
+    Some(files)
+}
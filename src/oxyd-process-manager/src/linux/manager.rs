@@ -1,48 +1,332 @@
 use oxyd_domain::{
     traits::ProcessManager,
     errors::ProcessError,
-    models::{Process, ProcessState, ProcessAction, ProcessSignal, ProcessActionResult}
+    models::{
+        AuditRecord, CgroupLimit, IoPriority, LimitChange, ManagedProcess, Process, ProcessAction, ProcessDetails, ProcessExit, ProcessIdentity, ProcessSignal, ProcessActionResult,
+        ProcessManagerConfig, ResourceLimit, SchedulingPolicy, SnapshotOptions, SpawnSpec, StopOptions, ThreadInfo, format_cpu_list,
+    },
 };
 use async_trait::async_trait;
 use tokio::fs;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
+use std::future::Future;
+use std::io;
 use std::path::Path;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use chrono::Utc;
 
-use super::cpu::{CpuMeasurement, calculate_cpu_usage_cached};
-use super::parsers::{parse_stat, parse_status};
-use super::helpers::{count_connections, get_boot_time, calculate_memory_percent};
+use crate::policy::{ActionKind, ActionPolicy};
+
+use super::audit::{AuditContext, AuditLog, describe_actor, read_target};
+use super::cgroup;
+use super::context::ScanContext;
+use super::details::read_details;
+use super::oom;
+use super::parsers::{parse_cgroup_path, parse_limits};
+use super::rlimit;
+use super::scheduling::{self, ThreadOutcome};
+use super::signals::{ensure_same_process, read_start_ticks, send_signal_checked, validate_signal};
+use super::snapshot::ProcessReader;
+use super::stop::ExitWatcher;
+use super::supervisor::Supervisor;
+
+// A context older than this is re-captured even without a new scan, so a
+// lone `get_process` call never works with stale totals.
+const MAX_CONTEXT_AGE: Duration = Duration::from_secs(1);
+
+// `watch_process` has no deadline; it re-arms the exit watcher this often.
+const WATCH_INTERVAL: Duration = Duration::from_secs(3600);
+
+// How long to wait for SIGKILL to take effect before giving up. Only a
+// process stuck in uninterruptible sleep should ever hit this.
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct LinuxProcessManager {
-    protected_processes: Vec<String>,
-    cpu_cache: Arc<Mutex<HashMap<u32, CpuMeasurement>>>,
+    policy: ActionPolicy,
+    reader: ProcessReader,
+    scan_context: Arc<RwLock<Option<Arc<ScanContext>>>>,
+    audit: Option<Arc<AuditLog>>,
+    supervisor: Supervisor,
+}
+
+impl Default for LinuxProcessManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LinuxProcessManager {
     pub fn new() -> Self {
+        Self::with_config(vec![
+            String::from("systemd"),
+            String::from("kernel"),
+            String::from("init"),
+        ])
+    }
+
+    pub fn with_config(protected_processes: Vec<String>) -> Self {
+        Self::with_policy(ActionPolicy::new(&protected_processes))
+    }
+
+    pub fn from_config(config: &ProcessManagerConfig) -> Self {
         Self {
-            protected_processes: vec![
-                String::from("systemd"),
-                String::from("kernel"),
-                String::from("init"),
-            ],
-            cpu_cache: Arc::new(Mutex::new(HashMap::new())),
+            supervisor: Supervisor::new(config.stop_options()),
+            ..Self::with_policy(ActionPolicy::from_config(config))
         }
     }
 
-    pub fn with_config(protected_processes: Vec<String>) -> Self {
+    pub fn with_policy(policy: ActionPolicy) -> Self {
         Self {
-            protected_processes,
-            cpu_cache: Arc::new(Mutex::new(HashMap::new())),
+            policy,
+            reader: ProcessReader::new(),
+            scan_context: Arc::new(RwLock::new(None)),
+            audit: None,
+            supervisor: Supervisor::new(StopOptions::default()),
+        }
+    }
+
+    // Records every action result, successful or not, to `log`.
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.audit = Some(Arc::new(log));
+        self
+    }
+
+    // Starts a new scan: clock ticks, page size, boot time, total memory and
+    // total CPU jiffies are read once and shared by every process until the
+    // next refresh.
+    pub async fn refresh_scan_context(&self) -> Result<Arc<ScanContext>, ProcessError> {
+        let context = Arc::new(ScanContext::capture().await?);
+        *self.scan_context.write().await = Some(context.clone());
+        Ok(context)
+    }
+
+    // Runs the policy checks for `action` and returns the target process.
+    async fn authorize(&self, pid: u32, kind: ActionKind, action: &str) -> Result<Process, ProcessError> {
+        self.policy.check_pid(pid, kind, action)?;
+        let process = self.get_process(pid).await?;
+        self.policy.check_process(&process, kind, action)?;
+        Ok(process)
+    }
+
+    // A cgroup limit throttles everything in the cgroup, so every member
+    // (including descendants) has to pass the policy, not just the selected
+    // process.
+    async fn authorize_members(&self, pid: u32, root: &Path, path: &str, action: &str) -> Result<(), ProcessError> {
+        let context = self.current_scan_context().await?;
+        let options = SnapshotOptions {
+            include_paths: true,
+            include_cgroup: true,
+            ..SnapshotOptions::basic()
+        };
+
+        for member in cgroup::members(root, path).await {
+            let result = match self.policy.check_pid(member, ActionKind::Priority, action) {
+                Ok(()) => match self.reader.read(member, &context, &options).await {
+                    Ok(process) => self.policy.check_process(&process, ActionKind::Priority, action),
+                    Err(ProcessError::NotFound(_)) => Ok(()),
+                    Err(e) => Err(e),
+                },
+                refused => refused,
+            };
+
+            if let Err(ProcessError::NotAllowed(_, _, reason)) = result {
+                return Err(ProcessError::NotAllowed(
+                    action.to_string(),
+                    pid,
+                    format!("{} also holds PID {}: {}", path, member, reason),
+                ));
+            }
+            result?;
+        }
+        Ok(())
+    }
+
+    // Who is acting on `pid`, or None when auditing is off.
+    async fn audit_context(&self, pid: u32) -> Option<AuditContext> {
+        self.audit.as_ref()?;
+
+        let actor_uid = unsafe { libc::geteuid() };
+        let (name, command) = read_target(pid);
+        Some(AuditContext {
+            actor: describe_actor(self.reader.user_name(actor_uid).await),
+            actor_uid,
+            pid,
+            name,
+            command,
+        })
+    }
+
+    // Who is acting on a managed command. Its name comes from the spec, as
+    // it may not be running.
+    async fn managed_audit_context(&self, pid: Option<u32>, spec: &SpawnSpec) -> Option<AuditContext> {
+        self.audit.as_ref()?;
+
+        let actor_uid = unsafe { libc::geteuid() };
+        Some(AuditContext {
+            actor: describe_actor(self.reader.user_name(actor_uid).await),
+            actor_uid,
+            pid: pid.unwrap_or(0),
+            name: spec.command.clone(),
+            command: spec.command_line(),
+        })
+    }
+
+    // Failing to write the audit log never fails the action itself. The
+    // record is written in the background.
+    fn audit(
+        &self,
+        context: &Option<AuditContext>,
+        action: ProcessAction,
+        result: Result<&ProcessActionResult, &ProcessError>,
+    ) {
+        if let (Some(log), Some(context)) = (&self.audit, context) {
+            log.submit(context.record(action, result));
         }
     }
+
+    // Captures the audit context, runs `operation` and records its result.
+    async fn audited<F>(&self, pid: u32, action: ProcessAction, operation: F) -> Result<ProcessActionResult, ProcessError>
+    where
+        F: Future<Output = Result<ProcessActionResult, ProcessError>>,
+    {
+        let context = self.audit_context(pid).await;
+        let result = operation.await;
+        self.audit(&context, action, result.as_ref());
+        result
+    }
+
+    async fn signal(&self, target: &ProcessIdentity, signal: ProcessSignal) -> Result<ProcessActionResult, ProcessError> {
+        let pid = target.pid;
+        validate_signal(signal.number())?;
+        self.authorize(pid, ActionKind::Signal, "signal").await?;
+
+        send_signal_checked(pid, signal.number(), target.start_ticks)?;
+
+        Ok(ProcessActionResult {
+            pid,
+            action: signal.action(),
+            success: true,
+            message: Some(format!("Sent {} to process {}", signal.name(), pid)),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn renice(&self, pid: u32, nice: i32, action: ProcessAction) -> Result<ProcessActionResult, ProcessError> {
+        self.authorize(pid, ActionKind::Priority, "renice").await?;
+        let outcome = scheduling::set_nice(pid, nice)?;
+
+        Ok(thread_result(
+            pid,
+            action,
+            format!("Set nice value of process {} to {}", pid, nice),
+            outcome,
+        ))
+    }
+
+    async fn stop(
+        &self,
+        target: &ProcessIdentity,
+        options: &StopOptions,
+        progress: &(dyn Fn(ProcessActionResult) + Send + Sync),
+    ) -> Result<ProcessActionResult, ProcessError> {
+        validate_signal(options.signal.number())?;
+
+        let pid = target.pid;
+        let process = self.authorize(pid, ActionKind::Signal, "stop").await?;
+        let watcher = ExitWatcher::open(pid, target.start_ticks)?;
+        let grace_period = Duration::from_millis(options.grace_period_ms);
+        let started = Instant::now();
+
+        watcher.send_signal(pid, options.signal.number())?;
+        progress(ProcessActionResult {
+            pid,
+            action: options.signal.action(),
+            success: true,
+            message: Some(format!(
+                "Sent {} to {} (PID: {}), waiting up to {:.1}s",
+                options.signal.name(),
+                process.name,
+                pid,
+                grace_period.as_secs_f64()
+            )),
+            timestamp: Utc::now(),
+        });
+
+        if watcher.wait(grace_period).await {
+            return Ok(ProcessActionResult {
+                pid,
+                action: ProcessAction::Stop,
+                success: true,
+                message: Some(format!(
+                    "{} (PID: {}) exited after {:.1}s",
+                    process.name,
+                    pid,
+                    started.elapsed().as_secs_f64()
+                )),
+                timestamp: Utc::now(),
+            });
+        }
+
+        // The process may exit between the timeout and SIGKILL.
+        match watcher.send_signal(pid, ProcessSignal::Kill.number()) {
+            Ok(()) => {}
+            Err(ProcessError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        progress(ProcessActionResult {
+            pid,
+            action: ProcessAction::Kill,
+            success: true,
+            message: Some(format!(
+                "{} (PID: {}) still running after {:.1}s, sent SIGKILL",
+                process.name,
+                pid,
+                grace_period.as_secs_f64()
+            )),
+            timestamp: Utc::now(),
+        });
+
+        let exited = watcher.wait(KILL_TIMEOUT).await;
+        Ok(ProcessActionResult {
+            pid,
+            action: ProcessAction::Stop,
+            success: exited,
+            message: Some(if exited {
+                format!(
+                    "{} (PID: {}) killed after {:.1}s",
+                    process.name,
+                    pid,
+                    started.elapsed().as_secs_f64()
+                )
+            } else {
+                format!(
+                    "{} (PID: {}) survived SIGKILL for {:.0}s (uninterruptible sleep?)",
+                    process.name,
+                    pid,
+                    KILL_TIMEOUT.as_secs_f64()
+                )
+            }),
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn current_scan_context(&self) -> Result<Arc<ScanContext>, ProcessError> {
+        if let Some(context) = self.scan_context.read().await.as_ref()
+            && context.captured_at.elapsed() < MAX_CONTEXT_AGE
+        {
+            return Ok(context.clone());
+        }
+
+        self.refresh_scan_context().await
+    }
 }
 
 #[async_trait]
 impl ProcessManager for LinuxProcessManager {
     async fn list_processes(&self) -> Result<Vec<u32>, ProcessError> {
+        self.refresh_scan_context().await?;
+
         let mut pids = Vec::new();
         let mut entries = fs::read_dir("/proc").await
             .map_err(|e| ProcessError::ListFailed(format!("Failed to read /proc: {}", e)))?;
@@ -64,226 +348,454 @@ impl ProcessManager for LinuxProcessManager {
     }
 
     async fn get_process(&self, pid: u32) -> Result<Process, ProcessError> {
-        let process_path = format!("/proc/{}", pid);
+        let context = self.current_scan_context().await?;
+        self.reader.read(pid, &context, &SnapshotOptions::full()).await
+    }
 
-        if !Path::new(&process_path).exists() {
-            return Err(ProcessError::NotFound(pid));
-        }
+    async fn get_process_details(&self, pid: u32) -> Result<ProcessDetails, ProcessError> {
+        // Thread CPU is a delta against the previous call, so it needs fresh
+        // system totals rather than a context up to a second old.
+        let context = self.refresh_scan_context().await?;
+        let process = self.reader.read(pid, &context, &SnapshotOptions::full()).await?;
+        let threads = self.reader.read_threads(pid, &context).await?;
+        read_details(process, threads).await
+    }
 
-        // Read and parse stat
-        let stat_path = format!("{}/stat", process_path);
-        let stat_content = fs::read_to_string(&stat_path).await
-            .map_err(|e| ProcessError::ReadFailed(pid, format!("Failed to read stat: {}", e)))?;
-        let stat_fields = parse_stat(&stat_content)?;
-
-        // Get process name
-        let comm_path = format!("{}/comm", process_path);
-        let name = fs::read_to_string(&comm_path).await
-            .unwrap_or_else(|_| stat_fields.comm.clone())
-            .trim()
-            .to_string();
-
-        // Get command and arguments
-        let cmdline_path = format!("{}/cmdline", process_path);
-        let (command, arguments) = match fs::read(&cmdline_path).await {
-            Ok(data) => {
-                let parts: Vec<String> = data.split(|&b| b == 0)
-                    .filter(|s| !s.is_empty())
-                    .map(|s| String::from_utf8_lossy(s).to_string())
-                    .collect();
-
-                if parts.is_empty() {
-                    (format!("[{}]", name), vec![])
-                } else {
-                    let cmd = parts[0].clone();
-                    let args = parts.into_iter().skip(1).collect();
-                    (cmd, args)
-                }
-            }
-            Err(_) => (format!("[{}]", name), vec![]),
-        };
+    async fn list_threads(&self, pid: u32) -> Result<Vec<ThreadInfo>, ProcessError> {
+        let context = self.refresh_scan_context().await?;
+        self.reader.read_threads(pid, &context).await
+    }
 
-        // Get executable and working directory
-        let exe_path = format!("{}/exe", process_path);
-        let executable_path = fs::read_link(&exe_path).await
-            .ok()
-            .map(|p| p.to_string_lossy().to_string());
+    async fn snapshot_all(&self, options: &SnapshotOptions) -> Result<Vec<Process>, ProcessError> {
+        let pids = self.list_processes().await?;
+        let context = self.current_scan_context().await?;
 
-        let cwd_path = format!("{}/cwd", process_path);
-        let working_dir = fs::read_link(&cwd_path).await
-            .ok()
-            .map(|p| p.to_string_lossy().to_string());
-
-        // Parse status
-        let status_path = format!("{}/status", process_path);
-        let status_info = fs::read_to_string(&status_path).await
-            .map_err(|e| ProcessError::ReadFailed(pid, format!("Failed to read status: {}", e)))?;
-        let status_fields = parse_status(&status_info);
-
-        // Count file descriptors
-        let fd_path = format!("{}/fd", process_path);
-        let open_files = match fs::read_dir(&fd_path).await {
-            Ok(mut entries) => {
-                let mut count = 0;
-                while let Ok(Some(_)) = entries.next_entry().await {
-                    count += 1
-                }
-                count
-            }
-            Err(_) => 0,
-        };
+        Ok(self.reader.read_all(pids, context, options).await)
+    }
 
-        // Count network connections
-        let tcp_path = format!("{}/net/tcp", process_path);
-        let tcp6_path = format!("{}/net/tcp6", process_path);
-        let udp_path = format!("{}/net/udp", process_path);
-        let udp6_path = format!("{}/net/udp6", process_path);
-
-        let open_connections = count_connections(&tcp_path).await
-            + count_connections(&tcp6_path).await
-            + count_connections(&udp_path).await
-            + count_connections(&udp6_path).await;
-
-        // Map state
-        let state = match stat_fields.state {
-            'R' => ProcessState::Running,
-            'S' => ProcessState::Sleeping,
-            'D' => ProcessState::Waiting,
-            'Z' => ProcessState::Zombie,
-            'T' => ProcessState::Stopped,
-            't' => ProcessState::Stopped,
-            'W' => ProcessState::Sleeping,
-            'X' | 'x' => ProcessState::Dead,
-            'K' => ProcessState::Waiting,
-            'P' => ProcessState::Waiting,
-            'I' => ProcessState::Idle,
-            _ => ProcessState::Unknown,
-        };
+    async fn kill_process(&self, pid: u32) -> Result<Process, ProcessError> {
+        let context = self.audit_context(pid).await;
+        let result = async {
+            // The signal is bound to the start time read while authorizing.
+            let process = self.authorize(pid, ActionKind::Signal, "kill").await?;
 
-        // Calculate CPU usage
-        let cpu_usage_percent = calculate_cpu_usage_cached(
-            pid,
-            &stat_fields,
-            &self.cpu_cache
-        ).await;
-
-        // Calculate start time
-        let boot_time = get_boot_time().await?;
-        let ticks_per_second = 100;
-        let start_time = boot_time + chrono::Duration::seconds(
-            (stat_fields.starttime / ticks_per_second) as i64
-        );
-
-        Ok(Process {
+            send_signal_checked(pid, ProcessSignal::Kill.number(), process.start_ticks)?;
+
+            Ok(process)
+        }
+        .await;
+
+        let outcome = result.as_ref().map(|_| ProcessActionResult {
             pid,
-            ppid: if stat_fields.ppid > 0 { Some(stat_fields.ppid) } else { None },
-            name,
-            command,
-            arguments,
-            executable_path,
-            working_dir,
-            state,
-            user: status_fields.uid.clone(),
-            group: status_fields.gid.clone(),
-            priority: stat_fields.priority,
-            nice: stat_fields.nice,
-            threads: status_fields.threads,
-            start_time,
-            cpu_usage_percent,
-            memory_usage_bytes: status_fields.rss_bytes,
-            memory_usage_percent: calculate_memory_percent(status_fields.rss_bytes).await,
-            virtual_memory_bytes: status_fields.vm_size,
-            disk_write_bytes: status_fields.write_bytes,
-            disk_read_bytes: status_fields.read_bytes,
-            open_files,
-            open_connections,
+            action: ProcessAction::Kill,
+            success: true,
+            message: Some(format!("Sent SIGKILL to process {}", pid)),
+            timestamp: Utc::now(),
+        });
+        self.audit(&context, ProcessAction::Kill, outcome.as_ref().map_err(|e| *e));
+
+        result
+    }
+
+    async fn send_signal(&self, pid: u32, signal: ProcessSignal) -> Result<ProcessActionResult, ProcessError> {
+        self.audited(pid, signal.action(), async {
+            // Refused PIDs never get their /proc entry read.
+            self.policy.check_pid(pid, ActionKind::Signal, "signal")?;
+            let target = ProcessIdentity {
+                pid,
+                start_ticks: read_start_ticks(pid)?,
+            };
+            self.signal(&target, signal).await
         })
+        .await
     }
 
-    async fn kill_process(&self, pid: u32) -> Result<Process, ProcessError> {
-        let process = self.get_process(pid).await?;
+    async fn send_signal_to(&self, target: &ProcessIdentity, signal: ProcessSignal) -> Result<ProcessActionResult, ProcessError> {
+        self.audited(target.pid, signal.action(), self.signal(target, signal)).await
+    }
 
-        if self.protected_processes.contains(&process.name) {
-            return Err(ProcessError::PermissionDenied(pid));
+    async fn verify_identity(&self, target: &ProcessIdentity) -> Result<(), ProcessError> {
+        ensure_same_process(target.pid, target.start_ticks)
+    }
+
+    async fn watch_process(&self, target: &ProcessIdentity) -> Result<ProcessExit, ProcessError> {
+        let pid = target.pid;
+        let process = self.get_process(pid).await?;
+        if process.identity() != *target {
+            return Err(ProcessError::IdentityMismatch(pid));
         }
+        let watcher = ExitWatcher::open(pid, target.start_ticks)?;
+        let managed = self.supervisor.find_by_pid(pid);
 
-        let output = tokio::process::Command::new("kill")
-            .arg("-9")
-            .arg(pid.to_string())
-            .output()
-            .await
-            .map_err(|e| ProcessError::ActionFailed(
-                "kill".to_string(),
+        while !watcher.wait(WATCH_INTERVAL).await {}
+        let exited_at = Utc::now();
+
+        let status = match managed {
+            Some(id) => self.supervisor.exit_status(id, pid).await,
+            None => None,
+        };
+        Ok(ProcessExit {
+            pid,
+            name: process.name,
+            started_at: process.start_time,
+            exited_at,
+            status,
+        })
+    }
+
+    async fn stop_process(
+        &self,
+        target: &ProcessIdentity,
+        options: &StopOptions,
+        progress: &(dyn Fn(ProcessActionResult) + Send + Sync),
+    ) -> Result<ProcessActionResult, ProcessError> {
+        let context = self.audit_context(target.pid).await;
+
+        // Intermediate steps (the first signal, the SIGKILL) are audited too.
+        let audited_progress = |result: ProcessActionResult| {
+            self.audit(&context, result.action.clone(), Ok(&result));
+            progress(result);
+        };
+
+        let result = self.stop(target, options, &audited_progress).await;
+        self.audit(&context, ProcessAction::Stop, result.as_ref());
+        result
+    }
+
+    async fn send_priority(&self, pid: u32, priority: i32) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetPriority(priority);
+        self.audited(pid, action.clone(), self.renice(pid, priority, action)).await
+    }
+
+    async fn set_nice(&self, pid: u32, nice: i32) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetNice(nice);
+        self.audited(pid, action.clone(), self.renice(pid, nice, action)).await
+    }
+
+    async fn set_scheduler(&self, pid: u32, policy: SchedulingPolicy, priority: i32) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetScheduler(policy, priority);
+
+        self.audited(pid, action.clone(), async {
+            self.authorize(pid, ActionKind::Priority, "reschedule").await?;
+            let outcome = scheduling::set_scheduler(pid, policy, priority)?;
+
+            Ok(thread_result(
                 pid,
-                e.to_string()
-            ))?;
+                action,
+                format!(
+                    "Set scheduling policy of process {} to {} (priority {})",
+                    pid,
+                    policy.name(),
+                    priority
+                ),
+                outcome,
+            ))
+        })
+        .await
+    }
+
+    async fn set_affinity(&self, pid: u32, cpus: &[usize]) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetAffinity(cpus.to_vec());
+
+        self.audited(pid, action.clone(), async {
+            self.authorize(pid, ActionKind::Priority, "pin").await?;
+            let outcome = scheduling::set_affinity(pid, cpus)?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(ProcessError::ActionFailed(
-                "kill".to_string(),
+            Ok(thread_result(
                 pid,
-                stderr.to_string()
-            ));
-        }
+                action,
+                format!("Pinned process {} to CPUs {}", pid, format_cpu_list(cpus)),
+                outcome,
+            ))
+        })
+        .await
+    }
 
-        Ok(process)
+    async fn get_affinity(&self, pid: u32) -> Result<Vec<usize>, ProcessError> {
+        scheduling::get_affinity(pid)
     }
 
-    async fn send_signal(&self, pid: u32, signal: ProcessSignal) -> Result<ProcessActionResult, ProcessError> {
-        let signal_name = match signal {
-            ProcessSignal::Kill => "SIGKILL",
-            ProcessSignal::Terminate => "SIGTERM",
-            ProcessSignal::Stop => "SIGSTOP",
-            ProcessSignal::Continue => "SIGCONT",
-            ProcessSignal::Interrupt => "SIGINT",
-            ProcessSignal::Quit => "SIGQUIT",
-            ProcessSignal::Hangup => "SIGHUP",
-            ProcessSignal::Custom(_) => "CUSTOM",
-        };
+    async fn get_io_priority(&self, pid: u32) -> Result<IoPriority, ProcessError> {
+        scheduling::get_io_priority(pid)
+    }
 
-        let process = self.get_process(pid).await?;
-        if self.protected_processes.contains(&process.name) {
-            return Err(ProcessError::PermissionDenied(pid));
-        }
+    async fn set_io_priority(&self, pid: u32, priority: IoPriority) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetIoPriority(priority);
+
+        self.audited(pid, action.clone(), async {
+            self.authorize(pid, ActionKind::Priority, "set I/O priority of").await?;
+            let outcome = scheduling::set_io_priority(pid, priority)?;
+
+            Ok(thread_result(
+                pid,
+                action,
+                format!("Set I/O priority of process {} to {}", pid, priority.label()),
+                outcome,
+            ))
+        })
+        .await
+    }
+
+    async fn move_to_cgroup(&self, pid: u32, path: &str) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::MoveToCgroup(path.to_string());
+
+        self.audited(pid, action.clone(), async {
+            let target = cgroup::normalize(path).ok_or_else(|| {
+                ProcessError::ActionFailed("move".to_string(), pid, format!("invalid cgroup path '{}'", path))
+            })?;
+            let process = self.authorize(pid, ActionKind::Priority, "move").await?;
+            self.policy.check_cgroup(pid, &target, "move")?;
+
+            let root = cgroup::cgroup_root(pid, "move")?;
+            cgroup::move_process(&root, pid, &target).await?;
 
-        let output = tokio::process::Command::new("kill")
-            .arg(format!("-{}", signal_name))
-            .arg(pid.to_string())
-            .output()
+            Ok(ProcessActionResult {
+                pid,
+                action,
+                success: true,
+                message: Some(format!(
+                    "Moved process {} from {} to {}",
+                    pid,
+                    process.cgroup.as_deref().unwrap_or("?"),
+                    target
+                )),
+                timestamp: Utc::now(),
+            })
+        })
+        .await
+    }
+
+    async fn set_cgroup_limit(&self, pid: u32, limit: &CgroupLimit) -> Result<ProcessActionResult, ProcessError> {
+        // Read up front so the audit record names the cgroup even when the
+        // action is refused.
+        let path = fs::read_to_string(format!("/proc/{}/cgroup", pid))
             .await
-            .map_err(|e| ProcessError::ActionFailed(
-                "kill".to_string(),
+            .ok()
+            .and_then(|content| parse_cgroup_path(&content));
+        let action = ProcessAction::SetCgroupLimit(path.clone().unwrap_or_else(|| "?".to_string()), limit.clone());
+
+        self.audited(pid, action.clone(), async {
+            self.authorize(pid, ActionKind::Priority, "limit").await?;
+            let path = path.ok_or_else(|| {
+                ProcessError::ActionFailed("limit".to_string(), pid, "not in a cgroup v2 hierarchy".to_string())
+            })?;
+            self.policy.check_cgroup(pid, &path, "limit")?;
+
+            let root = cgroup::cgroup_root(pid, "limit")?;
+            self.authorize_members(pid, &root, &path, "limit").await?;
+            cgroup::write_limit(&root, pid, &path, limit).await?;
+
+            Ok(ProcessActionResult {
                 pid,
-                e.to_string()
-            ))?;
+                action,
+                success: true,
+                message: Some(format!("Set {} on {}", limit.label(), path)),
+                timestamp: Utc::now(),
+            })
+        })
+        .await
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(ProcessError::ActionFailed(
-                "kill".to_string(),
+    async fn set_oom_score_adj(&self, pid: u32, adj: i32) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetOomScoreAdj(adj);
+
+        self.audited(pid, action.clone(), async {
+            self.authorize(pid, ActionKind::Priority, "set oom_score_adj of").await?;
+            let old = oom::set_oom_score_adj(pid, adj).await?;
+
+            Ok(ProcessActionResult {
                 pid,
-                stderr.to_string()
-            ));
-        }
+                action,
+                success: true,
+                message: Some(format!("Set oom_score_adj of process {} from {} to {}", pid, old, adj)),
+                timestamp: Utc::now(),
+            })
+        })
+        .await
+    }
 
-        Ok(ProcessActionResult {
-            pid,
-            action: ProcessAction::Terminate,
-            success: true,
-            message: Some(format!("Sent {} to process {}", signal_name, pid)),
-            timestamp: Utc::now(),
+    async fn get_limits(&self, pid: u32) -> Result<Vec<ResourceLimit>, ProcessError> {
+        let content = fs::read_to_string(format!("/proc/{}/limits", pid))
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ProcessError::NotFound(pid),
+                _ => ProcessError::ReadFailed(pid, format!("Failed to read limits: {}", e)),
+            })?;
+        Ok(parse_limits(&content))
+    }
+
+    async fn set_limit(&self, pid: u32, change: &LimitChange) -> Result<ProcessActionResult, ProcessError> {
+        let action = ProcessAction::SetLimit(change.clone());
+
+        self.audited(pid, action.clone(), async {
+            self.authorize(pid, ActionKind::Priority, "set limits of").await?;
+            let old = rlimit::set_limit(pid, change)?;
+            let hard = change.hard.unwrap_or(old.hard);
+
+            Ok(ProcessActionResult {
+                pid,
+                action,
+                success: true,
+                message: Some(format!(
+                    "Set {} limit of process {} from {}/{} to {}/{}",
+                    change.resource.name(),
+                    pid,
+                    old.soft.label(),
+                    old.hard.label(),
+                    change.soft.label(),
+                    hard.label()
+                )),
+                timestamp: Utc::now(),
+            })
         })
+        .await
     }
 
-    async fn send_priority(&self, pid: u32, priority: i32) -> Result<ProcessActionResult, ProcessError> {
-        Ok(ProcessActionResult {
-            pid,
-            action: ProcessAction::SetPriority(priority),
+    async fn spawn(&self, mut spec: SpawnSpec) -> Result<ManagedProcess, ProcessError> {
+        let action = ProcessAction::Spawn(spec.command_line());
+
+        let result = async {
+            if let Some(path) = &spec.cgroup {
+                let target = cgroup::normalize(path).ok_or_else(|| {
+                    ProcessError::SpawnFailed(spec.command_line(), format!("invalid cgroup path '{}'", path))
+                })?;
+                spec.cgroup = Some(target);
+            }
+            self.policy.check_spawn(&spec)?;
+
+            let cgroup_root = match &spec.cgroup {
+                Some(_) => Some(cgroup::cgroup_root(0, "spawn")?),
+                None => None,
+            };
+            self.supervisor.spawn(spec.clone(), cgroup_root).await
+        }
+        .await;
+
+        let pid = result.as_ref().ok().and_then(|managed| managed.pid);
+        let context = self.managed_audit_context(pid, &spec).await;
+        let outcome = result.as_ref().map(|managed| ProcessActionResult {
+            pid: pid.unwrap_or(0),
+            action: action.clone(),
             success: true,
-            message: Some(format!("Set priority of process {} to {}", pid, priority)),
+            message: Some(format!("Started #{} {} (PID: {})", managed.id, spec.command_line(), pid.unwrap_or(0))),
             timestamp: Utc::now(),
-        })
+        });
+        self.audit(&context, action, outcome.as_ref().map_err(|e| *e));
+
+        result
+    }
+
+    async fn managed_processes(&self) -> Result<Vec<ManagedProcess>, ProcessError> {
+        Ok(self.supervisor.list())
+    }
+
+    async fn stop_managed(&self, id: u64) -> Result<ProcessActionResult, ProcessError> {
+        let managed = self.supervisor.get(id)?;
+        let pid = managed.pid.unwrap_or(0);
+        let context = self.managed_audit_context(managed.pid, &managed.spec).await;
+
+        let result = async {
+            if let Some(pid) = managed.pid {
+                self.authorize(pid, ActionKind::Signal, "stop").await?;
+            }
+            let stopped = self.supervisor.stop(id).await?;
+
+            Ok(ProcessActionResult {
+                pid,
+                action: ProcessAction::Stop,
+                success: true,
+                message: Some(match (managed.state.is_active(), stopped.last_exit) {
+                    (false, _) => format!("#{} {} was not running", id, managed.spec.command),
+                    (true, Some(exit)) if managed.pid.is_some() => {
+                        format!("Stopped #{} {} ({})", id, managed.spec.command, exit.label())
+                    }
+                    (true, _) => format!("Stopped #{} {}", id, managed.spec.command),
+                }),
+                timestamp: Utc::now(),
+            })
+        }
+        .await;
+        self.audit(&context, ProcessAction::Stop, result.as_ref());
+        result
+    }
+
+    async fn restart_managed(&self, id: u64) -> Result<ProcessActionResult, ProcessError> {
+        let managed = self.supervisor.get(id)?;
+        let context = self.managed_audit_context(managed.pid, &managed.spec).await;
+
+        let result = async {
+            if let Some(pid) = managed.pid {
+                self.authorize(pid, ActionKind::Signal, "restart").await?;
+            }
+            self.policy.check_spawn(&managed.spec)?;
+            let restarted = self.supervisor.restart(id).await?;
+
+            Ok(ProcessActionResult {
+                pid: restarted.pid.unwrap_or(0),
+                action: ProcessAction::Restart,
+                success: restarted.pid.is_some(),
+                message: Some(match (restarted.pid, &restarted.error) {
+                    (Some(pid), _) => format!("Restarted #{} {} (PID: {})", id, managed.spec.command, pid),
+                    (None, Some(error)) => format!("Failed to restart #{} {}: {}", id, managed.spec.command, error),
+                    (None, None) => format!("#{} {} did not restart", id, managed.spec.command),
+                }),
+                timestamp: Utc::now(),
+            })
+        }
+        .await;
+        self.audit(&context, ProcessAction::Restart, result.as_ref());
+        result
+    }
+
+    async fn remove_managed(&self, id: u64) -> Result<(), ProcessError> {
+        self.supervisor.remove(id)
+    }
+
+    async fn recent_actions(&self, limit: usize) -> Result<Vec<AuditRecord>, ProcessError> {
+        match &self.audit {
+            Some(log) => {
+                let reader = Arc::clone(log);
+                tokio::task::spawn_blocking(move || reader.recent(limit))
+                    .await
+                    .map_err(io::Error::other)
+                    .and_then(|records| records)
+                    .map_err(|e| {
+                        ProcessError::ReadFailed(0, format!("Failed to read {}: {}", log.path().display(), e))
+                    })
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn audit_failure(&self) -> Option<String> {
+        self.audit.as_ref().and_then(|log| log.failure())
+    }
+
+    async fn record_dry_run(&self, pid: u32, action: ProcessAction) -> Result<(), ProcessError> {
+        let action = match action {
+            ProcessAction::SetCgroupLimit(path, limit) if path.is_empty() && pid != 0 => {
+                let path = fs::read_to_string(format!("/proc/{}/cgroup", pid))
+                    .await
+                    .ok()
+                    .and_then(|content| parse_cgroup_path(&content));
+                ProcessAction::SetCgroupLimit(path.unwrap_or_else(|| "?".to_string()), limit)
+            }
+            other => other,
+        };
+
+        if let (Some(log), Some(context)) = (&self.audit, self.audit_context(pid).await) {
+            let result = ProcessActionResult {
+                pid,
+                action: action.clone(),
+                success: true,
+                message: Some("Dry run: nothing was sent".to_string()),
+                timestamp: Utc::now(),
+            };
+            let mut record = context.record(action, Ok(&result));
+            record.dry_run = true;
+            log.submit(record);
+        }
+        Ok(())
     }
 
     async fn suspend_process(&self, pid: u32) -> Result<ProcessActionResult, ProcessError> {
@@ -294,3 +806,39 @@ impl ProcessManager for LinuxProcessManager {
         self.send_signal(pid, ProcessSignal::Continue).await
     }
 }
+
+// How many threads a partial failure names before summing up the rest.
+const LISTED_THREAD_FAILURES: usize = 5;
+
+// A per-thread change that missed some threads is reported as failed and
+// names them; the threads it reached keep the new setting.
+fn thread_result(pid: u32, action: ProcessAction, done: String, outcome: ThreadOutcome) -> ProcessActionResult {
+    let message = if outcome.is_complete() {
+        done
+    } else {
+        let mut failed: Vec<String> = outcome
+            .failed
+            .iter()
+            .take(LISTED_THREAD_FAILURES)
+            .map(|(tid, reason)| format!("TID {} ({})", tid, reason))
+            .collect();
+        if outcome.failed.len() > LISTED_THREAD_FAILURES {
+            failed.push(format!("{} more", outcome.failed.len() - LISTED_THREAD_FAILURES));
+        }
+        format!(
+            "{} on {} of {} threads; failed on {}",
+            done,
+            outcome.applied,
+            outcome.applied + outcome.failed.len(),
+            failed.join(", ")
+        )
+    };
+
+    ProcessActionResult {
+        pid,
+        action,
+        success: outcome.is_complete(),
+        message: Some(message),
+        timestamp: Utc::now(),
+    }
+}
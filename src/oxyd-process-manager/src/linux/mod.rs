@@ -1,6 +1,20 @@
+mod audit;
+mod cgroup;
+mod container;
+mod context;
 mod cpu;
+mod details;
 mod helpers;
 mod manager;
+mod oom;
 mod parsers;
+mod rlimit;
+mod scheduling;
+mod signals;
+mod snapshot;
+mod stop;
+mod supervisor;
+mod users;
 
+pub use audit::{AuditLog, ensure_private_dir};
 pub use manager::LinuxProcessManager;
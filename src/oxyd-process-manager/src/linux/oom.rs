@@ -0,0 +1,57 @@
+use oxyd_domain::errors::ProcessError;
+use std::io;
+use tokio::fs;
+
+use super::signals::map_errno;
+
+// oom_score and oom_score_adj of the process under `process_path`
+// ("/proc/<pid>"). Both are world-readable.
+pub async fn read_oom(process_path: &str) -> (Option<u32>, Option<i32>) {
+    let score = fs::read_to_string(format!("{}/oom_score", process_path))
+        .await
+        .ok()
+        .and_then(|content| content.trim().parse().ok());
+    let adj = fs::read_to_string(format!("{}/oom_score_adj", process_path))
+        .await
+        .ok()
+        .and_then(|content| content.trim().parse().ok());
+    (score, adj)
+}
+
+// Writes oom_score_adj and returns the value it replaced.
+pub async fn set_oom_score_adj(pid: u32, adj: i32) -> Result<i32, ProcessError> {
+    if !(-1000..=1000).contains(&adj) {
+        return Err(ProcessError::ActionFailed(
+            "set oom_score_adj of".to_string(),
+            pid,
+            format!("{} is outside -1000..1000", adj),
+        ));
+    }
+
+    let path = format!("/proc/{}/oom_score_adj", pid);
+    let current = fs::read_to_string(&path)
+        .await
+        .map_err(|e| map_errno(e, pid, "set oom_score_adj of"))?
+        .trim()
+        .parse::<i32>()
+        .unwrap_or_default();
+
+    fs::write(&path, adj.to_string())
+        .await
+        .map_err(|err| oom_error(err, pid, adj, current))?;
+    Ok(current)
+}
+
+fn oom_error(err: io::Error, pid: u32, adj: i32, current: i32) -> ProcessError {
+    match err.raw_os_error() {
+        // Anyone may make a process of theirs a likelier victim; protecting
+        // it takes privilege.
+        Some(libc::EACCES) | Some(libc::EPERM) if adj < current => ProcessError::ActionFailed(
+            "set oom_score_adj of".to_string(),
+            pid,
+            format!("lowering it below {} needs CAP_SYS_RESOURCE", current),
+        ),
+        Some(libc::ENOENT) => ProcessError::NotFound(pid),
+        _ => map_errno(err, pid, "set oom_score_adj of"),
+    }
+}
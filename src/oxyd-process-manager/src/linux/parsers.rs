@@ -1,4 +1,9 @@
 use oxyd_domain::errors::ProcessError;
+use oxyd_domain::models::{
+    MemoryAccounting, MemoryMapGroup, MemoryMapSummary, ProcessIds, ResourceKind, ResourceLimit,
+    SeccompMode, SecurityContext, parse_cpu_list,
+};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct StatFields {
@@ -10,16 +15,18 @@ pub struct StatFields {
     pub starttime: u64,
     pub utime: u64,
     pub stime: u64,
+    pub rss_pages: u64,
+    pub processor: Option<u32>, // CPU it last ran on
+    pub policy: i32,
 }
 
 pub struct StatusFields {
-    pub uid: String,
-    pub gid: String,
+    pub uids: ProcessIds,
+    pub gids: ProcessIds,
     pub threads: u32,
     pub vm_size: u64,
-    pub rss_bytes: u64,
-    pub read_bytes: u64,
-    pub write_bytes: u64,
+    pub cpus_allowed: Vec<usize>,
+    pub security: SecurityContext,
 }
 
 pub fn parse_stat(stat_content: &str) -> Result<StatFields, ProcessError> {
@@ -35,7 +42,7 @@ pub fn parse_stat(stat_content: &str) -> Result<StatFields, ProcessError> {
 
     let fields: Vec<&str> = after_comm.split_whitespace().collect();
 
-    if fields.len() < 20 {
+    if fields.len() < 22 {
         return Err(ProcessError::ParseError(
             "Insufficient stat fields".to_string(),
         ));
@@ -50,17 +57,19 @@ pub fn parse_stat(stat_content: &str) -> Result<StatFields, ProcessError> {
         starttime: fields[19].parse().unwrap_or(0),
         utime: fields[11].parse().unwrap_or(0),
         stime: fields[12].parse().unwrap_or(0),
+        rss_pages: fields[21].parse().unwrap_or(0),
+        processor: fields.get(36).and_then(|s| s.parse().ok()),
+        policy: fields.get(38).and_then(|s| s.parse().ok()).unwrap_or(0),
     })
 }
 
 pub fn parse_status(status_content: &str) -> StatusFields {
-    let mut uid = String::from("0");
-    let mut gid = String::from("0");
+    let mut uids = ProcessIds::default();
+    let mut gids = ProcessIds::default();
     let mut threads = 1;
     let mut vm_size = 0;
-    let mut rss_bytes = 0;
-    let read_bytes = 0;
-    let write_bytes = 0;
+    let mut cpus_allowed = Vec::new();
+    let mut security = SecurityContext::default();
 
     for line in status_content.lines() {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -70,39 +79,319 @@ pub fn parse_status(status_content: &str) -> StatusFields {
 
         match parts[0] {
             "Uid:" => {
-                if parts.len() > 1 {
-                    uid = parts[1].to_string();
-                }
+                uids = parse_id_set(&parts[1..]);
             }
             "Gid:" => {
-                if parts.len() > 1 {
-                    gid = parts[1].to_string();
-                }
+                gids = parse_id_set(&parts[1..]);
             }
             "Threads:" => {
                 threads = parts[1].parse().unwrap_or(1);
             }
-            "VmSize:" => {
-                if parts.len() > 1 {
-                    vm_size = parts[1].parse::<u64>().unwrap_or(0) * 1024;
-                }
+            "Cpus_allowed_list:" => {
+                cpus_allowed = parse_cpu_list(parts[1]).unwrap_or_default();
             }
-            "VmRSS:" => {
-                if parts.len() > 1 {
-                    rss_bytes = parts[1].parse::<u64>().unwrap_or(0) * 1024;
-                }
+            "VmSize:" if parts.len() > 1 => {
+                vm_size = parts[1].parse::<u64>().unwrap_or(0) * 1024;
             }
+            // Capability sets are 64-bit hex masks.
+            "CapInh:" => security.cap_inheritable = u64::from_str_radix(parts[1], 16).unwrap_or(0),
+            "CapPrm:" => security.cap_permitted = u64::from_str_radix(parts[1], 16).unwrap_or(0),
+            "CapEff:" => security.cap_effective = u64::from_str_radix(parts[1], 16).unwrap_or(0),
+            "CapBnd:" => security.cap_bounding = u64::from_str_radix(parts[1], 16).unwrap_or(0),
+            "CapAmb:" => security.cap_ambient = u64::from_str_radix(parts[1], 16).unwrap_or(0),
+            "NoNewPrivs:" => security.no_new_privs = parts[1] == "1",
+            "Seccomp:" => {
+                security.seccomp = SeccompMode::from_number(parts[1].parse().unwrap_or(0));
+            }
+            "Seccomp_filters:" => security.seccomp_filters = parts[1].parse().unwrap_or(0),
             _ => {}
         }
     }
 
     StatusFields {
-        uid,
-        gid,
+        uids,
+        gids,
         threads,
         vm_size,
-        rss_bytes,
-        read_bytes,
-        write_bytes,
+        cpus_allowed,
+        security,
+    }
+}
+
+// Returns (read_bytes, write_bytes) from /proc/<pid>/io.
+pub fn parse_io(io_content: &str) -> (u64, u64) {
+    let mut read_bytes = 0;
+    let mut write_bytes = 0;
+
+    for line in io_content.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().parse().unwrap_or(0);
+            match key {
+                "read_bytes" => read_bytes = value,
+                "write_bytes" => write_bytes = value,
+                _ => {}
+            }
+        }
+    }
+
+    (read_bytes, write_bytes)
+}
+
+// cgroup v2 path from /proc/<pid>/cgroup ("0::/system.slice/sshd.service").
+// On hybrid hierarchies the first v1 path is used instead.
+pub fn parse_cgroup_path(content: &str) -> Option<String> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .or_else(|| {
+            content
+                .lines()
+                .next()
+                .and_then(|line| line.splitn(3, ':').nth(2))
+        })
+        .map(|path| path.to_string())
+}
+
+// Target of a /proc/<pid>/ns link: "pid:[4026531836]" -> 4026531836
+pub fn parse_namespace_inode(link: &str) -> Option<u64> {
+    link.split_once(":[")?.1.strip_suffix(']')?.parse().ok()
+}
+
+// /proc/<pid>/smaps_rollup sums every mapping into one block of
+// "Key:   <n> kB" lines.
+pub fn parse_smaps_rollup(content: &str) -> MemoryAccounting {
+    let mut accounting = MemoryAccounting::default();
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(kb) = value.parse::<u64>() else {
+            continue;
+        };
+        let bytes = kb * 1024;
+
+        match key {
+            "Pss:" => accounting.pss_bytes = bytes,
+            "Private_Clean:" | "Private_Dirty:" => accounting.uss_bytes += bytes,
+            "Shared_Clean:" | "Shared_Dirty:" => accounting.shared_bytes += bytes,
+            "Swap:" => accounting.swap_bytes = bytes,
+            _ => {}
+        }
+    }
+
+    accounting
+}
+
+// /proc/<pid>/limits is a fixed-width table: the name takes 26 columns and
+// each limit 21, followed by the unit.
+pub fn parse_limits(limits_content: &str) -> Vec<ResourceLimit> {
+    let column = |line: &str, from: usize, to: usize| -> String {
+        line.get(from..to.min(line.len()))
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+    let value = |text: String| -> Option<u64> { text.parse().ok() };
+
+    limits_content
+        .lines()
+        .skip(1)
+        .filter(|line| line.len() > 26)
+        .map(|line| {
+            let unit = column(line, 68, line.len());
+            let name = column(line, 0, 26);
+            ResourceLimit {
+                resource: ResourceKind::from_proc_label(&name),
+                name,
+                soft: value(column(line, 26, 47)),
+                hard: value(column(line, 47, 68)),
+                unit: if unit.is_empty() { None } else { Some(unit) },
+            }
+        })
+        .collect()
+}
+
+// Groups /proc/<pid>/maps regions by backing file (or pseudo-name such as
+// "[heap]"); anonymous regions are reported as "[anon]".
+pub fn parse_maps(maps_content: &str) -> MemoryMapSummary {
+    let mut groups: HashMap<String, MemoryMapGroup> = HashMap::new();
+    let mut summary = MemoryMapSummary::default();
+
+    for line in maps_content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some((start, end)) = fields.next().and_then(|range| range.split_once('-')) else {
+            continue;
+        };
+        let (Ok(start), Ok(end)) = (u64::from_str_radix(start, 16), u64::from_str_radix(end, 16))
+        else {
+            continue;
+        };
+        // perms, offset, dev, inode, then the optional path (which may
+        // contain spaces).
+        let path: Vec<&str> = fields.skip(4).collect();
+        let name = if path.is_empty() {
+            "[anon]".to_string()
+        } else {
+            path.join(" ")
+        };
+
+        let size = end.saturating_sub(start);
+        summary.regions += 1;
+        summary.total_bytes += size;
+        let group = groups.entry(name.clone()).or_insert(MemoryMapGroup {
+            name,
+            regions: 0,
+            size_bytes: 0,
+        });
+        group.regions += 1;
+        group.size_bytes += size;
+    }
+
+    summary.groups = groups.into_values().collect();
+    summary
+        .groups
+        .sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.name.cmp(&b.name)));
+    summary
+}
+
+// NUL-separated KEY=VALUE pairs from /proc/<pid>/environ.
+pub fn parse_environ(data: &[u8]) -> Vec<(String, String)> {
+    data.split(|&b| b == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            match entry.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (entry.to_string(), String::new()),
+            }
+        })
+        .collect()
+}
+
+// `Uid:`/`Gid:` lines list the real, effective, saved and filesystem IDs.
+fn parse_id_set(values: &[&str]) -> ProcessIds {
+    let id = |i: usize| values.get(i).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    ProcessIds {
+        real: id(0),
+        effective: id(1),
+        saved: id(2),
+        filesystem: id(3),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Columns start at 0, 26, 47 and 68, as the kernel prints them.
+    const LIMITS: &str = "\
+Limit                     Soft Limit           Hard Limit           Units     
+Max cpu time              unlimited            unlimited            seconds   
+Max stack size            8388608              unlimited            bytes     
+Max open files            1024                 524288               files     
+Max nice priority         0                    0                    
+Max future thing          5                    10                   widgets   
+";
+
+    #[test]
+    fn parse_limits_reads_fixed_columns() {
+        let limits = parse_limits(LIMITS);
+        assert_eq!(limits.len(), 5);
+
+        let cpu = &limits[0];
+        assert_eq!(cpu.name, "Max cpu time");
+        assert_eq!(cpu.resource, Some(ResourceKind::Cpu));
+        assert_eq!((cpu.soft, cpu.hard), (None, None));
+        assert_eq!(cpu.unit.as_deref(), Some("seconds"));
+
+        let stack = &limits[1];
+        assert_eq!(stack.resource, Some(ResourceKind::Stack));
+        assert_eq!((stack.soft, stack.hard), (Some(8388608), None));
+
+        let files = &limits[2];
+        assert_eq!(files.resource, Some(ResourceKind::OpenFiles));
+        assert_eq!((files.soft, files.hard), (Some(1024), Some(524288)));
+        assert_eq!(files.unit.as_deref(), Some("files"));
+
+        let nice = &limits[3];
+        assert_eq!(nice.resource, Some(ResourceKind::Nice));
+        assert_eq!((nice.soft, nice.hard), (Some(0), Some(0)));
+        assert_eq!(nice.unit, None);
+
+        // Rows this build does not know are kept, without a resource.
+        let unknown = &limits[4];
+        assert_eq!(unknown.name, "Max future thing");
+        assert_eq!(unknown.resource, None);
+        assert_eq!((unknown.soft, unknown.hard), (Some(5), Some(10)));
+    }
+
+    #[test]
+    fn parse_limits_skips_short_lines() {
+        let limits = parse_limits("Limit  Soft Limit\nMax cpu time\n\n");
+        assert!(limits.is_empty());
+    }
+
+    #[test]
+    fn parse_smaps_rollup_sums_private_and_shared() {
+        let content = "\
+55721976b000-7ffd3f80e000 ---p 00000000 00:00 0                          [rollup]
+Rss:                1304 kB
+Pss:                 498 kB
+Pss_Anon:            104 kB
+Shared_Clean:       1116 kB
+Shared_Dirty:          8 kB
+Private_Clean:        84 kB
+Private_Dirty:       104 kB
+Swap:                 16 kB
+SwapPss:              12 kB
+";
+        let accounting = parse_smaps_rollup(content);
+        assert_eq!(
+            accounting,
+            MemoryAccounting {
+                pss_bytes: 498 * 1024,
+                uss_bytes: (84 + 104) * 1024,
+                shared_bytes: (1116 + 8) * 1024,
+                swap_bytes: 16 * 1024,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_smaps_rollup_of_nothing_is_zero() {
+        assert_eq!(parse_smaps_rollup(""), MemoryAccounting::default());
+    }
+
+    #[test]
+    fn parse_cgroup_path_prefers_the_unified_hierarchy() {
+        assert_eq!(
+            parse_cgroup_path("0::/system.slice/sshd.service\n").as_deref(),
+            Some("/system.slice/sshd.service")
+        );
+
+        let hybrid = "12:pids:/user.slice/user-1000.slice\n4:memory:/user.slice\n0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(
+            parse_cgroup_path(hybrid).as_deref(),
+            Some("/user.slice/user-1000.slice/session-2.scope")
+        );
+    }
+
+    #[test]
+    fn parse_cgroup_path_falls_back_to_the_first_v1_line() {
+        let v1 = "4:memory:/docker/3f2a\n3:cpu,cpuacct:/docker/3f2a\n";
+        assert_eq!(parse_cgroup_path(v1).as_deref(), Some("/docker/3f2a"));
+
+        assert_eq!(parse_cgroup_path(""), None);
+    }
+
+    #[test]
+    fn parse_namespace_inode_reads_the_link_target() {
+        assert_eq!(parse_namespace_inode("pid:[4026531836]"), Some(4026531836));
+        assert_eq!(parse_namespace_inode("pid_for_children:[4026532201]"), Some(4026532201));
+        assert_eq!(parse_namespace_inode("pid:[4026531836"), None);
+        assert_eq!(parse_namespace_inode("/proc/1/ns/pid"), None);
     }
 }
@@ -0,0 +1,149 @@
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{LimitChange, LimitValue, ResourceKind},
+};
+use std::io;
+
+use super::signals::map_errno;
+
+// Soft and hard limit of one resource, as `LimitValue`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rlimit {
+    pub soft: LimitValue,
+    pub hard: LimitValue,
+}
+
+fn resource_number(resource: ResourceKind) -> libc::c_int {
+    let number = match resource {
+        ResourceKind::Cpu => libc::RLIMIT_CPU,
+        ResourceKind::FileSize => libc::RLIMIT_FSIZE,
+        ResourceKind::Data => libc::RLIMIT_DATA,
+        ResourceKind::Stack => libc::RLIMIT_STACK,
+        ResourceKind::Core => libc::RLIMIT_CORE,
+        ResourceKind::Rss => libc::RLIMIT_RSS,
+        ResourceKind::Processes => libc::RLIMIT_NPROC,
+        ResourceKind::OpenFiles => libc::RLIMIT_NOFILE,
+        ResourceKind::LockedMemory => libc::RLIMIT_MEMLOCK,
+        ResourceKind::AddressSpace => libc::RLIMIT_AS,
+        ResourceKind::FileLocks => libc::RLIMIT_LOCKS,
+        ResourceKind::PendingSignals => libc::RLIMIT_SIGPENDING,
+        ResourceKind::MessageQueue => libc::RLIMIT_MSGQUEUE,
+        ResourceKind::Nice => libc::RLIMIT_NICE,
+        ResourceKind::RealtimePriority => libc::RLIMIT_RTPRIO,
+        ResourceKind::RealtimeTimeout => libc::RLIMIT_RTTIME,
+    };
+    number as libc::c_int
+}
+
+fn to_raw(value: LimitValue) -> libc::rlim_t {
+    match value {
+        LimitValue::Unlimited => libc::RLIM_INFINITY,
+        LimitValue::Value(value) => value,
+    }
+}
+
+fn from_raw(value: libc::rlim_t) -> LimitValue {
+    if value == libc::RLIM_INFINITY {
+        LimitValue::Unlimited
+    } else {
+        LimitValue::Value(value)
+    }
+}
+
+// Unlimited sorts above every number.
+fn exceeds(value: LimitValue, limit: LimitValue) -> bool {
+    match (value, limit) {
+        (_, LimitValue::Unlimited) => false,
+        (LimitValue::Unlimited, LimitValue::Value(_)) => true,
+        (LimitValue::Value(value), LimitValue::Value(limit)) => value > limit,
+    }
+}
+
+fn prlimit(
+    pid: u32,
+    resource: ResourceKind,
+    new: Option<&libc::rlimit>,
+) -> Result<Rlimit, io::Error> {
+    let mut old = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    let new = new.map_or(std::ptr::null(), |new| new as *const libc::rlimit);
+
+    let rc = unsafe {
+        libc::prlimit(
+            pid as libc::pid_t,
+            resource_number(resource) as _,
+            new,
+            &mut old,
+        )
+    };
+    if rc < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(Rlimit {
+        soft: from_raw(old.rlim_cur),
+        hard: from_raw(old.rlim_max),
+    })
+}
+
+// The kernel caps RLIMIT_NOFILE at fs.nr_open, even for root.
+fn nr_open() -> Option<u64> {
+    std::fs::read_to_string("/proc/sys/fs/nr_open")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+pub fn get_limit(pid: u32, resource: ResourceKind) -> Result<Rlimit, ProcessError> {
+    prlimit(pid, resource, None).map_err(|e| map_errno(e, pid, "read limits of"))
+}
+
+// Applies `change` and returns the limits it replaced.
+pub fn set_limit(pid: u32, change: &LimitChange) -> Result<Rlimit, ProcessError> {
+    let current = get_limit(pid, change.resource)?;
+    let hard = change.hard.unwrap_or(current.hard);
+
+    if exceeds(change.soft, hard) {
+        return Err(ProcessError::ActionFailed(
+            "set limits of".to_string(),
+            pid,
+            format!(
+                "soft limit {} is above the hard limit {}",
+                change.soft.label(),
+                hard.label()
+            ),
+        ));
+    }
+
+    if change.resource == ResourceKind::OpenFiles
+        && let Some(max) = nr_open()
+        && exceeds(hard, LimitValue::Value(max))
+    {
+        return Err(ProcessError::ActionFailed(
+            "set limits of".to_string(),
+            pid,
+            format!("open files cannot exceed fs.nr_open ({})", max),
+        ));
+    }
+
+    let new = libc::rlimit {
+        rlim_cur: to_raw(change.soft),
+        rlim_max: to_raw(hard),
+    };
+    prlimit(pid, change.resource, Some(&new)).map_err(|err| match err.raw_os_error() {
+        Some(libc::EPERM) if exceeds(hard, current.hard) => ProcessError::ActionFailed(
+            "set limits of".to_string(),
+            pid,
+            format!(
+                "raising the hard limit above {} needs CAP_SYS_RESOURCE",
+                current.hard.label()
+            ),
+        ),
+        _ => map_errno(err, pid, "set limits of"),
+    })?;
+
+    Ok(current)
+}
@@ -0,0 +1,244 @@
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{IoClass, IoPriority, SchedulingPolicy},
+};
+use std::io;
+
+use super::signals::map_errno;
+
+// How a per-thread change went across a process. setpriority,
+// sched_setscheduler, sched_setaffinity and ioprio_set each act on a single
+// thread, so they are applied to every TID in /proc/<pid>/task.
+#[derive(Debug, Default)]
+pub struct ThreadOutcome {
+    pub applied: usize,
+    // TIDs the change did not reach, with the reason.
+    pub failed: Vec<(u32, String)>,
+}
+
+impl ThreadOutcome {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+// Runs `apply` on every thread of `pid`. Threads that exit meanwhile are
+// skipped. Fails with the first error when no thread was changed, so a
+// plain EPERM still reads as PermissionDenied.
+fn for_each_thread(
+    pid: u32,
+    action: &str,
+    mut apply: impl FnMut(u32) -> io::Result<()>,
+) -> Result<ThreadOutcome, ProcessError> {
+    let tids = thread_ids(pid).map_err(|e| map_errno(e, pid, action))?;
+
+    let mut outcome = ThreadOutcome::default();
+    let mut first_error = None;
+    for tid in tids {
+        match apply(tid) {
+            Ok(()) => outcome.applied += 1,
+            Err(e) if e.raw_os_error() == Some(libc::ESRCH) => {}
+            Err(e) => {
+                outcome.failed.push((tid, e.to_string()));
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) if outcome.applied == 0 => Err(map_errno(e, pid, action)),
+        None if outcome.applied == 0 => Err(ProcessError::NotFound(pid)),
+        _ => Ok(outcome),
+    }
+}
+
+fn thread_ids(pid: u32) -> io::Result<Vec<u32>> {
+    std::fs::read_dir(format!("/proc/{}/task", pid))?
+        .map(|entry| {
+            let entry = entry?;
+            Ok(entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse().ok()))
+        })
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+// Turns a -1 return into errno.
+fn last_error(rc: impl Into<i64>) -> io::Result<()> {
+    if rc.into() < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn set_nice(pid: u32, nice: i32) -> Result<ThreadOutcome, ProcessError> {
+    if !(-20..=19).contains(&nice) {
+        return Err(ProcessError::ActionFailed(
+            "renice".to_string(),
+            pid,
+            format!("nice value {} is outside -20..19", nice),
+        ));
+    }
+
+    for_each_thread(pid, "renice", |tid| {
+        last_error(unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) })
+    })
+}
+
+pub fn set_scheduler(
+    pid: u32,
+    policy: SchedulingPolicy,
+    priority: i32,
+) -> Result<ThreadOutcome, ProcessError> {
+    if policy == SchedulingPolicy::Deadline {
+        return Err(ProcessError::ActionFailed(
+            "reschedule".to_string(),
+            pid,
+            "SCHED_DEADLINE needs runtime/deadline/period parameters".to_string(),
+        ));
+    }
+
+    let (min, max) = if policy.is_realtime() {
+        (1, 99)
+    } else {
+        (0, 0)
+    };
+    if !(min..=max).contains(&priority) {
+        return Err(ProcessError::ActionFailed(
+            "reschedule".to_string(),
+            pid,
+            format!(
+                "priority {} is outside {}..{} for {}",
+                priority,
+                min,
+                max,
+                policy.name()
+            ),
+        ));
+    }
+
+    let param = libc::sched_param {
+        sched_priority: priority,
+    };
+    for_each_thread(pid, "reschedule", |tid| {
+        last_error(unsafe { libc::sched_setscheduler(tid as libc::pid_t, policy.number(), &param) })
+    })
+}
+
+pub fn set_affinity(pid: u32, cpus: &[usize]) -> Result<ThreadOutcome, ProcessError> {
+    if cpus.is_empty() {
+        return Err(ProcessError::ActionFailed(
+            "pin".to_string(),
+            pid,
+            "CPU list is empty".to_string(),
+        ));
+    }
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &cpu in cpus {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return Err(ProcessError::ActionFailed(
+                "pin".to_string(),
+                pid,
+                format!("CPU {} does not exist", cpu),
+            ));
+        }
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+
+    for_each_thread(pid, "pin", |tid| {
+        last_error(unsafe {
+            libc::sched_setaffinity(
+                tid as libc::pid_t,
+                std::mem::size_of::<libc::cpu_set_t>(),
+                &set,
+            )
+        })
+    })
+}
+
+pub fn get_affinity(pid: u32) -> Result<Vec<usize>, ProcessError> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+
+    let rc = unsafe {
+        libc::sched_getaffinity(
+            pid as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &mut set,
+        )
+    };
+    if rc < 0 {
+        return Err(map_errno(
+            io::Error::last_os_error(),
+            pid,
+            "read affinity of",
+        ));
+    }
+
+    Ok((0..libc::CPU_SETSIZE as usize)
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect())
+}
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+
+pub fn get_io_priority(pid: u32) -> Result<IoPriority, ProcessError> {
+    let value =
+        unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as libc::c_int) };
+    if value < 0 {
+        return Err(map_errno(
+            io::Error::last_os_error(),
+            pid,
+            "read I/O priority of",
+        ));
+    }
+
+    let value = value as u32;
+    let class = match value >> IOPRIO_CLASS_SHIFT {
+        1 => IoClass::RealTime,
+        2 => IoClass::BestEffort,
+        3 => IoClass::Idle,
+        _ => IoClass::None,
+    };
+
+    Ok(IoPriority::new(class, (value & 0xff) as u8))
+}
+
+// IOPRIO_WHO_PGRP would reach other processes in the group as well, so this
+// goes thread by thread like the scheduler calls.
+pub fn set_io_priority(pid: u32, priority: IoPriority) -> Result<ThreadOutcome, ProcessError> {
+    if priority.level > 7 {
+        return Err(ProcessError::ActionFailed(
+            "set I/O priority of".to_string(),
+            pid,
+            format!("level {} is outside 0..7", priority.level),
+        ));
+    }
+
+    let class: u32 = match priority.class {
+        IoClass::None => 0,
+        IoClass::RealTime => 1,
+        IoClass::BestEffort => 2,
+        IoClass::Idle => 3,
+    };
+    let level = match priority.class {
+        IoClass::RealTime | IoClass::BestEffort => priority.level as u32,
+        IoClass::None | IoClass::Idle => 0,
+    };
+    let value = (class << IOPRIO_CLASS_SHIFT) | level;
+
+    for_each_thread(pid, "set I/O priority of", |tid| {
+        last_error(unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                tid as libc::c_int,
+                value as libc::c_int,
+            )
+        })
+    })
+}
@@ -0,0 +1,113 @@
+use oxyd_domain::errors::ProcessError;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+use super::parsers::parse_stat;
+
+// Highest real-time signal on Linux (SIGRTMAX).
+const MAX_SIGNAL: i32 = 64;
+
+// A handle on one specific process. Once opened, signals sent through it can
+// never reach a different process that later reuses the same PID.
+pub struct PidFd {
+    fd: OwnedFd,
+}
+
+impl PidFd {
+    // Returns `Ok(None)` when the kernel has no pidfd support (< 5.3).
+    pub fn open(pid: u32) -> Result<Option<Self>, ProcessError> {
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+
+        if fd < 0 {
+            let err = io::Error::last_os_error();
+            return match err.raw_os_error() {
+                Some(libc::ENOSYS) => Ok(None),
+                _ => Err(map_errno(err, pid, "open")),
+            };
+        }
+
+        Ok(Some(Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd as i32) },
+        }))
+    }
+
+    pub fn send_signal(&self, pid: u32, signal: i32) -> Result<(), ProcessError> {
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                self.fd.as_raw_fd(),
+                signal,
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+
+        if rc < 0 {
+            return Err(map_errno(io::Error::last_os_error(), pid, "signal"));
+        }
+        Ok(())
+    }
+}
+
+impl AsRawFd for PidFd {
+    fn as_raw_fd(&self) -> i32 {
+        self.fd.as_raw_fd()
+    }
+}
+
+pub fn validate_signal(signal: i32) -> Result<(), ProcessError> {
+    if (1..=MAX_SIGNAL).contains(&signal) {
+        Ok(())
+    } else {
+        Err(ProcessError::InvalidSignal(signal.to_string()))
+    }
+}
+
+// Delivers `signal` to `pid`, but only if it is still the process that
+// started at `expected_start` (clock ticks since boot). The pidfd is opened
+// first and the start time re-checked afterwards, so the signal cannot land
+// on a process that reused the PID in between.
+pub fn send_signal_checked(pid: u32, signal: i32, expected_start: u64) -> Result<(), ProcessError> {
+    validate_signal(signal)?;
+
+    match PidFd::open(pid)? {
+        Some(pidfd) => {
+            ensure_same_process(pid, expected_start)?;
+            pidfd.send_signal(pid, signal)
+        }
+        None => {
+            ensure_same_process(pid, expected_start)?;
+            kill(pid, signal)
+        }
+    }
+}
+
+pub fn kill(pid: u32, signal: i32) -> Result<(), ProcessError> {
+    let rc = unsafe { libc::kill(pid as libc::pid_t, signal) };
+    if rc < 0 {
+        return Err(map_errno(io::Error::last_os_error(), pid, "signal"));
+    }
+    Ok(())
+}
+
+// Start time of `pid` in clock ticks since boot, straight from /proc.
+pub fn read_start_ticks(pid: u32) -> Result<u64, ProcessError> {
+    let content = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .map_err(|_| ProcessError::NotFound(pid))?;
+    Ok(parse_stat(&content)?.starttime)
+}
+
+pub fn ensure_same_process(pid: u32, expected_start: u64) -> Result<(), ProcessError> {
+    if read_start_ticks(pid)? != expected_start {
+        return Err(ProcessError::IdentityMismatch(pid));
+    }
+    Ok(())
+}
+
+pub fn map_errno(err: io::Error, pid: u32, action: &str) -> ProcessError {
+    match err.raw_os_error() {
+        Some(libc::ESRCH) => ProcessError::NotFound(pid),
+        Some(libc::EPERM) | Some(libc::EACCES) => ProcessError::PermissionDenied(pid),
+        _ => ProcessError::ActionFailed(action.to_string(), pid, err.to_string()),
+    }
+}
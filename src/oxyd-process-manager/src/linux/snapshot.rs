@@ -0,0 +1,369 @@
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{Process, ProcessState, SchedulingPolicy, SnapshotOptions, ThreadInfo},
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+
+use super::container::ContainerResolver;
+use super::context::ScanContext;
+use super::cpu::{CpuMeasurement, calculate_cpu_usage_cached};
+use super::helpers::count_connections;
+use super::oom::read_oom;
+use super::parsers::{parse_cgroup_path, parse_io, parse_smaps_rollup, parse_stat, parse_status};
+use super::scheduling::get_io_priority;
+use super::users::UserResolver;
+
+// Everything needed to turn a PID into a `Process`. Cheap to clone so each
+// snapshot task can own one.
+#[derive(Clone)]
+pub struct ProcessReader {
+    cpu_cache: Arc<Mutex<HashMap<u32, CpuMeasurement>>>,
+    // Keyed by (PID, TID) so a process's entries can be dropped together.
+    thread_cpu_cache: Arc<Mutex<HashMap<(u32, u32), CpuMeasurement>>>,
+    users: Arc<UserResolver>,
+    containers: Arc<ContainerResolver>,
+}
+
+impl Default for ProcessReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessReader {
+    pub fn new() -> Self {
+        let users = Arc::new(UserResolver::new());
+        Self {
+            cpu_cache: Arc::new(Mutex::new(HashMap::new())),
+            thread_cpu_cache: Arc::new(Mutex::new(HashMap::new())),
+            containers: Arc::new(ContainerResolver::new(users.clone())),
+            users,
+        }
+    }
+
+    pub async fn user_name(&self, uid: u32) -> String {
+        self.users.user_name(uid).await
+    }
+
+    // Reads every PID concurrently, at most `options.max_concurrency` at a
+    // time. Processes that exit mid-scan are skipped.
+    pub async fn read_all(
+        &self,
+        pids: Vec<u32>,
+        context: Arc<ScanContext>,
+        options: &SnapshotOptions,
+    ) -> Vec<Process> {
+        let semaphore = Arc::new(Semaphore::new(options.max_concurrency.max(1)));
+        let mut tasks = JoinSet::new();
+
+        for pid in pids {
+            let reader = self.clone();
+            let context = context.clone();
+            let options = options.clone();
+            let semaphore = semaphore.clone();
+
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok()?;
+                reader.read(pid, &context, &options).await.ok()
+            });
+        }
+
+        let mut processes = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            if let Ok(Some(process)) = result {
+                processes.push(process);
+            }
+        }
+
+        self.prune_cpu_cache(&processes).await;
+        processes
+    }
+
+    pub async fn read(
+        &self,
+        pid: u32,
+        context: &ScanContext,
+        options: &SnapshotOptions,
+    ) -> Result<Process, ProcessError> {
+        let process_path = format!("/proc/{}", pid);
+
+        if !Path::new(&process_path).exists() {
+            return Err(ProcessError::NotFound(pid));
+        }
+
+        // Read and parse stat
+        let stat_path = format!("{}/stat", process_path);
+        let stat_content = fs::read_to_string(&stat_path)
+            .await
+            .map_err(|e| ProcessError::ReadFailed(pid, format!("Failed to read stat: {}", e)))?;
+        let stat_fields = parse_stat(&stat_content)?;
+        let name = stat_fields.comm.clone();
+
+        // Parse status
+        let status_path = format!("{}/status", process_path);
+        let status_info = fs::read_to_string(&status_path)
+            .await
+            .map_err(|e| ProcessError::ReadFailed(pid, format!("Failed to read status: {}", e)))?;
+        let status_fields = parse_status(&status_info);
+        let user = self.users.user_name(status_fields.uids.effective).await;
+        let group = self.users.group_name(status_fields.gids.effective).await;
+
+        // Get command and arguments
+        let (command, arguments) = if options.include_cmdline {
+            read_cmdline(&process_path, &name).await
+        } else {
+            (name.clone(), vec![])
+        };
+
+        // Get executable and working directory
+        let (executable_path, working_dir) = if options.include_paths {
+            let exe = fs::read_link(format!("{}/exe", process_path))
+                .await
+                .ok()
+                .map(|p| p.to_string_lossy().to_string());
+            let cwd = fs::read_link(format!("{}/cwd", process_path))
+                .await
+                .ok()
+                .map(|p| p.to_string_lossy().to_string());
+            (exe, cwd)
+        } else {
+            (None, None)
+        };
+
+        let cgroup = if options.include_cgroup {
+            fs::read_to_string(format!("{}/cgroup", process_path))
+                .await
+                .ok()
+                .and_then(|content| parse_cgroup_path(&content))
+        } else {
+            None
+        };
+        let container = if options.include_cgroup {
+            self.containers
+                .resolve(pid, cgroup.as_deref(), status_fields.uids.real)
+                .await
+        } else {
+            None
+        };
+
+        // Count file descriptors
+        let open_files = if options.include_fds {
+            count_entries(&format!("{}/fd", process_path)).await
+        } else {
+            0
+        };
+
+        // Count network connections
+        let open_connections = if options.include_connections {
+            count_connections(&format!("{}/net/tcp", process_path)).await
+                + count_connections(&format!("{}/net/tcp6", process_path)).await
+                + count_connections(&format!("{}/net/udp", process_path)).await
+                + count_connections(&format!("{}/net/udp6", process_path)).await
+        } else {
+            0
+        };
+
+        // Disk I/O counters (only readable for our own processes or as root)
+        let (disk_read_bytes, disk_write_bytes) = if options.include_io {
+            match fs::read_to_string(format!("{}/io", process_path)).await {
+                Ok(content) => parse_io(&content),
+                Err(_) => (0, 0),
+            }
+        } else {
+            (0, 0)
+        };
+
+        let io_priority = if options.include_io {
+            get_io_priority(pid).ok()
+        } else {
+            None
+        };
+
+        // Needs ptrace access, so only our own processes without root.
+        let memory_accounting = if options.include_smaps {
+            fs::read_to_string(format!("{}/smaps_rollup", process_path))
+                .await
+                .ok()
+                .map(|content| parse_smaps_rollup(&content))
+        } else {
+            None
+        };
+
+        let (oom_score, oom_score_adj) = if options.include_oom {
+            read_oom(&process_path).await
+        } else {
+            (None, None)
+        };
+
+        let state = map_state(stat_fields.state);
+
+        // Calculate CPU usage
+        let cpu_usage_percent =
+            calculate_cpu_usage_cached(pid, &stat_fields, context, &self.cpu_cache).await;
+
+        let start_time = context.start_time(stat_fields.starttime);
+        let rss_bytes = stat_fields.rss_pages * context.page_size;
+
+        Ok(Process {
+            pid,
+            ppid: if stat_fields.ppid > 0 {
+                Some(stat_fields.ppid)
+            } else {
+                None
+            },
+            name,
+            command,
+            arguments,
+            executable_path,
+            working_dir,
+            cgroup,
+            container,
+            state,
+            user,
+            group,
+            uids: status_fields.uids,
+            gids: status_fields.gids,
+            security: status_fields.security,
+            priority: stat_fields.priority,
+            nice: stat_fields.nice,
+            scheduling_policy: SchedulingPolicy::from_number(stat_fields.policy),
+            cpu_affinity: status_fields.cpus_allowed,
+            io_priority,
+            threads: status_fields.threads,
+            start_time,
+            start_ticks: stat_fields.starttime,
+            cpu_usage_percent,
+            memory_usage_bytes: rss_bytes,
+            memory_usage_percent: context.memory_percent(rss_bytes),
+            virtual_memory_bytes: status_fields.vm_size,
+            disk_write_bytes,
+            disk_read_bytes,
+            open_files,
+            open_connections,
+            memory_accounting,
+            oom_score,
+            oom_score_adj,
+        })
+    }
+
+    // Every thread of `pid` from /proc/<pid>/task, with CPU usage since the
+    // previous call for the same process.
+    pub async fn read_threads(
+        &self,
+        pid: u32,
+        context: &ScanContext,
+    ) -> Result<Vec<ThreadInfo>, ProcessError> {
+        let mut entries = fs::read_dir(format!("/proc/{}/task", pid))
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ProcessError::NotFound(pid),
+                _ => ProcessError::ReadFailed(pid, format!("Failed to list threads: {}", e)),
+            })?;
+        let mut threads = Vec::new();
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(tid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            // Threads can exit while we walk the directory.
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")).await else {
+                continue;
+            };
+            let Ok(fields) = parse_stat(&stat) else {
+                continue;
+            };
+
+            let cpu_usage_percent =
+                calculate_cpu_usage_cached((pid, tid), &fields, context, &self.thread_cpu_cache)
+                    .await;
+            threads.push(ThreadInfo {
+                tid,
+                name: fields.comm,
+                state: map_state(fields.state),
+                cpu_usage_percent,
+                last_cpu: fields.processor,
+                nice: fields.nice,
+                priority: fields.priority,
+            });
+        }
+
+        // Forget threads of this process that have exited.
+        let alive: std::collections::HashSet<u32> = threads.iter().map(|t| t.tid).collect();
+        self.thread_cpu_cache
+            .lock()
+            .await
+            .retain(|&(owner, tid), _| owner != pid || alive.contains(&tid));
+
+        threads.sort_by_key(|thread| thread.tid);
+        Ok(threads)
+    }
+
+    // Forget CPU samples for processes that no longer exist.
+    async fn prune_cpu_cache(&self, alive: &[Process]) {
+        let alive: std::collections::HashSet<u32> = alive.iter().map(|p| p.pid).collect();
+        self.cpu_cache
+            .lock()
+            .await
+            .retain(|pid, _| alive.contains(pid));
+        self.thread_cpu_cache
+            .lock()
+            .await
+            .retain(|(pid, _), _| alive.contains(pid));
+    }
+}
+
+async fn read_cmdline(process_path: &str, name: &str) -> (String, Vec<String>) {
+    match fs::read(format!("{}/cmdline", process_path)).await {
+        Ok(data) => {
+            let parts: Vec<String> = data
+                .split(|&b| b == 0)
+                .filter(|s| !s.is_empty())
+                .map(|s| String::from_utf8_lossy(s).to_string())
+                .collect();
+
+            if parts.is_empty() {
+                (format!("[{}]", name), vec![])
+            } else {
+                let cmd = parts[0].clone();
+                let args = parts.into_iter().skip(1).collect();
+                (cmd, args)
+            }
+        }
+        Err(_) => (format!("[{}]", name), vec![]),
+    }
+}
+
+async fn count_entries(path: &str) -> u32 {
+    match fs::read_dir(path).await {
+        Ok(mut entries) => {
+            let mut count = 0;
+            while let Ok(Some(_)) = entries.next_entry().await {
+                count += 1
+            }
+            count
+        }
+        Err(_) => 0,
+    }
+}
+
+pub fn map_state(state: char) -> ProcessState {
+    match state {
+        'R' => ProcessState::Running,
+        'S' => ProcessState::Sleeping,
+        'D' => ProcessState::Waiting,
+        'Z' => ProcessState::Zombie,
+        'T' => ProcessState::Stopped,
+        't' => ProcessState::Stopped,
+        'W' => ProcessState::Sleeping,
+        'X' | 'x' => ProcessState::Dead,
+        'K' => ProcessState::Waiting,
+        'P' => ProcessState::Waiting,
+        'I' => ProcessState::Idle,
+        _ => ProcessState::Unknown,
+    }
+}
@@ -0,0 +1,85 @@
+use oxyd_domain::errors::ProcessError;
+use std::time::Duration;
+use tokio::io::Interest;
+use tokio::io::unix::AsyncFd;
+
+use super::parsers::parse_stat;
+use super::signals::{PidFd, ensure_same_process, kill, validate_signal};
+
+// How often the /proc fallback checks whether the process is gone.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Watches one process until it exits. With pidfd support the kernel wakes us
+// the moment it exits; otherwise /proc is polled.
+pub enum ExitWatcher {
+    PidFd(AsyncFd<PidFd>),
+    Poll { pid: u32, start_ticks: u64 },
+}
+
+impl ExitWatcher {
+    // Attaches to `pid`, but only if it is still the process that started at
+    // `expected_start` (clock ticks since boot).
+    pub fn open(pid: u32, expected_start: u64) -> Result<Self, ProcessError> {
+        let watcher = match PidFd::open(pid)? {
+            Some(pidfd) => {
+                let fd = AsyncFd::with_interest(pidfd, Interest::READABLE).map_err(|e| {
+                    ProcessError::ActionFailed("watch".to_string(), pid, e.to_string())
+                })?;
+                ExitWatcher::PidFd(fd)
+            }
+            None => ExitWatcher::Poll {
+                pid,
+                start_ticks: expected_start,
+            },
+        };
+
+        ensure_same_process(pid, expected_start)?;
+        Ok(watcher)
+    }
+
+    pub fn send_signal(&self, pid: u32, signal: i32) -> Result<(), ProcessError> {
+        validate_signal(signal)?;
+        match self {
+            ExitWatcher::PidFd(fd) => fd.get_ref().send_signal(pid, signal),
+            // Without a pidfd the PID may have been reused since the last
+            // signal, so the start time is checked before every one.
+            ExitWatcher::Poll { start_ticks, .. } => {
+                ensure_same_process(pid, *start_ticks)?;
+                kill(pid, signal)
+            }
+        }
+    }
+
+    // Returns true if the process exited within `timeout`.
+    pub async fn wait(&self, timeout: Duration) -> bool {
+        match self {
+            ExitWatcher::PidFd(fd) => tokio::time::timeout(timeout, fd.readable())
+                .await
+                .is_ok_and(|ready| ready.is_ok()),
+            ExitWatcher::Poll { pid, start_ticks } => {
+                let deadline = tokio::time::Instant::now() + timeout;
+                loop {
+                    if has_exited(*pid, *start_ticks) {
+                        return true;
+                    }
+                    if tokio::time::Instant::now() >= deadline {
+                        return false;
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+// A process counts as exited once its PID is gone, reused, or a zombie
+// waiting to be reaped by its parent.
+fn has_exited(pid: u32, start_ticks: u64) -> bool {
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        Ok(content) => match parse_stat(&content) {
+            Ok(stat) => stat.starttime != start_ticks || stat.state == 'Z' || stat.state == 'X',
+            Err(_) => true,
+        },
+        Err(_) => true,
+    }
+}
@@ -0,0 +1,572 @@
+use chrono::Utc;
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{
+        ExitInfo, ManagedProcess, ManagedState, OutputLine, OutputStream, RestartPolicy, SpawnSpec,
+        StopOptions,
+    },
+};
+use std::collections::BTreeMap;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::process::ExitStatusExt;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+
+use super::cgroup;
+
+// Lines of stdout and stderr kept per managed command, together.
+const OUTPUT_TAIL_LINES: usize = 200;
+// Longer lines are cut, so a command that never prints a newline cannot
+// fill the tail on its own.
+const MAX_LINE_CHARS: usize = 1024;
+// Bytes of a line kept while reading it, enough for MAX_LINE_CHARS of any
+// UTF-8 text. The rest of the line is skipped as it arrives.
+const MAX_LINE_BYTES: usize = MAX_LINE_CHARS * 4;
+
+// Restarts back off from INITIAL_BACKOFF, doubling up to MAX_BACKOFF. A run
+// that lasted STABLE_RUN resets the backoff.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const STABLE_RUN: Duration = Duration::from_secs(10);
+
+// How long `exit_status` waits for a monitor to record an exit.
+const EXIT_STATUS_POLLS: usize = 20;
+const EXIT_STATUS_INTERVAL: Duration = Duration::from_millis(50);
+
+type Entries = Arc<Mutex<BTreeMap<u64, Entry>>>;
+
+struct Entry {
+    info: ManagedProcess,
+    // Talks to the monitor task while the entry is active.
+    control: mpsc::UnboundedSender<Control>,
+}
+
+// Requests to a monitor task, answered once they have taken effect.
+enum Control {
+    Stop(oneshot::Sender<()>),
+    Restart(oneshot::Sender<()>),
+}
+
+// Commands started by oxyd. Each runs in its own process group with a
+// monitor task that records its exit, restarts it according to its policy
+// and keeps a tail of its output. Children are killed when oxyd exits.
+pub struct Supervisor {
+    entries: Entries,
+    next_id: AtomicU64,
+    stop_options: StopOptions,
+}
+
+impl Supervisor {
+    pub fn new(stop_options: StopOptions) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(BTreeMap::new())),
+            next_id: AtomicU64::new(1),
+            stop_options,
+        }
+    }
+
+    // Starts `spec` and, once it is running, begins supervising it.
+    // `cgroup_root` is the cgroup v2 mount when `spec.cgroup` is set.
+    pub async fn spawn(
+        &self,
+        spec: SpawnSpec,
+        cgroup_root: Option<PathBuf>,
+    ) -> Result<ManagedProcess, ProcessError> {
+        let mut child = launch(&spec, cgroup_root.as_ref())
+            .await
+            .map_err(|e| ProcessError::SpawnFailed(spec.command_line(), e))?;
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let info = ManagedProcess {
+            id,
+            spec: spec.clone(),
+            state: ManagedState::Running,
+            pid: child.id(),
+            started_at: Some(Utc::now()),
+            restarts: 0,
+            next_restart: None,
+            last_exit: None,
+            error: None,
+            output: Vec::new(),
+        };
+
+        let (control, requests) = mpsc::unbounded_channel();
+        lock(&self.entries).insert(
+            id,
+            Entry {
+                info: info.clone(),
+                control,
+            },
+        );
+        tail_output(&self.entries, id, &mut child);
+        self.monitor(id, child, requests, spec, cgroup_root);
+        Ok(info)
+    }
+
+    pub fn list(&self) -> Vec<ManagedProcess> {
+        lock(&self.entries)
+            .values()
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Result<ManagedProcess, ProcessError> {
+        lock(&self.entries)
+            .get(&id)
+            .map(|entry| entry.info.clone())
+            .ok_or(ProcessError::ManagedNotFound(id))
+    }
+
+    // The managed command currently running as `pid`, if any.
+    pub fn find_by_pid(&self, pid: u32) -> Option<u64> {
+        lock(&self.entries)
+            .values()
+            .find(|entry| entry.info.pid == Some(pid))
+            .map(|entry| entry.info.id)
+    }
+
+    // How the run of `id` as `pid` ended. Its monitor records the exit
+    // right after reaping it, so this waits briefly for that to happen.
+    pub async fn exit_status(&self, id: u64, pid: u32) -> Option<ExitInfo> {
+        for _ in 0..EXIT_STATUS_POLLS {
+            match lock(&self.entries).get(&id) {
+                Some(entry) if entry.info.pid == Some(pid) => {}
+                Some(entry) => return entry.info.last_exit,
+                None => return None,
+            }
+            tokio::time::sleep(EXIT_STATUS_INTERVAL).await;
+        }
+        None
+    }
+
+    // Stops the command for good and returns the entry afterwards. Entries
+    // that are not active are returned unchanged.
+    pub async fn stop(&self, id: u64) -> Result<ManagedProcess, ProcessError> {
+        self.request(id, Control::Stop).await;
+        self.get(id)
+    }
+
+    // Restarts the command now: an active one through its monitor, an
+    // ended one with a new monitor. An ended entry is marked Starting under
+    // the lock first, so a concurrent restart cannot launch it twice.
+    pub async fn restart(&self, id: u64) -> Result<ManagedProcess, ProcessError> {
+        let spec = {
+            let mut entries = lock(&self.entries);
+            let entry = entries
+                .get_mut(&id)
+                .ok_or(ProcessError::ManagedNotFound(id))?;
+            match entry.info.state {
+                ManagedState::Starting => return Err(ProcessError::ManagedStarting(id)),
+                state if state.is_active() => None,
+                _ => {
+                    entry.info.state = ManagedState::Starting;
+                    Some(entry.info.spec.clone())
+                }
+            }
+        };
+        let Some(spec) = spec else {
+            self.request(id, Control::Restart).await;
+            return self.get(id);
+        };
+
+        let cgroup_root = match &spec.cgroup {
+            Some(_) => cgroup::cgroup_root(0, "spawn").map(Some),
+            None => Ok(None),
+        };
+        let launched = match &cgroup_root {
+            Ok(root) => launch(&spec, root.as_ref()).await,
+            Err(e) => Err(e.to_string()),
+        };
+        match launched {
+            Ok(mut child) => {
+                let (control, requests) = mpsc::unbounded_channel();
+                update(&self.entries, id, |entry| {
+                    entry.info.state = ManagedState::Running;
+                    entry.info.pid = child.id();
+                    entry.info.started_at = Some(Utc::now());
+                    entry.info.restarts += 1;
+                    entry.info.error = None;
+                    entry.control = control;
+                });
+                tail_output(&self.entries, id, &mut child);
+                self.monitor(id, child, requests, spec, cgroup_root.unwrap_or_default());
+                self.get(id)
+            }
+            Err(e) => {
+                update(&self.entries, id, |entry| {
+                    entry.info.state = ManagedState::Failed;
+                    entry.info.error = Some(e.clone());
+                });
+                Err(ProcessError::SpawnFailed(spec.command_line(), e))
+            }
+        }
+    }
+
+    pub fn remove(&self, id: u64) -> Result<(), ProcessError> {
+        let mut entries = lock(&self.entries);
+        match entries.get(&id) {
+            None => Err(ProcessError::ManagedNotFound(id)),
+            Some(entry) if entry.info.state.is_active() => Err(ProcessError::ManagedActive(id)),
+            Some(_) => {
+                entries.remove(&id);
+                Ok(())
+            }
+        }
+    }
+
+    // Sends a request to the monitor of `id` and waits for it to be done.
+    // Does nothing if no monitor is running.
+    async fn request(&self, id: u64, make: impl FnOnce(oneshot::Sender<()>) -> Control) {
+        let (done, finished) = oneshot::channel();
+        let sent = lock(&self.entries)
+            .get(&id)
+            .filter(|entry| entry.info.state.is_active())
+            .is_some_and(|entry| entry.control.send(make(done)).is_ok());
+        if sent {
+            let _ = finished.await;
+        }
+    }
+
+    fn monitor(
+        &self,
+        id: u64,
+        child: Child,
+        requests: mpsc::UnboundedReceiver<Control>,
+        spec: SpawnSpec,
+        cgroup_root: Option<PathBuf>,
+    ) {
+        let monitor = Monitor {
+            entries: self.entries.clone(),
+            id,
+            spec,
+            cgroup_root,
+            stop_options: self.stop_options,
+            requests,
+        };
+        tokio::spawn(monitor.run(child));
+    }
+}
+
+// Owns one managed command from its first start until it ends for good.
+struct Monitor {
+    entries: Entries,
+    id: u64,
+    spec: SpawnSpec,
+    cgroup_root: Option<PathBuf>,
+    stop_options: StopOptions,
+    requests: mpsc::UnboundedReceiver<Control>,
+}
+
+impl Monitor {
+    async fn run(mut self, mut child: Child) {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let started = Instant::now();
+            let mut restart_now = None;
+
+            let exit = tokio::select! {
+                status = child.wait() => exit_info(status),
+                Some(request) = self.requests.recv() => {
+                    let exit = terminate(&mut child, &self.stop_options).await;
+                    match request {
+                        Control::Stop(done) => return self.finish(ManagedState::Stopped, exit, done),
+                        Control::Restart(done) => restart_now = Some(done),
+                    }
+                    exit
+                }
+            };
+
+            self.update(|info| {
+                info.pid = None;
+                info.last_exit = exit;
+            });
+
+            if restart_now.is_none() {
+                if !self.spec.restart.restarts_after(exit.as_ref()) {
+                    self.update(|info| info.state = ManagedState::Exited);
+                    return;
+                }
+                if started.elapsed() >= STABLE_RUN {
+                    backoff = INITIAL_BACKOFF;
+                }
+            }
+
+            // Start it again, waiting out the backoff unless asked to restart
+            // right away. Failed starts back off too.
+            child = loop {
+                if restart_now.is_none() {
+                    let next = Utc::now() + backoff;
+                    self.update(|info| {
+                        info.state = ManagedState::BackingOff;
+                        info.next_restart = Some(next);
+                    });
+
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        Some(request) = self.requests.recv() => match request {
+                            Control::Stop(done) => return self.finish(ManagedState::Stopped, None, done),
+                            Control::Restart(done) => restart_now = Some(done),
+                        }
+                    }
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+
+                let result = launch(&self.spec, self.cgroup_root.as_ref()).await;
+                let done = restart_now.take();
+                match result {
+                    Ok(mut child) => {
+                        self.update(|info| {
+                            info.state = ManagedState::Running;
+                            info.pid = child.id();
+                            info.started_at = Some(Utc::now());
+                            info.restarts += 1;
+                            info.next_restart = None;
+                            info.error = None;
+                        });
+                        tail_output(&self.entries, self.id, &mut child);
+                        if let Some(done) = done {
+                            let _ = done.send(());
+                        }
+                        break child;
+                    }
+                    Err(e) => {
+                        let give_up = self.spec.restart == RestartPolicy::Never;
+                        self.update(|info| {
+                            info.error = Some(e);
+                            if give_up {
+                                info.state = ManagedState::Failed;
+                                info.next_restart = None;
+                            }
+                        });
+                        if let Some(done) = done {
+                            let _ = done.send(());
+                        }
+                        if give_up {
+                            return;
+                        }
+                    }
+                }
+            };
+        }
+    }
+
+    fn finish(&self, state: ManagedState, exit: Option<ExitInfo>, done: oneshot::Sender<()>) {
+        self.update(|info| {
+            info.state = state;
+            info.pid = None;
+            info.next_restart = None;
+            if exit.is_some() {
+                info.last_exit = exit;
+            }
+        });
+        let _ = done.send(());
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ManagedProcess)) {
+        update(&self.entries, self.id, |entry| change(&mut entry.info));
+    }
+}
+
+// Runs `spec` in a new process group, with stdout and stderr piped.
+async fn launch(spec: &SpawnSpec, cgroup_root: Option<&PathBuf>) -> Result<Child, String> {
+    let mut command = Command::new(&spec.command);
+    command
+        .args(&spec.args)
+        .envs(spec.env.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .kill_on_drop(true);
+    if let Some(cwd) = &spec.cwd {
+        command.current_dir(cwd);
+    }
+
+    // The cgroup is joined, and the nice value set, in the child between
+    // fork and exec: the command never runs in oxyd's cgroup or at its
+    // priority, and neither does anything it forks.
+    let procs = match (cgroup_root, &spec.cgroup) {
+        (Some(root), Some(path)) => Some(
+            cgroup::open_procs(root, path)
+                .await
+                .map_err(|e| e.to_string())?,
+        ),
+        _ => None,
+    };
+    let procs_fd = procs.as_ref().map(|file| file.as_raw_fd());
+    let nice = spec.nice;
+    if procs_fd.is_some() || nice.is_some() {
+        unsafe {
+            command.pre_exec(move || {
+                // "0" moves the writing process.
+                if let Some(fd) = procs_fd
+                    && libc::write(fd, b"0".as_ptr().cast(), 1) == -1
+                {
+                    return Err(io::Error::last_os_error());
+                }
+                if let Some(nice) = nice
+                    && libc::setpriority(libc::PRIO_PROCESS, 0, nice) == -1
+                {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    command.spawn().map_err(|e| e.to_string())
+}
+
+// Sends the stop signal to the child's process group, then SIGKILL once
+// the grace period is over.
+async fn terminate(child: &mut Child, options: &StopOptions) -> Option<ExitInfo> {
+    if let Some(pid) = child.id() {
+        unsafe { libc::kill(-(pid as i32), options.signal.number()) };
+
+        let grace_period = Duration::from_millis(options.grace_period_ms);
+        if let Ok(status) = tokio::time::timeout(grace_period, child.wait()).await {
+            return exit_info(status);
+        }
+        unsafe { libc::kill(-(pid as i32), libc::SIGKILL) };
+    }
+    exit_info(child.wait().await)
+}
+
+fn exit_info(status: io::Result<ExitStatus>) -> Option<ExitInfo> {
+    status.ok().map(|status| ExitInfo {
+        code: status.code(),
+        signal: status.signal(),
+        at: Utc::now(),
+    })
+}
+
+fn tail_output(entries: &Entries, id: u64, child: &mut Child) {
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(read_lines(
+            entries.clone(),
+            id,
+            OutputStream::Stdout,
+            stdout,
+        ));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(read_lines(
+            entries.clone(),
+            id,
+            OutputStream::Stderr,
+            stderr,
+        ));
+    }
+}
+
+async fn read_lines(
+    entries: Entries,
+    id: u64,
+    stream: OutputStream,
+    reader: impl AsyncRead + Unpin,
+) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+
+    loop {
+        let chunk = match reader.fill_buf().await {
+            Ok([]) | Err(_) => break,
+            Ok(chunk) => chunk,
+        };
+        let (length, complete) = match chunk.iter().position(|&b| b == b'\n') {
+            Some(end) => (end + 1, true),
+            None => (chunk.len(), false),
+        };
+        let room = MAX_LINE_BYTES.saturating_sub(line.len());
+        line.extend_from_slice(&chunk[..length.min(room)]);
+        reader.consume(length);
+
+        if complete {
+            push_line(&entries, id, stream, &line);
+            line.clear();
+        }
+    }
+
+    // Output that did not end in a newline.
+    if !line.is_empty() {
+        push_line(&entries, id, stream, &line);
+    }
+}
+
+fn push_line(entries: &Entries, id: u64, stream: OutputStream, line: &[u8]) {
+    let text: String = String::from_utf8_lossy(line)
+        .trim_end_matches(['\n', '\r'])
+        .chars()
+        .take(MAX_LINE_CHARS)
+        .collect();
+    update(entries, id, |entry| {
+        let output = &mut entry.info.output;
+        output.push(OutputLine { stream, text });
+        if output.len() > OUTPUT_TAIL_LINES {
+            output.drain(..output.len() - OUTPUT_TAIL_LINES);
+        }
+    });
+}
+
+fn lock(entries: &Entries) -> std::sync::MutexGuard<'_, BTreeMap<u64, Entry>> {
+    entries.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Changes an entry in place; removed entries are left alone.
+fn update(entries: &Entries, id: u64, change: impl FnOnce(&mut Entry)) {
+    if let Some(entry) = lock(entries).get_mut(&id) {
+        change(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn wait_until_ended(supervisor: &Supervisor, id: u64) {
+        for _ in 0..100 {
+            if !supervisor.get(id).unwrap().state.is_active() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("managed process #{} did not end", id);
+    }
+
+    #[tokio::test]
+    async fn restart_rejects_an_entry_that_is_starting() {
+        let supervisor = Supervisor::new(StopOptions::default());
+        let spawned = supervisor
+            .spawn(SpawnSpec::new("true", Vec::new()), None)
+            .await
+            .unwrap();
+        wait_until_ended(&supervisor, spawned.id).await;
+
+        // As left by a restart that is still launching the command.
+        update(&supervisor.entries, spawned.id, |entry| {
+            entry.info.state = ManagedState::Starting;
+        });
+        assert!(matches!(
+            supervisor.restart(spawned.id).await,
+            Err(ProcessError::ManagedStarting(id)) if id == spawned.id
+        ));
+        assert!(matches!(
+            supervisor.remove(spawned.id),
+            Err(ProcessError::ManagedActive(_))
+        ));
+
+        update(&supervisor.entries, spawned.id, |entry| {
+            entry.info.state = ManagedState::Exited;
+        });
+        assert_eq!(supervisor.restart(spawned.id).await.unwrap().restarts, 1);
+        wait_until_ended(&supervisor, spawned.id).await;
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+const PASSWD_PATH: &str = "/etc/passwd";
+const GROUP_PATH: &str = "/etc/group";
+
+// How often the passwd/group files are stat'ed for changes.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+// Scratch space for getpwuid_r/getgrgid_r, grown up to the maximum on ERANGE.
+const NSS_BUFFER_SIZE: usize = 4096;
+const MAX_NSS_BUFFER_SIZE: usize = 1 << 20;
+
+#[derive(Default)]
+struct UserCache {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+    homes: HashMap<u32, PathBuf>,
+    passwd_mtime: Option<SystemTime>,
+    group_mtime: Option<SystemTime>,
+    last_checked: Option<Instant>,
+}
+
+// Resolves numeric UIDs/GIDs to names, and UIDs to home directories. The local passwd and group files are
+// loaded up front; anything missing from them (LDAP, sssd, systemd-homed...)
+// falls back to NSS and is cached as well.
+pub struct UserResolver {
+    cache: Mutex<UserCache>,
+}
+
+impl Default for UserResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserResolver {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(UserCache::default()),
+        }
+    }
+
+    // The cache lock is never held across I/O: NSS lookups can be slow
+    // (LDAP, sssd) and a concurrent scan resolves many processes at once.
+    pub async fn user_name(&self, uid: u32) -> String {
+        self.refresh_if_changed().await;
+
+        if let Some(name) = self.cache.lock().await.users.get(&uid) {
+            return name.clone();
+        }
+
+        let name = lookup_passwd(uid, |pwd| pwd.pw_name)
+            .await
+            .unwrap_or_else(|| uid.to_string());
+        self.cache.lock().await.users.entry(uid).or_insert(name).clone()
+    }
+
+    pub async fn home_dir(&self, uid: u32) -> Option<PathBuf> {
+        self.refresh_if_changed().await;
+
+        if let Some(home) = self.cache.lock().await.homes.get(&uid) {
+            return Some(home.clone());
+        }
+
+        let home = PathBuf::from(lookup_passwd(uid, |pwd| pwd.pw_dir).await?);
+        Some(self.cache.lock().await.homes.entry(uid).or_insert(home).clone())
+    }
+
+    pub async fn group_name(&self, gid: u32) -> String {
+        self.refresh_if_changed().await;
+
+        if let Some(name) = self.cache.lock().await.groups.get(&gid) {
+            return name.clone();
+        }
+
+        let name = lookup_group(gid).await.unwrap_or_else(|| gid.to_string());
+        self.cache.lock().await.groups.entry(gid).or_insert(name).clone()
+    }
+
+    async fn refresh_if_changed(&self) {
+        let (passwd_mtime, group_mtime) = {
+            let mut cache = self.cache.lock().await;
+            if let Some(checked) = cache.last_checked
+                && checked.elapsed() < RECHECK_INTERVAL
+            {
+                return;
+            }
+            cache.last_checked = Some(Instant::now());
+            (cache.passwd_mtime, cache.group_mtime)
+        };
+
+        let passwd = reload_if_changed(PASSWD_PATH, passwd_mtime).await;
+        let group = reload_if_changed(GROUP_PATH, group_mtime).await;
+
+        let mut cache = self.cache.lock().await;
+        if let Some((mtime, content)) = passwd {
+            cache.users = parse_id_file(&content);
+            cache.homes = parse_homes(&content);
+            cache.passwd_mtime = mtime;
+        }
+        if let Some((mtime, content)) = group {
+            cache.groups = parse_id_file(&content);
+            cache.group_mtime = mtime;
+        }
+    }
+}
+
+// The file's mtime and content, or None when it is unchanged since `known`.
+// An unreadable file reads as empty.
+async fn reload_if_changed(
+    path: &str,
+    known: Option<SystemTime>,
+) -> Option<(Option<SystemTime>, String)> {
+    let mtime = modified_time(path).await;
+    if known.is_some() && mtime == known {
+        return None;
+    }
+
+    let content = fs::read_to_string(path).await.unwrap_or_default();
+    Some((mtime, content))
+}
+
+async fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).await.ok().and_then(|m| m.modified().ok())
+}
+
+// Both /etc/passwd and /etc/group are `name:password:id:...`.
+fn parse_id_file(content: &str) -> HashMap<u32, String> {
+    let mut entries = HashMap::new();
+
+    for line in content.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split(':');
+        let name = fields.next();
+        let id = fields.nth(1).and_then(|s| s.parse::<u32>().ok());
+
+        if let (Some(name), Some(id)) = (name, id)
+            && !name.is_empty()
+        {
+            entries.entry(id).or_insert_with(|| name.to_string());
+        }
+    }
+
+    entries
+}
+
+// passwd is `name:password:uid:gid:gecos:home:shell`.
+fn parse_homes(content: &str) -> HashMap<u32, PathBuf> {
+    let mut homes = HashMap::new();
+
+    for line in content.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(':').collect();
+        if let (Some(uid), Some(home)) = (fields.get(2), fields.get(5))
+            && let Ok(uid) = uid.parse::<u32>()
+            && !home.is_empty()
+        {
+            homes.entry(uid).or_insert_with(|| PathBuf::from(home));
+        }
+    }
+
+    homes
+}
+
+// One field of the NSS passwd entry of `uid`.
+async fn lookup_passwd(uid: u32, field: fn(&libc::passwd) -> *mut libc::c_char) -> Option<String> {
+    tokio::task::spawn_blocking(move || {
+        lookup_with_buffer(|buf| {
+            let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+            let rc = unsafe {
+                libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+
+            if rc != 0 {
+                return Err(rc);
+            }
+            if result.is_null() {
+                return Ok(None);
+            }
+
+            let value = unsafe { CStr::from_ptr(field(&pwd)) };
+            Ok(Some(value.to_string_lossy().to_string()))
+        })
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+async fn lookup_group(gid: u32) -> Option<String> {
+    tokio::task::spawn_blocking(move || {
+        lookup_with_buffer(|buf| {
+            let mut grp: libc::group = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::group = std::ptr::null_mut();
+
+            let rc = unsafe {
+                libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+
+            if rc != 0 {
+                return Err(rc);
+            }
+            if result.is_null() {
+                return Ok(None);
+            }
+
+            let name = unsafe { CStr::from_ptr(grp.gr_name) };
+            Ok(Some(name.to_string_lossy().to_string()))
+        })
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+// Runs a getpwuid_r/getgrgid_r call with a scratch buffer, doubling it
+// while the entry does not fit (ERANGE); large group lists need more than
+// the initial size.
+fn lookup_with_buffer(
+    mut lookup: impl FnMut(&mut [libc::c_char]) -> Result<Option<String>, libc::c_int>,
+) -> Option<String> {
+    let mut size = NSS_BUFFER_SIZE;
+    loop {
+        let mut buf = vec![0 as libc::c_char; size];
+        match lookup(&mut buf) {
+            Ok(name) => return name,
+            Err(libc::ERANGE) if size < MAX_NSS_BUFFER_SIZE => size *= 2,
+            Err(_) => return None,
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use oxyd_domain::{
+    errors::ProcessError,
+    models::{Process, ProcessManagerConfig, SpawnSpec},
+};
+
+// What an action does to its target, which decides the config flag that
+// governs it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Signal,
+    Priority,
+}
+
+// One entry of `protected_processes`. Plain entries are globs over the
+// process name; prefixed entries match other properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectedRule {
+    Name(String),       // "sshd", "systemd*"
+    Executable(String), // "exe:/usr/sbin/*"
+    Uid(u32),           // "uid:0"
+    Cgroup(String),     // "cgroup:/system.slice/*"
+}
+
+impl ProtectedRule {
+    pub fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            return None;
+        }
+
+        if let Some(path) = entry.strip_prefix("exe:") {
+            Some(ProtectedRule::Executable(path.to_string()))
+        } else if let Some(uid) = entry.strip_prefix("uid:") {
+            uid.trim().parse().ok().map(ProtectedRule::Uid)
+        } else if let Some(cgroup) = entry.strip_prefix("cgroup:") {
+            Some(ProtectedRule::Cgroup(cgroup.to_string()))
+        } else {
+            Some(ProtectedRule::Name(entry.to_string()))
+        }
+    }
+
+    fn matches(&self, process: &Process) -> bool {
+        match self {
+            ProtectedRule::Name(pattern) => glob_match(pattern, &process.name),
+            ProtectedRule::Executable(pattern) => process
+                .executable_path
+                .as_deref()
+                .is_some_and(|exe| glob_match(pattern, exe)),
+            ProtectedRule::Uid(uid) => process.uids.effective == *uid || process.uids.real == *uid,
+            ProtectedRule::Cgroup(pattern) => process.cgroup.as_deref().is_some_and(|path| {
+                glob_match(pattern, path) || path.starts_with(&format!("{}/", pattern))
+            }),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            ProtectedRule::Name(pattern) => format!("name matches protected rule '{}'", pattern),
+            ProtectedRule::Executable(pattern) => {
+                format!("executable matches protected rule 'exe:{}'", pattern)
+            }
+            ProtectedRule::Uid(uid) => format!("owner matches protected rule 'uid:{}'", uid),
+            ProtectedRule::Cgroup(pattern) => {
+                format!("cgroup matches protected rule 'cgroup:{}'", pattern)
+            }
+        }
+    }
+}
+
+// Every process action goes through `ActionPolicy` before any syscall is
+// made. Refusals come back as `ProcessError::NotAllowed` naming the rule.
+#[derive(Debug, Clone)]
+pub struct ActionPolicy {
+    allow_kill: bool,
+    allow_priority_change: bool,
+    allow_spawn: bool,
+    require_sudo: bool,
+    protected: Vec<ProtectedRule>,
+}
+
+impl ActionPolicy {
+    pub fn new(protected_processes: &[String]) -> Self {
+        Self {
+            allow_kill: true,
+            allow_priority_change: true,
+            allow_spawn: true,
+            require_sudo: true,
+            protected: protected_processes
+                .iter()
+                .filter_map(|entry| ProtectedRule::parse(entry))
+                .collect(),
+        }
+    }
+
+    pub fn from_config(config: &ProcessManagerConfig) -> Self {
+        Self {
+            allow_kill: config.allow_kill,
+            allow_priority_change: config.allow_priority_change,
+            allow_spawn: config.allow_spawn,
+            require_sudo: config.require_sudo,
+            ..Self::new(&config.protected_processes)
+        }
+    }
+
+    // Checks that need nothing but the PID. Run these before reading /proc
+    // so a refused action never touches the target.
+    pub fn check_pid(&self, pid: u32, kind: ActionKind, action: &str) -> Result<(), ProcessError> {
+        let refuse = |reason: String| Err(ProcessError::NotAllowed(action.to_string(), pid, reason));
+
+        if pid == 1 {
+            return refuse("PID 1 is always protected".to_string());
+        }
+        if pid == std::process::id() {
+            return refuse("oxyd does not act on itself".to_string());
+        }
+
+        match kind {
+            ActionKind::Signal if !self.allow_kill => {
+                return refuse("signals are disabled (allow_kill = false)".to_string());
+            }
+            ActionKind::Priority if !self.allow_priority_change => {
+                return refuse(
+                    "priority changes are disabled (allow_priority_change = false)".to_string(),
+                );
+            }
+            _ => {}
+        }
+
+        if self.require_sudo && unsafe { libc::geteuid() } != 0 {
+            return refuse("require_sudo is set and oxyd is not running as root".to_string());
+        }
+
+        Ok(())
+    }
+
+    // Matches the process against the protected rules.
+    pub fn check_process(
+        &self,
+        process: &Process,
+        kind: ActionKind,
+        action: &str,
+    ) -> Result<(), ProcessError> {
+        self.check_pid(process.pid, kind, action)?;
+
+        match self.protected.iter().find(|rule| rule.matches(process)) {
+            Some(rule) => Err(ProcessError::NotAllowed(
+                action.to_string(),
+                process.pid,
+                rule.describe(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // Cgroups oxyd may move processes into or set limits on: never the root,
+    // nor one covered by a `cgroup:` protected rule. `pid` is only used in
+    // the error.
+    pub fn check_cgroup(&self, pid: u32, cgroup: &str, action: &str) -> Result<(), ProcessError> {
+        let refuse = |reason: String| Err(ProcessError::NotAllowed(action.to_string(), pid, reason));
+
+        if cgroup == "/" {
+            return refuse("the root cgroup is always protected".to_string());
+        }
+
+        let protected = self.protected.iter().find(|rule| match rule {
+            ProtectedRule::Cgroup(pattern) => {
+                glob_match(pattern, cgroup) || cgroup.starts_with(&format!("{}/", pattern))
+            }
+            _ => false,
+        });
+        match protected {
+            Some(rule) => refuse(format!("target {}", rule.describe())),
+            None => Ok(()),
+        }
+    }
+
+    // Launching a command. Its cgroup, if any, is held to the same rules as
+    // moving a process there; `spec.cgroup` must already be normalized.
+    pub fn check_spawn(&self, spec: &SpawnSpec) -> Result<(), ProcessError> {
+        let refuse = |reason: String| Err(ProcessError::SpawnNotAllowed(spec.command.clone(), reason));
+
+        if !self.allow_spawn {
+            return refuse("spawning is disabled (allow_spawn = false)".to_string());
+        }
+        if self.require_sudo && unsafe { libc::geteuid() } != 0 {
+            return refuse("require_sudo is set and oxyd is not running as root".to_string());
+        }
+
+        match spec.cgroup.as_deref().map(|cgroup| self.check_cgroup(0, cgroup, "spawn")) {
+            Some(Err(ProcessError::NotAllowed(_, _, reason))) => refuse(reason),
+            Some(Err(e)) => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Shell-style glob supporting `*` and `?`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn literal_patterns_match_exactly() {
+        assert!(glob_match("sshd", "sshd"));
+        assert!(!glob_match("sshd", "sshd2"));
+        assert!(!glob_match("sshd", "ssh"));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn star_matches_any_run() {
+        assert!(glob_match("systemd*", "systemd"));
+        assert!(glob_match("systemd*", "systemd-journald"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("/usr/sbin/*", "/usr/sbin/nginx"));
+        assert!(glob_match("*.service", "/system.slice/sshd.service"));
+        assert!(!glob_match("*.service", "/system.slice/sshd.scope"));
+    }
+
+    #[test]
+    fn star_backtracks() {
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("*ab", "aab"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(glob_match("**x", "yyx"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_char() {
+        assert!(glob_match("kworker/?", "kworker/0"));
+        assert!(!glob_match("kworker/?", "kworker/"));
+        assert!(!glob_match("kworker/?", "kworker/10"));
+        assert!(glob_match("é?", "éa"));
+    }
+}
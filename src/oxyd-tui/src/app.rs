@@ -1,10 +1,31 @@
-use crate::history::MetricsHistory;
+use crate::commands::{
+    BatchOutcome, ManagedCommand, ProcessBatch, ProcessCommand, ProcessRequest, ProcessTarget,
+};
+use crate::groups::{GroupRow, group_by_container};
+use crate::history::{MetricsHistory, ProcessHistory};
 use crate::notifications::NotificationManager;
 use crate::tabs::Tab;
+use crate::tree::{TreeRow, build_tree, subtree};
+use crate::ui::widgets::format_duration;
+use chrono::Local;
 use oxyd_domain::traits::ProcessManager;
-use oxyd_domain::{Process, SystemMetrics};
+use oxyd_domain::{
+    AlertEvent, AlertMessage, AlertRule, AlertSeverity, AlertState, AuditRecord, CgroupLimit,
+    DEFAULT_ALERT_THRESHOLD_PERCENT, IoPriority, LimitChange, LimitValue, ManagedProcess, Process,
+    ProcessAction, ProcessDetails, ProcessExit, ProcessIdentity, ResourceKind, SchedulingPolicy,
+    SpawnSpec, SystemMetrics, capability_from_name, format_cpu_list, parse_cpu_list,
+    threshold_alert_rules,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
+// Process table sort columns, indexed by `AppState::sort_column`. The ones
+// after `Memory` need memory accounting (smaps_rollup) to be on.
+pub const SORT_COLUMNS: [&str; 7] = ["PID", "Name", "CPU%", "Memory", "PSS", "USS", "Swap"];
+const BASIC_SORT_COLUMNS: usize = 4;
+// Filter `E` toggles: processes with any effective capability.
+const ELEVATED_FILTER: &str = "cap:any";
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputMode {
     Normal,
@@ -12,6 +33,15 @@ pub enum InputMode {
     EditCpuThreshold,
     EditMemoryThreshold,
     EditDiskThreshold,
+    EditNice,
+    EditScheduler,
+    EditAffinity,
+    EditIoPriority,
+    EditCgroup,
+    EditCgroupLimit,
+    EditLimit,
+    EditOomScoreAdj,
+    SpawnCommand,
 }
 
 #[derive(Debug, Clone)]
@@ -29,23 +59,72 @@ pub enum Action {
     Home,
     End,
     SortByColumn(usize),
+    NextSortColumn,
+    PreviousSortColumn,
+    ToggleMemoryAccounting,
 
     LoadProcessList,
     ProcessListLoaded(Vec<Process>),
     SelectProcess(usize),
-    KillSelectedProcess,
-    SuspendSelectedProcess,
-    ContinueSelectedProcess,
-    TerminateSelectedProcess,
+    ToggleMarkSelected,
+    MarkAllFiltered,
+    MarkSubtree,
+    ClearMarks,
+    ToggleTreeView,
+    ToggleGroupByContainer,
+    OpenProcessDetails,
+    CloseProcessDetails,
+    ToggleThreadView,
+    LoadProcessDetails(ProcessIdentity),
+    ProcessDetailsLoaded(Box<ProcessDetails>),
+    ProcessDetailsFailed(String),
+    CollapseSelected,
+    ExpandSelected,
+    // Act on the marked rows, or the selected row when nothing is marked.
+    // Batches and destructive commands open the confirmation dialog; the
+    // rest are submitted straight away.
+    RequestProcessCommand(ProcessCommand),
+    ConfirmProcessCommand,
+    CancelProcessCommand,
+    // Bound to process identities; executed by the event loop.
+    ExecuteProcessCommand(ProcessRequest),
+    ExecuteProcessBatch(ProcessBatch),
+    ProcessBatchComplete(String, Vec<BatchOutcome>),
+    ToggleDryRun,
+    // A dry-run action, written to the audit log by the event loop.
+    RecordDryRun(u32, ProcessAction),
+    // `w` / `W`: start or stop watching the selected process; `W` also
+    // sends its exit to the configured alert channels.
+    WatchSelected { alert_channels: bool },
+    WatchProcess(ProcessTarget),
+    ProcessExited(ProcessIdentity, Result<ProcessExit, String>),
+    SendAlert(AlertMessage),
+    AlertDeliveryFailed(String),
+    ProcessActionProgress(String),
     ProcessActionComplete(String),
     ProcessActionFailed(String),
 
+    LoadAuditLog,
+    AuditLogLoaded(Vec<AuditRecord>),
+    // Why audit records are not being written, or None once they are.
+    AuditLogStatus(Option<String>),
+
+    LoadManagedProcesses,
+    ManagedProcessesLoaded(Vec<ManagedProcess>),
+    // Dry-run aware; executed by the event loop as ExecuteManagedCommand.
+    RequestManagedCommand(ManagedCommand),
+    ExecuteManagedCommand(ManagedCommand),
+
     ToggleHelp,
 
     MarkAllNotificationsRead,
     ClearAllNotifications,
 
     CheckAlerts(SystemMetrics),
+    // A rule changed state; evaluated by the event loop.
+    AlertStateChanged(AlertEvent),
+    // A threshold was edited; the event loop hands the rule to its engine.
+    SetAlertRule(AlertRule),
 
     EnterInputMode(InputMode),
     ExitInputMode,
@@ -54,6 +133,26 @@ pub enum Action {
     InputSubmit,
 
     ClearFilter,
+    ToggleElevatedFilter,
+}
+
+// The detail pane opened with Enter on a process row. It stays bound to the
+// process it was opened for, whatever the cursor does afterwards.
+#[derive(Debug, Clone)]
+pub struct ProcessDetailView {
+    pub target: ProcessIdentity,
+    pub details: Option<Box<ProcessDetails>>,
+    pub error: Option<String>,
+    pub scroll: usize,
+    // List threads as table rows (hottest first) instead of the sections.
+    pub show_threads: bool,
+}
+
+// A process the user asked to be told about when it exits.
+#[derive(Debug, Clone)]
+pub struct Watch {
+    pub target: ProcessTarget,
+    pub alert_channels: bool,
 }
 
 pub struct AppState {
@@ -65,6 +164,8 @@ pub struct AppState {
     pub selected_process: Option<usize>,
     pub sort_column: usize,
     pub sort_ascending: bool,
+    // Whether process list loads read smaps_rollup for PSS, USS and swap.
+    pub memory_accounting: bool,
     pub update_count: u64,
 
     pub process_list: Vec<Process>,
@@ -79,12 +180,42 @@ pub struct AppState {
     pub cpu_alert_threshold: f32,
     pub memory_alert_threshold: f32,
     pub disk_alert_threshold: f32,
-    pub last_cpu_alert: Option<f32>,
-    pub last_memory_alert: Option<f32>,
-    pub last_disk_alert: Option<f32>,
+    // Rules currently pending or firing, by rule id.
+    pub active_alerts: BTreeMap<String, AlertEvent>,
 
     pub input_mode: InputMode,
     pub input_buffer: String,
+
+    pub marked_processes: HashSet<ProcessIdentity>,
+
+    // Tree mode: `process_tree[i]` describes `filtered_process_list[i]`.
+    pub tree_view: bool,
+    pub process_tree: Vec<TreeRow>,
+    pub collapsed_processes: HashSet<ProcessIdentity>,
+    // Group-by-container mode: `process_groups[i]` describes
+    // `filtered_process_list[i]`. Never on together with the tree view.
+    pub group_by_container: bool,
+    pub process_groups: Vec<GroupRow>,
+
+    pub process_details: Option<ProcessDetailView>,
+    pub process_history: ProcessHistory,
+    pub confirmation: Option<ProcessBatch>,
+    pub dry_run: bool,
+    // Watches outlive the process list: they end when the process exits or
+    // the user stops watching.
+    pub watches: HashMap<ProcessIdentity, Watch>,
+    // Whether any alert channel is configured and enabled.
+    pub alert_channels: bool,
+
+    pub audit_records: Vec<AuditRecord>,
+    pub audit_scroll: usize,
+    pub audit_failure: Option<String>,
+
+    pub cgroup_scroll: usize,
+
+    // Commands started from the Managed tab, oldest first.
+    pub managed_processes: Vec<ManagedProcess>,
+    pub managed_selected: usize,
 }
 
 impl Default for AppState {
@@ -98,6 +229,7 @@ impl Default for AppState {
             selected_process: Some(0),
             sort_column: 2,
             sort_ascending: false,
+            memory_accounting: false,
             update_count: 0,
             process_list: Vec::new(),
             filtered_process_list: Vec::new(),
@@ -105,14 +237,30 @@ impl Default for AppState {
             status_message: None,
             show_help: false,
             notification_manager: NotificationManager::new(),
-            cpu_alert_threshold: 90.0,
-            memory_alert_threshold: 90.0,
-            disk_alert_threshold: 90.0,
-            last_cpu_alert: None,
-            last_memory_alert: None,
-            last_disk_alert: None,
+            cpu_alert_threshold: DEFAULT_ALERT_THRESHOLD_PERCENT,
+            memory_alert_threshold: DEFAULT_ALERT_THRESHOLD_PERCENT,
+            disk_alert_threshold: DEFAULT_ALERT_THRESHOLD_PERCENT,
+            active_alerts: BTreeMap::new(),
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
+            marked_processes: HashSet::new(),
+            tree_view: false,
+            process_tree: Vec::new(),
+            collapsed_processes: HashSet::new(),
+            group_by_container: false,
+            process_groups: Vec::new(),
+            process_details: None,
+            process_history: ProcessHistory::new(),
+            confirmation: None,
+            dry_run: false,
+            watches: HashMap::new(),
+            alert_channels: false,
+            audit_records: Vec::new(),
+            audit_scroll: 0,
+            audit_failure: None,
+            cgroup_scroll: 0,
+            managed_processes: Vec::new(),
+            managed_selected: 0,
         }
     }
 }
@@ -120,6 +268,15 @@ impl Default for AppState {
 pub struct App {
     pub state: AppState,
     pub process_manager: Option<Arc<dyn ProcessManager>>,
+    // Follow-up actions produced while dispatching (e.g. a submitted prompt
+    // turning into a renice). The event loop drains and executes them.
+    pending_actions: Vec<Action>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl App {
@@ -127,6 +284,7 @@ impl App {
         let mut app = Self {
             state: AppState::default(),
             process_manager: None,
+            pending_actions: Vec::new(),
         };
 
         app.state.notification_manager.add_info(
@@ -142,9 +300,24 @@ impl App {
         self
     }
 
+    pub fn with_memory_accounting(mut self, enabled: bool) -> Self {
+        self.state.memory_accounting = enabled;
+        self
+    }
+
+    pub fn with_alert_channels(mut self, enabled: bool) -> Self {
+        self.state.alert_channels = enabled;
+        self
+    }
+
     pub fn dispatch(&mut self, action: Action) {
         match action {
-            Action::Tick => {}
+            // The Managed tab follows output live, so it reloads every tick.
+            Action::Tick => {
+                if self.state.current_tab == Tab::Managed {
+                    self.pending_actions.push(Action::LoadManagedProcesses);
+                }
+            }
             Action::Quit => {
                 self.state.should_quit = true;
             }
@@ -152,16 +325,19 @@ impl App {
                 self.state.current_tab = tab;
                 self.state.scroll_offset = 0;
                 self.state.status_message = None;
+                self.reload_tab_data();
             }
             Action::NextTab => {
                 self.state.current_tab = self.state.current_tab.next();
                 self.state.scroll_offset = 0;
                 self.state.status_message = None;
+                self.reload_tab_data();
             }
             Action::PreviousTab => {
                 self.state.current_tab = self.state.current_tab.previous();
                 self.state.scroll_offset = 0;
                 self.state.status_message = None;
+                self.reload_tab_data();
             }
             Action::UpdateMetrics(metrics) => {
                 if let Some(ref mut history) = self.state.metrics_history {
@@ -178,6 +354,53 @@ impl App {
                 self.state.metrics = Some(metrics);
                 self.state.update_count += 1;
             }
+            Action::ScrollUp if self.details_visible() => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.scroll = view.scroll.saturating_sub(1);
+                }
+            }
+            Action::ScrollDown if self.details_visible() => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.scroll += 1;
+                }
+            }
+            Action::PageUp if self.details_visible() => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.scroll = view.scroll.saturating_sub(20);
+                }
+            }
+            Action::PageDown if self.details_visible() => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.scroll += 20;
+                }
+            }
+            Action::Home if self.details_visible() => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.scroll = 0;
+                }
+            }
+            Action::ScrollUp if self.state.current_tab == Tab::Audit => {
+                self.state.audit_scroll = self.state.audit_scroll.saturating_sub(1);
+            }
+            Action::ScrollDown if self.state.current_tab == Tab::Audit => {
+                let max = self.state.audit_records.len().saturating_sub(1);
+                self.state.audit_scroll = (self.state.audit_scroll + 1).min(max);
+            }
+            Action::ScrollUp if self.state.current_tab == Tab::Managed => {
+                self.state.managed_selected = self.state.managed_selected.saturating_sub(1);
+            }
+            Action::ScrollDown if self.state.current_tab == Tab::Managed => {
+                let max = self.state.managed_processes.len().saturating_sub(1);
+                self.state.managed_selected = (self.state.managed_selected + 1).min(max);
+            }
+            Action::ScrollUp if self.state.current_tab == Tab::Cgroups => {
+                self.state.cgroup_scroll = self.state.cgroup_scroll.saturating_sub(1);
+            }
+            Action::ScrollDown if self.state.current_tab == Tab::Cgroups => {
+                let count = self.state.metrics.as_ref().map_or(0, |m| m.cgroups.len());
+                self.state.cgroup_scroll =
+                    (self.state.cgroup_scroll + 1).min(count.saturating_sub(1));
+            }
             Action::ScrollUp => {
                 let list_len = self.state.filtered_process_list.len();
                 if let Some(selected) = self.state.selected_process {
@@ -256,8 +479,57 @@ impl App {
                 self.sort_processes();
                 self.apply_filter();
             }
+            Action::NextSortColumn | Action::PreviousSortColumn => {
+                let count = if self.state.memory_accounting {
+                    SORT_COLUMNS.len()
+                } else {
+                    BASIC_SORT_COLUMNS
+                };
+                let current = self.state.sort_column.min(count - 1);
+                self.state.sort_column = if matches!(action, Action::NextSortColumn) {
+                    (current + 1) % count
+                } else {
+                    (current + count - 1) % count
+                };
+                self.state.sort_ascending = false;
+                self.sort_processes();
+                self.apply_filter();
+            }
+            Action::ToggleMemoryAccounting => {
+                self.state.memory_accounting = !self.state.memory_accounting;
+                let msg = if self.state.memory_accounting {
+                    "Memory accounting enabled: reading PSS, USS and swap from smaps_rollup"
+                } else {
+                    "Memory accounting disabled"
+                };
+                if !self.state.memory_accounting && self.state.sort_column >= BASIC_SORT_COLUMNS {
+                    self.state.sort_column = 3;
+                    self.state.sort_ascending = false;
+                }
+                self.state.status_message = Some(msg.to_string());
+                self.state
+                    .notification_manager
+                    .add_info("Memory Accounting".to_string(), msg.to_string());
+                self.pending_actions.push(Action::LoadProcessList);
+            }
             Action::ProcessListLoaded(processes) => {
                 self.state.process_list = processes;
+                self.state.process_history.record(&self.state.process_list);
+                if let Some(view) = &self.state.process_details {
+                    self.pending_actions
+                        .push(Action::LoadProcessDetails(view.target));
+                }
+                // Forget marks on processes that have exited.
+                let alive: HashSet<ProcessIdentity> = self
+                    .state
+                    .process_list
+                    .iter()
+                    .map(|p| p.identity())
+                    .collect();
+                self.state.marked_processes.retain(|id| alive.contains(id));
+                self.state
+                    .collapsed_processes
+                    .retain(|id| alive.contains(id));
                 self.sort_processes();
                 self.apply_filter();
                 if self.state.selected_process.is_none()
@@ -269,13 +541,280 @@ impl App {
             Action::SelectProcess(index) => {
                 if index < self.state.filtered_process_list.len() {
                     self.state.selected_process = Some(index);
+                    if index < self.state.scroll_offset {
+                        self.state.scroll_offset = index;
+                    }
                 }
             }
+            Action::ToggleMarkSelected => {
+                if let Some(identity) = self.get_selected_process().map(|p| p.identity()) {
+                    if !self.state.marked_processes.remove(&identity) {
+                        self.state.marked_processes.insert(identity);
+                    }
+                    self.dispatch(Action::ScrollDown);
+                }
+            }
+            Action::MarkAllFiltered => {
+                self.state.marked_processes.extend(
+                    self.state
+                        .filtered_process_list
+                        .iter()
+                        .map(|p| p.identity()),
+                );
+                self.state.status_message = Some(format!(
+                    "{} processes marked",
+                    self.state.marked_processes.len()
+                ));
+            }
+            Action::MarkSubtree => {
+                if let Some(process) = self.get_selected_process() {
+                    let name = process.name.clone();
+                    let members: Vec<ProcessIdentity> =
+                        subtree(&self.state.process_list, process.identity())
+                            .iter()
+                            .map(|p| p.identity())
+                            .collect();
+                    let count = members.len();
+                    self.state.marked_processes.extend(members);
+                    self.state.status_message =
+                        Some(format!("Marked {} and its {} descendants", name, count - 1));
+                }
+            }
+            Action::ToggleTreeView => {
+                let selected = self.get_selected_process().map(|p| p.identity());
+                self.state.tree_view = !self.state.tree_view;
+                self.state.group_by_container = false;
+                self.apply_filter();
+                self.reselect(selected);
+            }
+            Action::ToggleGroupByContainer => {
+                let selected = self.get_selected_process().map(|p| p.identity());
+                self.state.group_by_container = !self.state.group_by_container;
+                self.state.tree_view = false;
+                self.apply_filter();
+                self.reselect(selected);
+            }
+            Action::CollapseSelected if self.state.tree_view => {
+                let Some(index) = self.state.selected_process else {
+                    return;
+                };
+                let Some(row) = self.state.process_tree.get(index) else {
+                    return;
+                };
+                let process = &self.state.filtered_process_list[index];
+                if row.has_children && !row.collapsed {
+                    let identity = process.identity();
+                    self.state.collapsed_processes.insert(identity);
+                    self.apply_filter();
+                    self.reselect(Some(identity));
+                } else if let Some(parent) = process.ppid.and_then(|ppid| {
+                    self.state.filtered_process_list[..index]
+                        .iter()
+                        .rposition(|p| p.pid == ppid)
+                }) {
+                    // Already collapsed or a leaf: jump to the parent.
+                    self.dispatch(Action::SelectProcess(parent));
+                }
+            }
+            Action::ExpandSelected if self.state.tree_view => {
+                if let Some(identity) = self.get_selected_process().map(|p| p.identity())
+                    && self.state.collapsed_processes.remove(&identity)
+                {
+                    self.apply_filter();
+                    self.reselect(Some(identity));
+                }
+            }
+            Action::CollapseSelected | Action::ExpandSelected => {}
+            Action::OpenProcessDetails => {
+                if let Some(target) = self.get_selected_process().map(|p| p.identity()) {
+                    self.state.process_details = Some(ProcessDetailView {
+                        target,
+                        details: None,
+                        error: None,
+                        scroll: 0,
+                        show_threads: false,
+                    });
+                    self.pending_actions
+                        .push(Action::LoadProcessDetails(target));
+                }
+            }
+            Action::CloseProcessDetails => {
+                self.state.process_details = None;
+            }
+            Action::ToggleThreadView => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.show_threads = !view.show_threads;
+                    view.scroll = 0;
+                }
+            }
+            Action::ProcessDetailsLoaded(details) => {
+                if let Some(view) = self.state.process_details.as_mut()
+                    && view.target == details.process.identity()
+                {
+                    view.details = Some(details);
+                    view.error = None;
+                }
+            }
+            Action::ProcessDetailsFailed(msg) => {
+                if let Some(view) = self.state.process_details.as_mut() {
+                    view.error = Some(msg);
+                }
+            }
+            Action::ClearMarks => {
+                self.state.marked_processes.clear();
+                self.state.status_message = Some("Marks cleared".to_string());
+            }
+            Action::RequestProcessCommand(command) => {
+                if !self.state.marked_processes.is_empty() {
+                    // Marks survive filter changes, so look in the full list.
+                    let targets = self
+                        .state
+                        .process_list
+                        .iter()
+                        .filter(|p| self.state.marked_processes.contains(&p.identity()))
+                        .map(ProcessTarget::from_process)
+                        .collect();
+                    self.state.confirmation = Some(ProcessBatch { command, targets });
+                } else if let Some(process) = self.get_selected_process() {
+                    let request = ProcessRequest {
+                        command,
+                        target: ProcessTarget::from_process(process),
+                    };
+                    if request.command.needs_confirmation() {
+                        self.state.confirmation = Some(ProcessBatch::single(request));
+                    } else {
+                        self.submit_process_request(request);
+                    }
+                }
+            }
+            Action::ConfirmProcessCommand => {
+                if let Some(batch) = self.state.confirmation.take() {
+                    self.submit_process_batch(batch);
+                }
+            }
+            Action::CancelProcessCommand => {
+                if self.state.confirmation.take().is_some() {
+                    self.state.status_message = Some("Cancelled".to_string());
+                }
+            }
+            Action::ToggleDryRun => {
+                self.state.dry_run = !self.state.dry_run;
+                let msg = if self.state.dry_run {
+                    "Dry-run enabled: process actions are logged, not sent"
+                } else {
+                    "Dry-run disabled: process actions are sent"
+                };
+                self.state.status_message = Some(msg.to_string());
+                self.state
+                    .notification_manager
+                    .add_info("Dry Run".to_string(), msg.to_string());
+            }
+            Action::WatchSelected { alert_channels } => {
+                let Some(process) = self.get_selected_process() else {
+                    return;
+                };
+                let target = ProcessTarget::from_process(process);
+                let label = format!("{} (PID: {})", target.name, target.identity.pid);
+
+                if self.state.watches.remove(&target.identity).is_some() {
+                    self.state.status_message = Some(format!("Stopped watching {}", label));
+                    return;
+                }
+
+                self.state.status_message = Some(if !alert_channels {
+                    format!("Watching {}: you will be notified when it exits", label)
+                } else if self.state.alert_channels {
+                    format!(
+                        "Watching {}: its exit also goes to the alert channels",
+                        label
+                    )
+                } else {
+                    format!("Watching {}: no alert channels are configured", label)
+                });
+                self.state.watches.insert(
+                    target.identity,
+                    Watch {
+                        target: target.clone(),
+                        alert_channels,
+                    },
+                );
+                self.pending_actions.push(Action::WatchProcess(target));
+            }
+            Action::ProcessExited(identity, result) => {
+                // Not watched any more: the user changed their mind.
+                let Some(watch) = self.state.watches.remove(&identity) else {
+                    return;
+                };
+
+                let exit = match result {
+                    Ok(exit) => exit,
+                    Err(msg) => {
+                        self.state.notification_manager.add_critical(
+                            "Watch Failed".to_string(),
+                            format!("Cannot watch {}: {}", watch.target.name, msg),
+                        );
+                        return;
+                    }
+                };
+
+                let status = exit
+                    .status
+                    .map(|status| format!(" ({})", status.label()))
+                    .unwrap_or_default();
+                let msg = format!(
+                    "{} (PID: {}) exited at {} after {}{}",
+                    exit.name,
+                    exit.pid,
+                    exit.exited_at.with_timezone(&Local).format("%H:%M:%S"),
+                    format_duration(exit.runtime_seconds()),
+                    status
+                );
+                let failed = exit.status.is_some_and(|status| !status.success());
+
+                self.state.status_message = Some(msg.clone());
+                if watch.alert_channels {
+                    let severity = if failed {
+                        AlertSeverity::Critical
+                    } else {
+                        AlertSeverity::Info
+                    };
+                    self.pending_actions
+                        .push(Action::SendAlert(AlertMessage::new(
+                            "Process Exited",
+                            msg.clone(),
+                            severity,
+                        )));
+                }
+                let title = "Process Exited".to_string();
+                match exit.status {
+                    Some(status) if status.success() => {
+                        self.state.notification_manager.add_success(title, msg)
+                    }
+                    Some(_) => self.state.notification_manager.add_critical(title, msg),
+                    None => self.state.notification_manager.add_info(title, msg),
+                }
+            }
+            Action::AlertDeliveryFailed(msg) => {
+                self.state
+                    .notification_manager
+                    .add_warning("Alert Delivery Failed".to_string(), msg);
+            }
+            Action::ProcessActionProgress(msg) => {
+                self.state.status_message = Some(msg.clone());
+                self.state
+                    .notification_manager
+                    .add_info("Process Action".to_string(), msg);
+            }
             Action::ProcessActionComplete(msg) => {
                 self.state.status_message = Some(msg.clone());
                 self.state
                     .notification_manager
                     .add_success("Process Action".to_string(), msg);
+                // Keep an open detail pane in step with what just changed.
+                if let Some(view) = &self.state.process_details {
+                    self.pending_actions
+                        .push(Action::LoadProcessDetails(view.target));
+                }
             }
             Action::ProcessActionFailed(msg) => {
                 self.state.status_message = Some(format!("ERROR: {}", msg));
@@ -283,6 +822,75 @@ impl App {
                     .notification_manager
                     .add_critical("Process Action Failed".to_string(), msg);
             }
+            Action::ProcessBatchComplete(description, outcomes) => {
+                let total = outcomes.len();
+                let failed = outcomes.iter().filter(|o| o.result.is_err()).count();
+                for outcome in outcomes {
+                    let target = format!(
+                        "{} (PID: {})",
+                        outcome.target.name, outcome.target.identity.pid
+                    );
+                    match outcome.result {
+                        Ok(msg) => self.state.notification_manager.add_success(target, msg),
+                        Err(msg) => self.state.notification_manager.add_critical(target, msg),
+                    }
+                }
+
+                let msg = format!(
+                    "{}: {} succeeded, {} failed",
+                    description,
+                    total - failed,
+                    failed
+                );
+                self.state.status_message = Some(msg.clone());
+                if failed == 0 {
+                    self.state
+                        .notification_manager
+                        .add_success("Batch Action".to_string(), msg);
+                } else {
+                    self.state
+                        .notification_manager
+                        .add_warning("Batch Action".to_string(), msg);
+                }
+            }
+            Action::AuditLogLoaded(records) => {
+                self.state.audit_records = records;
+                let max = self.state.audit_records.len().saturating_sub(1);
+                self.state.audit_scroll = self.state.audit_scroll.min(max);
+            }
+            Action::AuditLogStatus(failure) => {
+                // Notify when the log breaks or fails differently; the Audit
+                // tab keeps showing the error until a write succeeds.
+                if let Some(error) = &failure
+                    && self.state.audit_failure.as_ref() != Some(error)
+                {
+                    self.state.notification_manager.add_critical(
+                        "Audit Log".to_string(),
+                        format!("Actions are not being recorded: {}", error),
+                    );
+                }
+                self.state.audit_failure = failure;
+            }
+            Action::ManagedProcessesLoaded(managed) => {
+                self.state.managed_processes = managed;
+                let max = self.state.managed_processes.len().saturating_sub(1);
+                self.state.managed_selected = self.state.managed_selected.min(max);
+            }
+            Action::RequestManagedCommand(command) => {
+                if self.state.dry_run {
+                    if let Some((pid, action)) = command.action(&self.state.managed_processes) {
+                        self.pending_actions.push(Action::RecordDryRun(pid, action));
+                    }
+                    let msg = format!("Would {}", command.describe());
+                    self.state.status_message = Some(format!("DRY RUN: {}", msg));
+                    self.state
+                        .notification_manager
+                        .add_info("Dry Run".to_string(), msg);
+                } else {
+                    self.pending_actions
+                        .push(Action::ExecuteManagedCommand(command));
+                }
+            }
             Action::ToggleHelp => {
                 self.state.show_help = !self.state.show_help;
             }
@@ -292,64 +900,57 @@ impl App {
             Action::ClearAllNotifications => {
                 self.state.notification_manager.clear();
             }
+            // Threshold rules are evaluated by the event loop; this only
+            // looks for new OOM kills.
             Action::CheckAlerts(metrics) => {
-                // Check CPU alert
-                let cpu_usage = metrics.cpu.overall_usage_percent;
-                if cpu_usage > self.state.cpu_alert_threshold {
-                    if self.state.last_cpu_alert.is_none()
-                        || self.state.last_cpu_alert.unwrap() < self.state.cpu_alert_threshold
-                    {
-                        self.state.notification_manager.add_warning(
-                            "High CPU Usage".to_string(),
-                            format!(
-                                "CPU usage is at {:.1}% (threshold: {:.1}%)",
-                                cpu_usage, self.state.cpu_alert_threshold
-                            ),
-                        );
-                    }
-                    self.state.last_cpu_alert = Some(cpu_usage);
-                } else {
-                    self.state.last_cpu_alert = None;
-                }
-
-                let mem_usage = metrics.memory.usage_percent;
-                if mem_usage > self.state.memory_alert_threshold {
-                    if self.state.last_memory_alert.is_none()
-                        || self.state.last_memory_alert.unwrap() < self.state.memory_alert_threshold
-                    {
-                        self.state.notification_manager.add_warning(
-                            "High Memory Usage".to_string(),
-                            format!(
-                                "Memory usage is at {:.1}% (threshold: {:.1}%)",
-                                mem_usage, self.state.memory_alert_threshold
-                            ),
-                        );
+                // `state.metrics` still holds the previous sample here.
+                let previous_oom_kills = self
+                    .state
+                    .metrics
+                    .as_ref()
+                    .and_then(|previous| previous.memory.oom_kills);
+                if let (Some(previous), Some(now)) = (previous_oom_kills, metrics.memory.oom_kills)
+                    && now > previous
+                {
+                    self.state.notification_manager.add_critical(
+                        "OOM Kill".to_string(),
+                        format!(
+                            "The OOM killer ended {} process(es) ({} since boot); see the kernel log",
+                            now - previous,
+                            now
+                        ),
+                    );
+                }
+            }
+            Action::AlertStateChanged(event) => {
+                if event.notify {
+                    let alert = event.to_message();
+                    let notifications = &mut self.state.notification_manager;
+                    match (event.state, event.severity) {
+                        (AlertState::Pending, _) => {}
+                        (AlertState::Resolved, _) => {
+                            notifications.add_success(alert.title, alert.message)
+                        }
+                        (AlertState::Firing, AlertSeverity::Info) => {
+                            notifications.add_info(alert.title, alert.message)
+                        }
+                        (AlertState::Firing, AlertSeverity::Warning) => {
+                            notifications.add_warning(alert.title, alert.message)
+                        }
+                        (AlertState::Firing, AlertSeverity::Critical) => {
+                            notifications.add_critical(alert.title, alert.message)
+                        }
                     }
-                    self.state.last_memory_alert = Some(mem_usage);
-                } else {
-                    self.state.last_memory_alert = None;
                 }
 
-                for disk in &metrics.disks {
-                    let disk_usage = disk.info.usage_percent;
-                    if disk_usage > self.state.disk_alert_threshold {
-                        if self.state.last_disk_alert.is_none()
-                            || self.state.last_disk_alert.unwrap() < self.state.disk_alert_threshold
-                        {
-                            self.state.notification_manager.add_warning(
-                                "High Disk Usage".to_string(),
-                                format!(
-                                    "Disk {} usage is at {:.1}% (threshold: {:.1}%)",
-                                    disk.info.mount_point,
-                                    disk_usage,
-                                    self.state.disk_alert_threshold
-                                ),
-                            );
-                        }
-                        self.state.last_disk_alert = Some(disk_usage);
-                        break;
-                    } else {
-                        self.state.last_disk_alert = None;
+                match event.state {
+                    AlertState::Pending | AlertState::Firing => {
+                        self.state
+                            .active_alerts
+                            .insert(event.rule_id.clone(), event);
+                    }
+                    AlertState::Resolved => {
+                        self.state.active_alerts.remove(&event.rule_id);
                     }
                 }
             }
@@ -371,6 +972,57 @@ impl App {
                     InputMode::SearchProcess => {
                         self.state.input_buffer = self.state.process_filter.clone();
                     }
+                    InputMode::EditNice => {
+                        if let Some(process) = self.get_selected_process() {
+                            self.state.input_buffer = process.nice.to_string();
+                        }
+                    }
+                    InputMode::EditScheduler => {
+                        if let Some(process) = self.get_selected_process() {
+                            self.state.input_buffer = process.scheduling_policy.name().to_string();
+                        }
+                    }
+                    InputMode::EditAffinity => {
+                        if let Some(process) = self.get_selected_process() {
+                            self.state.input_buffer = format_cpu_list(&process.cpu_affinity);
+                        }
+                    }
+                    InputMode::EditIoPriority => {
+                        if let Some(io) = self.get_selected_process().and_then(|p| p.io_priority) {
+                            self.state.input_buffer = io.label().replace('/', " ");
+                        }
+                    }
+                    InputMode::EditCgroup => {
+                        if let Some(cgroup) =
+                            self.get_selected_process().and_then(|p| p.cgroup.clone())
+                        {
+                            self.state.input_buffer = cgroup;
+                        }
+                    }
+                    InputMode::EditOomScoreAdj => {
+                        if let Some(adj) = self.get_selected_process().and_then(|p| p.oom_score_adj)
+                        {
+                            self.state.input_buffer = adj.to_string();
+                        }
+                    }
+                    // Start from the current open files limit when the
+                    // detail pane has it loaded; that is the one usually
+                    // raised.
+                    InputMode::EditLimit => {
+                        let current = self.open_details().and_then(|details| {
+                            details
+                                .limits
+                                .as_ref()?
+                                .iter()
+                                .find(|limit| limit.resource == Some(ResourceKind::OpenFiles))
+                        });
+                        self.state.input_buffer = match current {
+                            Some(limit) => {
+                                format!("nofile {}", LimitValue::from_option(limit.soft).label())
+                            }
+                            None => "nofile ".to_string(),
+                        };
+                    }
                     _ => {}
                 }
             }
@@ -408,8 +1060,9 @@ impl App {
                 match self.state.input_mode {
                     InputMode::EditCpuThreshold => {
                         if let Ok(value) = self.state.input_buffer.parse::<f32>() {
-                            if value >= 0.0 && value <= 100.0 {
+                            if (0.0..=100.0).contains(&value) {
                                 self.state.cpu_alert_threshold = value;
+                                self.push_threshold_rules();
                                 self.state.notification_manager.add_success(
                                     "Settings Updated".to_string(),
                                     format!("CPU threshold set to {:.0}%", value),
@@ -429,8 +1082,9 @@ impl App {
                     }
                     InputMode::EditMemoryThreshold => {
                         if let Ok(value) = self.state.input_buffer.parse::<f32>() {
-                            if value >= 0.0 && value <= 100.0 {
+                            if (0.0..=100.0).contains(&value) {
                                 self.state.memory_alert_threshold = value;
+                                self.push_threshold_rules();
                                 self.state.notification_manager.add_success(
                                     "Settings Updated".to_string(),
                                     format!("Memory threshold set to {:.0}%", value),
@@ -450,8 +1104,9 @@ impl App {
                     }
                     InputMode::EditDiskThreshold => {
                         if let Ok(value) = self.state.input_buffer.parse::<f32>() {
-                            if value >= 0.0 && value <= 100.0 {
+                            if (0.0..=100.0).contains(&value) {
                                 self.state.disk_alert_threshold = value;
+                                self.push_threshold_rules();
                                 self.state.notification_manager.add_success(
                                     "Settings Updated".to_string(),
                                     format!("Disk threshold set to {:.0}%", value),
@@ -472,6 +1127,32 @@ impl App {
                     InputMode::SearchProcess => {
                         // Already applied live
                     }
+                    InputMode::SpawnCommand => match SpawnSpec::parse(&self.state.input_buffer) {
+                        Ok(spec) => self
+                            .dispatch(Action::RequestManagedCommand(ManagedCommand::Spawn(spec))),
+                        Err(msg) => {
+                            self.state
+                                .notification_manager
+                                .add_critical("Invalid Input".to_string(), msg);
+                        }
+                    },
+                    InputMode::EditNice
+                    | InputMode::EditScheduler
+                    | InputMode::EditAffinity
+                    | InputMode::EditIoPriority
+                    | InputMode::EditCgroup
+                    | InputMode::EditCgroupLimit
+                    | InputMode::EditLimit
+                    | InputMode::EditOomScoreAdj => {
+                        match parse_process_input(self.state.input_mode, &self.state.input_buffer) {
+                            Ok(command) => self.dispatch(Action::RequestProcessCommand(command)),
+                            Err(msg) => {
+                                self.state
+                                    .notification_manager
+                                    .add_critical("Invalid Input".to_string(), msg);
+                            }
+                        }
+                    }
                     _ => {}
                 }
                 self.state.input_mode = InputMode::Normal;
@@ -484,14 +1165,119 @@ impl App {
                 self.state.selected_process = Some(0);
                 self.state.scroll_offset = 0;
             }
+            Action::ToggleElevatedFilter => {
+                if self.state.process_filter == ELEVATED_FILTER {
+                    self.state.process_filter.clear();
+                } else {
+                    self.state.process_filter = ELEVATED_FILTER.to_string();
+                }
+                self.apply_filter();
+                self.state.selected_process = Some(0);
+                self.state.scroll_offset = 0;
+            }
             Action::LoadProcessList
-            | Action::KillSelectedProcess
-            | Action::SuspendSelectedProcess
-            | Action::ContinueSelectedProcess
-            | Action::TerminateSelectedProcess => {}
+            | Action::ExecuteProcessCommand(_)
+            | Action::ExecuteProcessBatch(_)
+            | Action::LoadProcessDetails(_)
+            | Action::LoadAuditLog
+            | Action::LoadManagedProcesses
+            | Action::ExecuteManagedCommand(_)
+            | Action::RecordDryRun(..)
+            | Action::WatchProcess(_)
+            | Action::SendAlert(_)
+            | Action::SetAlertRule(_) => {}
         }
     }
 
+    // Details of the selected process, if the detail pane has loaded them.
+    fn open_details(&self) -> Option<&ProcessDetails> {
+        let selected = self.get_selected_process()?.identity();
+        let view = self.state.process_details.as_ref()?;
+        (view.target == selected)
+            .then_some(view.details.as_deref())
+            .flatten()
+    }
+
+    fn details_visible(&self) -> bool {
+        self.state.current_tab == Tab::Processes && self.state.process_details.is_some()
+    }
+
+    // Tabs that show data loaded on demand fetch it when they are opened.
+    fn reload_tab_data(&mut self) {
+        match self.state.current_tab {
+            Tab::Audit => self.pending_actions.push(Action::LoadAuditLog),
+            Tab::Managed => self.pending_actions.push(Action::LoadManagedProcesses),
+            _ => {}
+        }
+    }
+
+    // A confirmed single-target batch runs like any other request, so its
+    // result is reported the same way.
+    fn submit_process_batch(&mut self, batch: ProcessBatch) {
+        if batch.targets.len() == 1 {
+            if let Some(request) = batch.requests().next() {
+                self.submit_process_request(request);
+            }
+        } else if self.state.dry_run {
+            let msg = format!("Would {}", batch.describe());
+            self.state.status_message = Some(format!("DRY RUN: {}", msg));
+            self.state
+                .notification_manager
+                .add_info("Dry Run".to_string(), msg);
+            for request in batch.requests() {
+                self.pending_actions.push(Action::RecordDryRun(
+                    request.target.identity.pid,
+                    request.command.action(),
+                ));
+                self.state.notification_manager.add_info(
+                    "Dry Run".to_string(),
+                    format!("Would {}", request.describe()),
+                );
+            }
+        } else {
+            self.pending_actions
+                .push(Action::ExecuteProcessBatch(batch));
+        }
+    }
+
+    // In dry-run mode the request is only logged; otherwise it is handed to
+    // the event loop for execution.
+    fn submit_process_request(&mut self, request: ProcessRequest) {
+        if self.state.dry_run {
+            self.pending_actions.push(Action::RecordDryRun(
+                request.target.identity.pid,
+                request.command.action(),
+            ));
+            let msg = format!("Would {}", request.describe());
+            self.state.status_message = Some(format!("DRY RUN: {}", msg));
+            self.state
+                .notification_manager
+                .add_info("Dry Run".to_string(), msg);
+        } else {
+            self.pending_actions
+                .push(Action::ExecuteProcessCommand(request));
+        }
+    }
+
+    // The built-in CPU, memory and disk rules at the Settings thresholds.
+    pub fn threshold_rules(&self) -> Vec<AlertRule> {
+        threshold_alert_rules(
+            self.state.cpu_alert_threshold,
+            self.state.memory_alert_threshold,
+            self.state.disk_alert_threshold,
+        )
+    }
+
+    fn push_threshold_rules(&mut self) {
+        for rule in self.threshold_rules() {
+            self.pending_actions.push(Action::SetAlertRule(rule));
+        }
+    }
+
+    pub fn take_pending_actions(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.pending_actions)
+    }
+
     fn sort_processes(&mut self) {
         let ascending = self.state.sort_ascending;
         match self.state.sort_column {
@@ -527,11 +1313,63 @@ impl App {
                     b.memory_usage_bytes.cmp(&a.memory_usage_bytes)
                 }
             }),
+            column @ 4..=6 => {
+                // Processes without accounting (unreadable, or not collected)
+                // sort as zero.
+                let key = |p: &Process| {
+                    let accounting = p.memory_accounting.unwrap_or_default();
+                    match column {
+                        4 => accounting.pss_bytes,
+                        5 => accounting.uss_bytes,
+                        _ => accounting.swap_bytes,
+                    }
+                };
+                self.state.process_list.sort_by(|a, b| {
+                    if ascending {
+                        key(a).cmp(&key(b))
+                    } else {
+                        key(b).cmp(&key(a))
+                    }
+                });
+            }
             _ => {}
         }
     }
 
     fn apply_filter(&mut self) {
+        self.state.process_groups.clear();
+        if self.state.group_by_container {
+            let filter_lower = self.state.process_filter.to_lowercase();
+            let rows = group_by_container(&self.state.process_list, |p| {
+                filter_lower.is_empty() || matches_filter(p, &filter_lower)
+            });
+            let (indices, rows): (Vec<usize>, Vec<GroupRow>) = rows.into_iter().unzip();
+            self.state.filtered_process_list = indices
+                .into_iter()
+                .map(|i| self.state.process_list[i].clone())
+                .collect();
+            self.state.process_groups = rows;
+            self.state.process_tree.clear();
+            return;
+        }
+
+        if self.state.tree_view {
+            let filter_lower = self.state.process_filter.to_lowercase();
+            let rows = build_tree(
+                &self.state.process_list,
+                &self.state.collapsed_processes,
+                |p| filter_lower.is_empty() || matches_filter(p, &filter_lower),
+            );
+            let (indices, rows): (Vec<usize>, Vec<TreeRow>) = rows.into_iter().unzip();
+            self.state.filtered_process_list = indices
+                .into_iter()
+                .map(|i| self.state.process_list[i].clone())
+                .collect();
+            self.state.process_tree = rows;
+            return;
+        }
+
+        self.state.process_tree.clear();
         if self.state.process_filter.is_empty() {
             self.state.filtered_process_list = self.state.process_list.clone();
         } else {
@@ -540,17 +1378,30 @@ impl App {
                 .state
                 .process_list
                 .iter()
-                .filter(|p| {
-                    p.name.to_lowercase().contains(&filter_lower)
-                        || p.command.to_lowercase().contains(&filter_lower)
-                        || p.pid.to_string().contains(&filter_lower)
-                        || p.user.to_lowercase().contains(&filter_lower)
-                })
+                .filter(|p| matches_filter(p, &filter_lower))
                 .cloned()
                 .collect();
         }
     }
 
+    // Moves the cursor back onto `identity` after the list was rebuilt.
+    fn reselect(&mut self, identity: Option<ProcessIdentity>) {
+        let Some(index) = identity.and_then(|id| {
+            self.state
+                .filtered_process_list
+                .iter()
+                .position(|p| p.identity() == id)
+        }) else {
+            return;
+        };
+
+        self.state.selected_process = Some(index);
+        let visible_rows = 20;
+        if index < self.state.scroll_offset || index >= self.state.scroll_offset + visible_rows {
+            self.state.scroll_offset = index.saturating_sub(visible_rows / 2);
+        }
+    }
+
     pub fn should_quit(&self) -> bool {
         self.state.should_quit
     }
@@ -561,3 +1412,105 @@ impl App {
             .and_then(|idx| self.state.filtered_process_list.get(idx))
     }
 }
+
+// Turns the text typed into a Processes-tab prompt into the command to run.
+fn parse_process_input(mode: InputMode, input: &str) -> Result<ProcessCommand, String> {
+    let input = input.trim();
+
+    match mode {
+        InputMode::EditNice => match input.parse::<i32>() {
+            Ok(nice) if (-20..=19).contains(&nice) => Ok(ProcessCommand::Renice(nice)),
+            _ => Err("Nice value must be a number between -20 and 19".to_string()),
+        },
+        InputMode::EditScheduler => {
+            let mut parts = input.split_whitespace();
+            let policy = parts
+                .next()
+                .and_then(SchedulingPolicy::parse)
+                .ok_or_else(|| "Policy must be one of: other, batch, idle, fifo, rr".to_string())?;
+            let priority = match parts.next() {
+                Some(p) => p
+                    .parse::<i32>()
+                    .map_err(|_| "Priority must be a number".to_string())?,
+                None if policy.is_realtime() => 1,
+                None => 0,
+            };
+            Ok(ProcessCommand::SetScheduler(policy, priority))
+        }
+        InputMode::EditAffinity => match parse_cpu_list(input) {
+            Some(cpus) if !cpus.is_empty() => Ok(ProcessCommand::SetAffinity(cpus)),
+            _ => Err("CPU list must look like 0-3,6".to_string()),
+        },
+        InputMode::EditIoPriority => IoPriority::parse(input)
+            .map(ProcessCommand::SetIoPriority)
+            .ok_or_else(|| "I/O priority must be rt 0-7, be 0-7, idle or none".to_string()),
+        InputMode::EditCgroup if input.starts_with('/') && input.len() > 1 => {
+            Ok(ProcessCommand::MoveToCgroup(input.to_string()))
+        }
+        InputMode::EditCgroup => Err("Cgroup must be a path like /oxyd/throttled".to_string()),
+        InputMode::EditCgroupLimit => CgroupLimit::parse(input)
+            .map(ProcessCommand::SetCgroupLimit)
+            .ok_or_else(|| {
+                "Limit must look like: cpu 0.5 | high 512M | max 1G | io 8:0 rbps=10M".to_string()
+            }),
+        InputMode::EditOomScoreAdj => match input.parse::<i32>() {
+            Ok(adj) if (-1000..=1000).contains(&adj) => Ok(ProcessCommand::SetOomScoreAdj(adj)),
+            _ => Err("oom_score_adj must be a number between -1000 and 1000".to_string()),
+        },
+        InputMode::EditLimit => LimitChange::parse(input)
+            .map(ProcessCommand::SetLimit)
+            .ok_or_else(|| {
+                "Limit must look like: nofile 65536 [hard] | core unlimited | as 4G | nproc 4096"
+                    .to_string()
+            }),
+        _ => Err("Nothing to submit".to_string()),
+    }
+}
+
+// `user:<name>` (or `user:<uid>`) restricts the list to one user,
+// `container:<name or ID prefix>` to one container ("container:host" to
+// processes outside any) and `cap:<name>` to processes holding that
+// effective capability ("cap:any" for any); anything else is a substring
+// match over name, command, PID and user.
+fn matches_filter(p: &Process, filter_lower: &str) -> bool {
+    if let Some(capability) = filter_lower.strip_prefix("cap:") {
+        // Kernel threads hold every capability and would bury the rest.
+        if p.pid == 2 || p.ppid == Some(2) {
+            return false;
+        }
+        return match capability.trim() {
+            "" | "any" => p.security.is_elevated(),
+            name => capability_from_name(name).is_some_and(|cap| p.security.has_capability(cap)),
+        };
+    }
+    if let Some(container) = filter_lower.strip_prefix("container:") {
+        let container = container.trim();
+        return match &p.container {
+            Some(info) => {
+                info.name
+                    .as_ref()
+                    .is_some_and(|name| name.to_lowercase() == container)
+                    || (!container.is_empty() && info.id.to_lowercase().starts_with(container))
+            }
+            None => container == "host",
+        };
+    }
+    if let Some(user) = filter_lower.strip_prefix("user:") {
+        let user = user.trim();
+        return p.user.to_lowercase() == user
+            || user.parse::<u32>().is_ok_and(|uid| {
+                [
+                    p.uids.real,
+                    p.uids.effective,
+                    p.uids.saved,
+                    p.uids.filesystem,
+                ]
+                .contains(&uid)
+            });
+    }
+
+    p.name.to_lowercase().contains(filter_lower)
+        || p.command.to_lowercase().contains(filter_lower)
+        || p.pid.to_string().contains(filter_lower)
+        || p.user.to_lowercase().contains(filter_lower)
+}
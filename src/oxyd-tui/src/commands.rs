@@ -0,0 +1,236 @@
+use chrono::{DateTime, Utc};
+use oxyd_domain::{
+    CgroupLimit, IoPriority, LimitChange, ManagedProcess, Process, ProcessAction, ProcessIdentity,
+    ProcessSignal, SchedulingPolicy, SpawnSpec, format_cpu_list,
+};
+
+// Something the user asked to do to one process.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessCommand {
+    Kill,
+    Terminate,
+    Stop,
+    Suspend,
+    Continue,
+    Renice(i32),
+    SetScheduler(SchedulingPolicy, i32),
+    SetAffinity(Vec<usize>),
+    SetIoPriority(IoPriority),
+    MoveToCgroup(String),
+    // Applies to the whole cgroup the target is in.
+    SetCgroupLimit(CgroupLimit),
+    SetLimit(LimitChange),
+    SetOomScoreAdj(i32),
+}
+
+impl ProcessCommand {
+    // Commands that can end or freeze a process, or throttle everything in
+    // its cgroup, go through the confirmation dialog first.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(
+            self,
+            ProcessCommand::Kill
+                | ProcessCommand::Terminate
+                | ProcessCommand::Stop
+                | ProcessCommand::Suspend
+                | ProcessCommand::SetCgroupLimit(_)
+        )
+    }
+
+    // The signal a plain signal command sends. `Stop` is not included: it
+    // sends several, see `ProcessManager::stop_process`.
+    pub fn signal(&self) -> Option<ProcessSignal> {
+        match self {
+            ProcessCommand::Kill => Some(ProcessSignal::Kill),
+            ProcessCommand::Terminate => Some(ProcessSignal::Terminate),
+            ProcessCommand::Suspend => Some(ProcessSignal::Stop),
+            ProcessCommand::Continue => Some(ProcessSignal::Continue),
+            _ => None,
+        }
+    }
+
+    // What the audit log records for this command. The cgroup of a limit is
+    // left for the process manager to fill in.
+    pub fn action(&self) -> ProcessAction {
+        match self {
+            ProcessCommand::Kill => ProcessAction::Kill,
+            ProcessCommand::Terminate => ProcessAction::Terminate,
+            ProcessCommand::Stop => ProcessAction::Stop,
+            ProcessCommand::Suspend => ProcessAction::Suspend,
+            ProcessCommand::Continue => ProcessAction::Resume,
+            ProcessCommand::Renice(nice) => ProcessAction::SetNice(*nice),
+            ProcessCommand::SetScheduler(policy, priority) => {
+                ProcessAction::SetScheduler(*policy, *priority)
+            }
+            ProcessCommand::SetAffinity(cpus) => ProcessAction::SetAffinity(cpus.clone()),
+            ProcessCommand::SetIoPriority(io) => ProcessAction::SetIoPriority(*io),
+            ProcessCommand::MoveToCgroup(cgroup) => ProcessAction::MoveToCgroup(cgroup.clone()),
+            ProcessCommand::SetCgroupLimit(limit) => {
+                ProcessAction::SetCgroupLimit(String::new(), limit.clone())
+            }
+            ProcessCommand::SetLimit(change) => ProcessAction::SetLimit(change.clone()),
+            ProcessCommand::SetOomScoreAdj(adj) => ProcessAction::SetOomScoreAdj(*adj),
+        }
+    }
+
+    // Sentence fragment naming what happens to `target`, e.g.
+    // "send SIGKILL to nginx (PID: 812)".
+    pub fn describe(&self, target: &str) -> String {
+        match self {
+            ProcessCommand::Stop => format!("stop {} (escalating to SIGKILL)", target),
+            ProcessCommand::Renice(nice) => format!("renice {} to {}", target, nice),
+            ProcessCommand::SetScheduler(policy, priority) => format!(
+                "set scheduling policy of {} to {} {}",
+                target,
+                policy.name(),
+                priority
+            ),
+            ProcessCommand::SetAffinity(cpus) => {
+                format!("pin {} to CPUs {}", target, format_cpu_list(cpus))
+            }
+            ProcessCommand::SetIoPriority(io) => {
+                format!("set I/O priority of {} to {}", target, io.label())
+            }
+            ProcessCommand::MoveToCgroup(cgroup) => format!("move {} to cgroup {}", target, cgroup),
+            ProcessCommand::SetCgroupLimit(limit) => {
+                format!("set {} on the cgroup of {}", limit.label(), target)
+            }
+            ProcessCommand::SetOomScoreAdj(adj) => {
+                format!("set oom_score_adj of {} to {}", target, adj)
+            }
+            ProcessCommand::SetLimit(change) => {
+                format!("set limit {} of {}", change.label(), target)
+            }
+            other => match other.signal() {
+                Some(signal) => format!("send {} to {}", signal.name(), target),
+                None => format!("act on {}", target),
+            },
+        }
+    }
+}
+
+// The exact process a command was issued against, captured when the key was
+// pressed so a refresh moving the cursor cannot change the target.
+#[derive(Debug, Clone)]
+pub struct ProcessTarget {
+    pub identity: ProcessIdentity,
+    pub name: String,
+    pub command_line: String,
+    pub start_time: DateTime<Utc>,
+}
+
+impl ProcessTarget {
+    pub fn from_process(process: &Process) -> Self {
+        let command_line = if process.arguments.is_empty() {
+            process.command.clone()
+        } else {
+            format!("{} {}", process.command, process.arguments.join(" "))
+        };
+
+        Self {
+            identity: process.identity(),
+            name: process.name.clone(),
+            command_line,
+            start_time: process.start_time,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessRequest {
+    pub command: ProcessCommand,
+    pub target: ProcessTarget,
+}
+
+impl ProcessRequest {
+    pub fn describe(&self) -> String {
+        self.command.describe(&format!(
+            "{} (PID: {})",
+            self.target.name, self.target.identity.pid
+        ))
+    }
+}
+
+// One command applied to one or more processes, confirmed once. Marked rows
+// in the Processes tab turn into a multi-target batch.
+#[derive(Debug, Clone)]
+pub struct ProcessBatch {
+    pub command: ProcessCommand,
+    pub targets: Vec<ProcessTarget>,
+}
+
+impl ProcessBatch {
+    pub fn single(request: ProcessRequest) -> Self {
+        Self {
+            command: request.command,
+            targets: vec![request.target],
+        }
+    }
+
+    pub fn requests(&self) -> impl Iterator<Item = ProcessRequest> + '_ {
+        self.targets.iter().map(|target| ProcessRequest {
+            command: self.command.clone(),
+            target: target.clone(),
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        match self.targets.as_slice() {
+            [target] => ProcessRequest {
+                command: self.command.clone(),
+                target: target.clone(),
+            }
+            .describe(),
+            targets => self
+                .command
+                .describe(&format!("{} processes", targets.len())),
+        }
+    }
+}
+
+// What happened to one target of a batch: the manager's message, or the
+// error it failed with.
+#[derive(Debug, Clone)]
+pub struct BatchOutcome {
+    pub target: ProcessTarget,
+    pub result: Result<String, String>,
+}
+
+// Something the user asked to do on the Managed tab. Entries are named by
+// the supervisor's id, which unlike a PID survives restarts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManagedCommand {
+    Spawn(SpawnSpec),
+    Stop(u64),
+    Restart(u64),
+    Remove(u64),
+}
+
+impl ManagedCommand {
+    pub fn describe(&self) -> String {
+        match self {
+            ManagedCommand::Spawn(spec) => format!("start {}", spec.command_line()),
+            ManagedCommand::Stop(id) => format!("stop managed process #{}", id),
+            ManagedCommand::Restart(id) => format!("restart managed process #{}", id),
+            ManagedCommand::Remove(id) => format!("remove managed process #{}", id),
+        }
+    }
+
+    // The PID and audit log action of this command, looked up in `managed`.
+    // Removing an entry is not audited, so it has none.
+    pub fn action(&self, managed: &[ManagedProcess]) -> Option<(u32, ProcessAction)> {
+        let pid = |id: &u64| {
+            managed
+                .iter()
+                .find(|entry| entry.id == *id)
+                .and_then(|entry| entry.pid)
+                .unwrap_or(0)
+        };
+        match self {
+            ManagedCommand::Spawn(spec) => Some((0, ProcessAction::Spawn(spec.command_line()))),
+            ManagedCommand::Stop(id) => Some((pid(id), ProcessAction::Stop)),
+            ManagedCommand::Restart(id) => Some((pid(id), ProcessAction::Restart)),
+            ManagedCommand::Remove(_) => None,
+        }
+    }
+}
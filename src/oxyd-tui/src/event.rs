@@ -1,4 +1,5 @@
-use crate::app::{Action, InputMode};
+use crate::app::{Action, AppState, InputMode};
+use crate::commands::{ManagedCommand, ProcessCommand};
 use crate::tabs::Tab;
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent};
 use std::time::Duration;
@@ -15,6 +16,12 @@ pub struct EventHandler {
     rx: mpsc::UnboundedReceiver<Event>,
 }
 
+impl Default for EventHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EventHandler {
     pub fn new() -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
@@ -58,10 +65,22 @@ impl EventHandler {
     }
 }
 
-pub fn map_key_to_action(key: KeyEvent, input_mode: InputMode, current_tab: Tab) -> Option<Action> {
+pub fn map_key_to_action(key: KeyEvent, state: &AppState) -> Option<Action> {
     use crossterm::event::KeyCode;
 
-    if input_mode != InputMode::Normal {
+    if state.confirmation.is_some() {
+        return match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                Some(Action::ConfirmProcessCommand)
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                Some(Action::CancelProcessCommand)
+            }
+            _ => None,
+        };
+    }
+
+    if state.input_mode != InputMode::Normal {
         match key.code {
             KeyCode::Enter => return Some(Action::InputSubmit),
             KeyCode::Esc => return Some(Action::ExitInputMode),
@@ -71,9 +90,23 @@ pub fn map_key_to_action(key: KeyEvent, input_mode: InputMode, current_tab: Tab)
         }
     }
 
+    // The detail pane takes Esc and Enter (to close), 'r' and 'H'; scrolling
+    // and the other keys keep their usual meaning.
+    if state.current_tab == Tab::Processes
+        && let Some(view) = &state.process_details
+    {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => return Some(Action::CloseProcessDetails),
+            KeyCode::Char('r') => return Some(Action::LoadProcessDetails(view.target)),
+            KeyCode::Char('H') => return Some(Action::ToggleThreadView),
+            _ => {}
+        }
+    }
+
     match key.code {
         KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
         KeyCode::Char('?') => return Some(Action::ToggleHelp),
+        KeyCode::Char('D') => return Some(Action::ToggleDryRun),
         KeyCode::Tab => return Some(Action::NextTab),
         KeyCode::BackTab => return Some(Action::PreviousTab),
         KeyCode::Char('1') => return Some(Action::SwitchTab(Tab::Overview)),
@@ -86,6 +119,9 @@ pub fn map_key_to_action(key: KeyEvent, input_mode: InputMode, current_tab: Tab)
             return Some(Action::SwitchTab(Tab::Notifications));
         }
         KeyCode::Char('8') | KeyCode::Char('z') => return Some(Action::SwitchTab(Tab::Settings)),
+        KeyCode::Char('9') => return Some(Action::SwitchTab(Tab::Audit)),
+        KeyCode::Char('0') => return Some(Action::SwitchTab(Tab::Cgroups)),
+        KeyCode::Char('-') => return Some(Action::SwitchTab(Tab::Managed)),
         KeyCode::Up | KeyCode::Char('k') => return Some(Action::ScrollUp),
         KeyCode::Down | KeyCode::Char('j') => return Some(Action::ScrollDown),
         KeyCode::PageUp => return Some(Action::PageUp),
@@ -95,21 +131,73 @@ pub fn map_key_to_action(key: KeyEvent, input_mode: InputMode, current_tab: Tab)
         _ => {}
     }
 
-    match current_tab {
+    match state.current_tab {
         Tab::Processes => match key.code {
-            KeyCode::Char('K') => Some(Action::KillSelectedProcess),
-            KeyCode::Char('s') => Some(Action::SuspendSelectedProcess),
-            KeyCode::Char('c') => Some(Action::ContinueSelectedProcess),
-            KeyCode::Char('t') => Some(Action::TerminateSelectedProcess),
+            KeyCode::Char('K') => Some(Action::RequestProcessCommand(ProcessCommand::Kill)),
+            KeyCode::Char('s') => Some(Action::RequestProcessCommand(ProcessCommand::Suspend)),
+            KeyCode::Char('c') => Some(Action::RequestProcessCommand(ProcessCommand::Continue)),
+            KeyCode::Char('t') => Some(Action::RequestProcessCommand(ProcessCommand::Terminate)),
+            KeyCode::Char('e') => Some(Action::RequestProcessCommand(ProcessCommand::Stop)),
             KeyCode::Char('r') => Some(Action::LoadProcessList),
             KeyCode::Char('p') => Some(Action::SortByColumn(0)),
             KeyCode::Char('n') => Some(Action::SortByColumn(1)),
             KeyCode::Char('C') => Some(Action::SortByColumn(2)),
             KeyCode::Char('M') => Some(Action::SortByColumn(3)),
+            KeyCode::Char('<') => Some(Action::PreviousSortColumn),
+            KeyCode::Char('>') => Some(Action::NextSortColumn),
+            KeyCode::Char('U') => Some(Action::ToggleMemoryAccounting),
             KeyCode::Char('/') => Some(Action::EnterInputMode(InputMode::SearchProcess)),
             KeyCode::Char('x') => Some(Action::ClearFilter),
+            KeyCode::Char('E') => Some(Action::ToggleElevatedFilter),
+            KeyCode::Char('N') => Some(Action::EnterInputMode(InputMode::EditNice)),
+            KeyCode::Char('P') => Some(Action::EnterInputMode(InputMode::EditScheduler)),
+            KeyCode::Char('A') => Some(Action::EnterInputMode(InputMode::EditAffinity)),
+            KeyCode::Char('I') => Some(Action::EnterInputMode(InputMode::EditIoPriority)),
+            KeyCode::Char('g') => Some(Action::EnterInputMode(InputMode::EditCgroup)),
+            KeyCode::Char('L') => Some(Action::EnterInputMode(InputMode::EditCgroupLimit)),
+            KeyCode::Char('R') => Some(Action::EnterInputMode(InputMode::EditLimit)),
+            KeyCode::Char('O') => Some(Action::EnterInputMode(InputMode::EditOomScoreAdj)),
+            KeyCode::Char('w') => Some(Action::WatchSelected {
+                alert_channels: false,
+            }),
+            KeyCode::Char('W') => Some(Action::WatchSelected {
+                alert_channels: true,
+            }),
+            KeyCode::Char(' ') => Some(Action::ToggleMarkSelected),
+            KeyCode::Char('a') => Some(Action::MarkAllFiltered),
+            KeyCode::Char('u') => Some(Action::ClearMarks),
+            KeyCode::Char('S') => Some(Action::MarkSubtree),
+            KeyCode::Char('T') => Some(Action::ToggleTreeView),
+            KeyCode::Char('G') => Some(Action::ToggleGroupByContainer),
+            KeyCode::Enter => Some(Action::OpenProcessDetails),
+            KeyCode::Left | KeyCode::Char('h') => Some(Action::CollapseSelected),
+            KeyCode::Right | KeyCode::Char('l') => Some(Action::ExpandSelected),
+            _ => None,
+        },
+        Tab::Memory => match key.code {
+            KeyCode::Char('U') => Some(Action::ToggleMemoryAccounting),
             _ => None,
         },
+        Tab::Audit => match key.code {
+            KeyCode::Char('r') => Some(Action::LoadAuditLog),
+            _ => None,
+        },
+        Tab::Managed => {
+            let selected = state
+                .managed_processes
+                .get(state.managed_selected)
+                .map(|managed| managed.id);
+            let on_selected = |command: fn(u64) -> ManagedCommand| {
+                selected.map(|id| Action::RequestManagedCommand(command(id)))
+            };
+            match key.code {
+                KeyCode::Char('a') => Some(Action::EnterInputMode(InputMode::SpawnCommand)),
+                KeyCode::Char('s') => on_selected(ManagedCommand::Stop),
+                KeyCode::Char('r') => on_selected(ManagedCommand::Restart),
+                KeyCode::Char('x') => on_selected(ManagedCommand::Remove),
+                _ => None,
+            }
+        }
         Tab::Notifications => match key.code {
             KeyCode::Char('m') => Some(Action::MarkAllNotificationsRead),
             KeyCode::Char('x') => Some(Action::ClearAllNotifications),
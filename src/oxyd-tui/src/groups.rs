@@ -0,0 +1,84 @@
+use oxyd_domain::Process;
+use std::collections::HashMap;
+
+pub const HOST_GROUP: &str = "(host)";
+
+// One visible line of the group-by-container view, parallel to the process
+// it belongs to in `AppState::filtered_process_list`.
+#[derive(Debug, Clone, Default)]
+pub struct GroupRow {
+    // Container name or short ID, or `HOST_GROUP`.
+    pub label: String,
+    // First visible row of its group; the totals are shown there.
+    pub first: bool,
+    // Totals over every process of the container, whether visible or not.
+    pub size: usize,
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+}
+
+// Orders `processes` by container, busiest container first and host
+// processes last. Processes keep the order of `processes` within their
+// group, so sorting the input sorts each group. Returns the index of each
+// process matching the filter with its row.
+pub fn group_by_container(
+    processes: &[Process],
+    matches: impl Fn(&Process) -> bool,
+) -> Vec<(usize, GroupRow)> {
+    // Keyed by container ID; `None` is the host.
+    let mut groups: Vec<(Option<&str>, GroupRow, Vec<usize>)> = Vec::new();
+    let mut by_id: HashMap<Option<&str>, usize> = HashMap::new();
+
+    for (index, process) in processes.iter().enumerate() {
+        let key = process.container.as_ref().map(|c| c.id.as_str());
+        let slot = *by_id.entry(key).or_insert_with(|| {
+            let label = process
+                .container
+                .as_ref()
+                .map_or(HOST_GROUP, |c| c.label())
+                .to_string();
+            groups.push((
+                key,
+                GroupRow {
+                    label,
+                    ..Default::default()
+                },
+                Vec::new(),
+            ));
+            groups.len() - 1
+        });
+
+        let (_, totals, members) = &mut groups[slot];
+        totals.size += 1;
+        totals.cpu_percent += process.cpu_usage_percent;
+        totals.memory_bytes += process.memory_usage_bytes;
+        if matches(process) {
+            members.push(index);
+        }
+    }
+
+    groups.sort_by(|(a_key, a, _), (b_key, b, _)| {
+        a_key
+            .is_none()
+            .cmp(&b_key.is_none())
+            .then(b.cpu_percent.total_cmp(&a.cpu_percent))
+    });
+
+    groups
+        .into_iter()
+        .flat_map(|(_, totals, members)| {
+            members
+                .into_iter()
+                .enumerate()
+                .map(move |(position, index)| {
+                    (
+                        index,
+                        GroupRow {
+                            first: position == 0,
+                            ..totals.clone()
+                        },
+                    )
+                })
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
-use std::collections::VecDeque;
+use oxyd_domain::{Process, ProcessIdentity};
+use std::collections::{HashMap, VecDeque};
 
 const MAX_HISTORY: usize = 60;
 
@@ -12,6 +13,12 @@ pub struct MetricsHistory {
     pub disk_write: VecDeque<u64>,
 }
 
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MetricsHistory {
     pub fn new() -> Self {
         Self {
@@ -72,3 +79,54 @@ impl MetricsHistory {
         self.network_rx.iter().copied().collect()
     }
 }
+
+// CPU and memory samples of one process, one per process list refresh.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSamples {
+    pub cpu_usage: VecDeque<f64>,
+    pub memory_bytes: VecDeque<u64>,
+}
+
+impl ProcessSamples {
+    // CPU in tenths of a percent, so low-usage processes still draw.
+    pub fn cpu_data(&self) -> Vec<u64> {
+        self.cpu_usage.iter().map(|&x| (x * 10.0) as u64).collect()
+    }
+
+    pub fn memory_data(&self) -> Vec<u64> {
+        self.memory_bytes.iter().copied().collect()
+    }
+}
+
+// Per-process sample history for the detail view sparklines. Processes
+// that disappear from the list are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessHistory {
+    samples: HashMap<ProcessIdentity, ProcessSamples>,
+}
+
+impl ProcessHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, processes: &[Process]) {
+        let mut samples = HashMap::with_capacity(processes.len());
+        for process in processes {
+            let identity = process.identity();
+            let mut entry = self.samples.remove(&identity).unwrap_or_default();
+            if entry.cpu_usage.len() >= MAX_HISTORY {
+                entry.cpu_usage.pop_front();
+                entry.memory_bytes.pop_front();
+            }
+            entry.cpu_usage.push_back(process.cpu_usage_percent);
+            entry.memory_bytes.push_back(process.memory_usage_bytes);
+            samples.insert(identity, entry);
+        }
+        self.samples = samples;
+    }
+
+    pub fn get(&self, identity: &ProcessIdentity) -> Option<&ProcessSamples> {
+        self.samples.get(identity)
+    }
+}
@@ -1,12 +1,18 @@
 pub mod app;
+pub mod commands;
 pub mod ui;
 pub mod event;
 pub mod tabs;
 pub mod history;
 pub mod notifications;
+pub mod tree;
+pub mod groups;
 
 pub use app::{App, AppState};
+pub use commands::{
+    BatchOutcome, ManagedCommand, ProcessBatch, ProcessCommand, ProcessRequest, ProcessTarget,
+};
 pub use event::{Event, EventHandler};
 pub use tabs::Tab;
-pub use history::MetricsHistory;
+pub use history::{MetricsHistory, ProcessHistory};
 pub use notifications::{Notification, NotificationManager, NotificationLevel};
@@ -71,12 +71,11 @@ impl NotificationManager {
     }
 
     pub fn add(&mut self, notification: Notification) {
-        if self.notifications.len() >= MAX_NOTIFICATIONS {
-            if let Some(removed) = self.notifications.pop_front() {
-                if !removed.read {
-                    self.unread_count = self.unread_count.saturating_sub(1);
-                }
-            }
+        if self.notifications.len() >= MAX_NOTIFICATIONS
+            && let Some(removed) = self.notifications.pop_front()
+            && !removed.read
+        {
+            self.unread_count = self.unread_count.saturating_sub(1);
         }
         
         self.unread_count += 1;
@@ -100,11 +99,11 @@ impl NotificationManager {
     }
 
     pub fn mark_read(&mut self, id: usize) {
-        if let Some(notif) = self.notifications.iter_mut().find(|n| n.id == id) {
-            if !notif.read {
-                notif.read = true;
-                self.unread_count = self.unread_count.saturating_sub(1);
-            }
+        if let Some(notif) = self.notifications.iter_mut().find(|n| n.id == id)
+            && !notif.read
+        {
+            notif.read = true;
+            self.unread_count = self.unread_count.saturating_sub(1);
         }
     }
 
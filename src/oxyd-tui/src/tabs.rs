@@ -8,6 +8,9 @@ pub enum Tab {
     Disk,
     Notifications,
     Settings,
+    Audit,
+    Cgroups,
+    Managed,
 }
 
 impl Tab {
@@ -20,13 +23,16 @@ impl Tab {
             Tab::Network => Tab::Disk,
             Tab::Disk => Tab::Notifications,
             Tab::Notifications => Tab::Settings,
-            Tab::Settings => Tab::Overview,
+            Tab::Settings => Tab::Audit,
+            Tab::Audit => Tab::Cgroups,
+            Tab::Cgroups => Tab::Managed,
+            Tab::Managed => Tab::Overview,
         }
     }
 
     pub fn previous(&self) -> Self {
         match self {
-            Tab::Overview => Tab::Settings,
+            Tab::Overview => Tab::Managed,
             Tab::Cpu => Tab::Overview,
             Tab::Memory => Tab::Cpu,
             Tab::Processes => Tab::Memory,
@@ -34,6 +40,9 @@ impl Tab {
             Tab::Disk => Tab::Network,
             Tab::Notifications => Tab::Disk,
             Tab::Settings => Tab::Notifications,
+            Tab::Audit => Tab::Settings,
+            Tab::Cgroups => Tab::Audit,
+            Tab::Managed => Tab::Cgroups,
         }
     }
 
@@ -47,6 +56,9 @@ impl Tab {
             Tab::Disk => "Disk",
             Tab::Notifications => "Notifications",
             Tab::Settings => "Settings",
+            Tab::Audit => "Audit",
+            Tab::Cgroups => "Cgroups",
+            Tab::Managed => "Managed",
         }
     }
 
@@ -60,6 +72,9 @@ impl Tab {
             Tab::Disk,
             Tab::Notifications,
             Tab::Settings,
+            Tab::Audit,
+            Tab::Cgroups,
+            Tab::Managed,
         ]
     }
 }
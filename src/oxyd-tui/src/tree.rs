@@ -0,0 +1,177 @@
+use oxyd_domain::{Process, ProcessIdentity};
+use std::collections::{HashMap, HashSet};
+
+// One visible line of the process tree, parallel to the process it belongs
+// to in `AppState::filtered_process_list`.
+#[derive(Debug, Clone, Default)]
+pub struct TreeRow {
+    pub depth: usize,
+    // Box-drawing guides drawn before the name, e.g. "│  ├─ ".
+    pub prefix: String,
+    pub has_children: bool,
+    pub collapsed: bool,
+    // Only shown because a descendant matches the filter.
+    pub context_only: bool,
+    // Totals over the process and all of its descendants, whether visible
+    // or not.
+    pub subtree_cpu_percent: f64,
+    pub subtree_memory_bytes: u64,
+    pub subtree_size: usize,
+}
+
+// Indices into `processes`, grouped by parent. Children keep the order of
+// `processes`, so sorting the input sorts siblings.
+struct Hierarchy {
+    roots: Vec<usize>,
+    children: HashMap<usize, Vec<usize>>,
+}
+
+impl Hierarchy {
+    fn new(processes: &[Process]) -> Self {
+        let by_pid: HashMap<u32, usize> = processes
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.pid, i))
+            .collect();
+
+        let mut roots = Vec::new();
+        let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (index, process) in processes.iter().enumerate() {
+            match process
+                .ppid
+                .filter(|&ppid| ppid != process.pid)
+                .and_then(|ppid| by_pid.get(&ppid))
+            {
+                Some(&parent) => children.entry(parent).or_default().push(index),
+                None => roots.push(index),
+            }
+        }
+
+        Self { roots, children }
+    }
+
+    fn children(&self, index: usize) -> &[usize] {
+        self.children.get(&index).map_or(&[], |c| c.as_slice())
+    }
+}
+
+// Flattens `processes` into tree order. Subtrees of `collapsed` processes
+// are hidden; with a filter, non-matching processes are kept only as
+// ancestors of a match. Returns the index of each visible process with its
+// row.
+pub fn build_tree(
+    processes: &[Process],
+    collapsed: &HashSet<ProcessIdentity>,
+    matches: impl Fn(&Process) -> bool,
+) -> Vec<(usize, TreeRow)> {
+    let mut builder = TreeBuilder {
+        processes,
+        hierarchy: Hierarchy::new(processes),
+        collapsed,
+        matches,
+        totals: vec![(0.0, 0, 0); processes.len()],
+        relevant: vec![false; processes.len()],
+        rows: Vec::new(),
+    };
+
+    let roots = builder.hierarchy.roots.clone();
+    for &root in &roots {
+        builder.accumulate(root);
+    }
+    let visible_roots = roots.into_iter().filter(|&i| builder.relevant[i]).collect();
+    builder.emit(visible_roots, 0, "");
+    builder.rows
+}
+
+struct TreeBuilder<'a, F> {
+    processes: &'a [Process],
+    hierarchy: Hierarchy,
+    collapsed: &'a HashSet<ProcessIdentity>,
+    matches: F,
+    // (CPU %, memory bytes, process count) of each subtree.
+    totals: Vec<(f64, u64, usize)>,
+    // Whether anything in the subtree matches the filter.
+    relevant: Vec<bool>,
+    rows: Vec<(usize, TreeRow)>,
+}
+
+impl<F: Fn(&Process) -> bool> TreeBuilder<'_, F> {
+    // Post-order pass filling in `totals` and `relevant`.
+    fn accumulate(&mut self, index: usize) {
+        let process = &self.processes[index];
+        let mut total = (process.cpu_usage_percent, process.memory_usage_bytes, 1);
+        let mut any_match = (self.matches)(process);
+
+        for child in self.hierarchy.children(index).to_vec() {
+            self.accumulate(child);
+            total.0 += self.totals[child].0;
+            total.1 += self.totals[child].1;
+            total.2 += self.totals[child].2;
+            any_match |= self.relevant[child];
+        }
+
+        self.totals[index] = total;
+        self.relevant[index] = any_match;
+    }
+
+    fn emit(&mut self, siblings: Vec<usize>, depth: usize, guides: &str) {
+        let count = siblings.len();
+        for (position, index) in siblings.into_iter().enumerate() {
+            let process = &self.processes[index];
+            let last = position + 1 == count;
+            let children: Vec<usize> = self
+                .hierarchy
+                .children(index)
+                .iter()
+                .copied()
+                .filter(|&c| self.relevant[c])
+                .collect();
+            let collapsed = self.collapsed.contains(&process.identity());
+
+            let prefix = if depth == 0 {
+                String::new()
+            } else {
+                format!("{}{}", guides, if last { "└─ " } else { "├─ " })
+            };
+            let (cpu, memory, size) = self.totals[index];
+            self.rows.push((
+                index,
+                TreeRow {
+                    depth,
+                    prefix,
+                    has_children: !children.is_empty(),
+                    collapsed,
+                    context_only: !(self.matches)(process),
+                    subtree_cpu_percent: cpu,
+                    subtree_memory_bytes: memory,
+                    subtree_size: size,
+                },
+            ));
+
+            if !collapsed && !children.is_empty() {
+                let child_guides = if depth == 0 {
+                    String::new()
+                } else {
+                    format!("{}{}", guides, if last { "   " } else { "│  " })
+                };
+                self.emit(children, depth + 1, &child_guides);
+            }
+        }
+    }
+}
+
+// `root` and all of its descendants, parents before children.
+pub fn subtree(processes: &[Process], root: ProcessIdentity) -> Vec<&Process> {
+    let hierarchy = Hierarchy::new(processes);
+    let Some(start) = processes.iter().position(|p| p.identity() == root) else {
+        return Vec::new();
+    };
+
+    let mut result = Vec::new();
+    let mut queue = vec![start];
+    while let Some(index) = queue.pop() {
+        result.push(&processes[index]);
+        queue.extend(hierarchy.children(index).iter().rev());
+    }
+    result
+}
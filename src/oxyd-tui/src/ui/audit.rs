@@ -0,0 +1,187 @@
+use chrono::Local;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+
+use crate::app::AppState;
+
+pub fn render(f: &mut Frame, area: Rect, app: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Min(10),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    render_summary(f, chunks[0], app);
+    render_audit_table(f, chunks[1], app);
+    render_footer(f, chunks[2]);
+}
+
+fn render_summary(f: &mut Frame, area: Rect, app: &AppState) {
+    let failed = app.audit_records.iter().filter(|r| !r.success).count();
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            "Recorded actions: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            app.audit_records.len().to_string(),
+            Style::default().fg(Color::White),
+        ),
+        Span::styled(
+            "   Failed: ",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(failed.to_string(), Style::default().fg(Color::Red)),
+    ])];
+    if let Some(error) = &app.audit_failure {
+        lines.push(Line::from(Span::styled(
+            format!("Not recording actions: {}", error),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Process Action Audit Log ")
+            .title_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+    );
+    f.render_widget(paragraph, area);
+}
+
+fn render_audit_table(f: &mut Frame, area: Rect, app: &AppState) {
+    let header_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(
+        [
+            "Time", "Actor", "PID", "Name", "Action", "Result", "Details",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(h).style(header_style)),
+    )
+    .height(1)
+    .bottom_margin(1);
+
+    let rows: Vec<Row> = app
+        .audit_records
+        .iter()
+        .rev() // Most recent first
+        .skip(app.audit_scroll)
+        .map(|record| {
+            let (result_str, result_color) = if record.dry_run {
+                ("DRY RUN", Color::Yellow)
+            } else if record.success {
+                ("OK", Color::Green)
+            } else {
+                ("FAILED", Color::Red)
+            };
+            let details = record
+                .error
+                .clone()
+                .or_else(|| record.message.clone())
+                .unwrap_or_default();
+            let name = if record.command.is_empty() {
+                record.name.clone()
+            } else {
+                format!("{} ({})", record.name, record.command)
+            };
+
+            Row::new(vec![
+                Cell::from(
+                    record
+                        .timestamp
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string(),
+                ),
+                Cell::from(truncate_string(&record.actor, 20)),
+                Cell::from(record.pid.to_string()),
+                Cell::from(truncate_string(&name, 30)),
+                Cell::from(record.action.label()),
+                Cell::from(result_str).style(Style::default().fg(result_color)),
+                Cell::from(details),
+            ])
+            .height(1)
+        })
+        .collect();
+
+    let rows = if rows.is_empty() {
+        vec![Row::new(vec![
+            Cell::from("No process actions recorded yet")
+                .style(Style::default().fg(Color::DarkGray)),
+        ])]
+    } else {
+        rows
+    };
+
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Length(20), // time
+            Constraint::Length(20), // actor
+            Constraint::Length(8),  // pID
+            Constraint::Length(30), // name
+            Constraint::Length(16), // action
+            Constraint::Length(7),  // result
+            Constraint::Min(30),    // details
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Actions ")
+            .style(Style::default().fg(Color::White)),
+    );
+
+    f.render_widget(table, area);
+}
+
+fn render_footer(f: &mut Frame, area: Rect) {
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled("Press ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "r",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" to reload | ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "↑/↓",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" to scroll", Style::default().fg(Color::DarkGray)),
+    ]))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(footer, area);
+}
+
+fn truncate_string(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", truncated)
+    }
+}
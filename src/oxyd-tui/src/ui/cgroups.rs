@@ -0,0 +1,205 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+
+use super::widgets::format_bytes;
+use crate::app::AppState;
+use oxyd_domain::models::{CgroupStats, SystemMetrics};
+
+pub fn render(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(10)])
+        .split(area);
+
+    render_summary(f, chunks[0], &metrics.cgroups);
+    render_cgroup_table(f, chunks[1], &metrics.cgroups, app);
+}
+
+fn render_summary(f: &mut Frame, area: Rect, cgroups: &[CgroupStats]) {
+    let label = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let over_limit = cgroups
+        .iter()
+        .filter(|c| c.memory_percent_of_limit().is_some_and(|p| p >= 90.0))
+        .count();
+    let pressure = cgroups
+        .first()
+        .and_then(|root| root.cpu_pressure)
+        .map_or_else(|| "-".to_string(), |p| format!("{:.2}%", p.some.avg10));
+
+    let line = Line::from(vec![
+        Span::styled("Cgroups: ", label),
+        Span::raw(cgroups.len().to_string()),
+        Span::styled("   Near memory limit (≥90%): ", label),
+        Span::styled(
+            over_limit.to_string(),
+            Style::default().fg(if over_limit > 0 {
+                Color::Red
+            } else {
+                Color::White
+            }),
+        ),
+        Span::styled("   System CPU pressure (some, avg10): ", label),
+        Span::raw(pressure),
+    ]);
+
+    let paragraph = Paragraph::new(line).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Cgroups (v2) ")
+            .title_style(label),
+    );
+    f.render_widget(paragraph, area);
+}
+
+fn render_cgroup_table(f: &mut Frame, area: Rect, cgroups: &[CgroupStats], app: &AppState) {
+    let header_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(
+        [
+            "Cgroup", "CPU%", "CPU Max", "Memory", "Mem Max", "Mem%", "Pids", "IO Read",
+            "IO Write", "CPU PSI",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(h).style(header_style)),
+    )
+    .height(1)
+    .bottom_margin(1);
+
+    let prefixes = tree_prefixes(cgroups);
+    let rows: Vec<Row> = cgroups
+        .iter()
+        .zip(prefixes)
+        .skip(app.cgroup_scroll)
+        .map(|(cgroup, prefix)| {
+            let memory_percent = cgroup.memory_percent_of_limit();
+            let memory_color = match memory_percent {
+                Some(p) if p >= 90.0 => Color::Red,
+                Some(p) if p >= 75.0 => Color::Yellow,
+                _ => Color::Green,
+            };
+            let cpu_color = match cgroup.cpu_limit {
+                Some(limit) if cgroup.cpu_usage_percent >= limit * 90.0 => Color::Red,
+                _ => Color::White,
+            };
+
+            Row::new(vec![
+                Cell::from(format!("{}{}", prefix, cgroup.name())),
+                Cell::from(format!("{:.1}", cgroup.cpu_usage_percent))
+                    .style(Style::default().fg(cpu_color)),
+                Cell::from(
+                    cgroup
+                        .cpu_limit
+                        .map_or_else(|| "-".to_string(), |cpus| format!("{:.2}", cpus)),
+                ),
+                Cell::from(optional_bytes(cgroup.memory_current_bytes)),
+                Cell::from(optional_bytes(cgroup.memory_max_bytes)),
+                Cell::from(
+                    memory_percent.map_or_else(|| "-".to_string(), |p| format!("{:.1}%", p)),
+                )
+                .style(Style::default().fg(memory_color)),
+                Cell::from(match (cgroup.pids_current, cgroup.pids_max) {
+                    (Some(current), Some(max)) => format!("{}/{}", current, max),
+                    (Some(current), None) => current.to_string(),
+                    _ => "-".to_string(),
+                }),
+                Cell::from(format_bytes(cgroup.io_read_bytes)),
+                Cell::from(format_bytes(cgroup.io_write_bytes)),
+                Cell::from(
+                    cgroup
+                        .cpu_pressure
+                        .map_or_else(|| "-".to_string(), |p| format!("{:.2}", p.some.avg10)),
+                ),
+            ])
+            .height(1)
+        })
+        .collect();
+
+    let rows = if rows.is_empty() {
+        vec![Row::new(vec![
+            Cell::from("No cgroup v2 hierarchy found at /sys/fs/cgroup")
+                .style(Style::default().fg(Color::DarkGray)),
+        ])]
+    } else {
+        rows
+    };
+
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Min(30),    // cgroup
+            Constraint::Length(7),  // CPU
+            Constraint::Length(8),  // CPU max
+            Constraint::Length(11), // memory
+            Constraint::Length(11), // memory max
+            Constraint::Length(7),  // memory % of max
+            Constraint::Length(12), // pids
+            Constraint::Length(11), // IO read
+            Constraint::Length(11), // IO write
+            Constraint::Length(8),  // CPU pressure
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Slices and scopes ")
+            .title_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White)),
+    );
+
+    f.render_widget(table, area);
+}
+
+fn optional_bytes(bytes: Option<u64>) -> String {
+    bytes.map_or_else(|| "-".to_string(), format_bytes)
+}
+
+// Box-drawing guides for `cgroups`, which are in depth-first order with
+// parents before children.
+fn tree_prefixes(cgroups: &[CgroupStats]) -> Vec<String> {
+    let depths: Vec<usize> = cgroups.iter().map(|c| c.depth()).collect();
+
+    // Walking backwards, a row is the last of its siblings unless a later
+    // row at the same depth was seen before reaching its parent.
+    let mut last = vec![false; depths.len()];
+    let mut later_sibling: Vec<bool> = Vec::new();
+    for (i, &depth) in depths.iter().enumerate().rev() {
+        later_sibling.resize(depth + 1, false);
+        last[i] = !later_sibling[depth];
+        later_sibling[depth] = true;
+    }
+
+    let mut ancestors_last: Vec<bool> = Vec::new();
+    depths
+        .iter()
+        .zip(last)
+        .map(|(&depth, is_last)| {
+            ancestors_last.truncate(depth);
+            let prefix = if depth == 0 {
+                String::new()
+            } else {
+                let guides: String = ancestors_last
+                    .iter()
+                    .skip(1)
+                    .map(|&ancestor_last| if ancestor_last { "   " } else { "│  " })
+                    .collect();
+                format!("{}{}", guides, if is_last { "└─ " } else { "├─ " })
+            };
+            ancestors_last.resize(depth, false);
+            ancestors_last.push(is_last);
+            prefix
+        })
+        .collect()
+}
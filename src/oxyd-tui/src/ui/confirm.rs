@@ -0,0 +1,121 @@
+use chrono::Local;
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::help::centered_rect;
+use crate::commands::{ProcessBatch, ProcessTarget};
+
+// Batches list this many targets and summarise the rest.
+const MAX_LISTED_TARGETS: usize = 10;
+
+pub fn render_confirmation(f: &mut Frame, batch: &ProcessBatch, dry_run: bool) {
+    let area = centered_rect(60, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let question = match batch.targets.as_slice() {
+        [target] => batch.command.describe(&target.name),
+        _ => batch.describe(),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Really {}?", question),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    match batch.targets.as_slice() {
+        [target] => lines.extend(target_details(target)),
+        targets => lines.extend(target_list(targets)),
+    }
+    lines.push(Line::from(""));
+
+    if dry_run {
+        lines.push(Line::from(Span::styled(
+            "Dry-run is on: nothing will be sent.",
+            Style::default().fg(Color::Magenta),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "y / Enter: confirm | n / Esc: cancel",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Confirm ")
+                .border_style(Style::default().fg(Color::Red)),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn target_details(target: &ProcessTarget) -> Vec<Line<'static>> {
+    let label = Style::default().fg(Color::Cyan);
+    let value = Style::default().fg(Color::White);
+
+    vec![
+        Line::from(vec![
+            Span::styled("PID:      ", label),
+            Span::styled(target.identity.pid.to_string(), value),
+        ]),
+        Line::from(vec![
+            Span::styled("Name:     ", label),
+            Span::styled(target.name.clone(), value),
+        ]),
+        Line::from(vec![
+            Span::styled("Command:  ", label),
+            Span::styled(target.command_line.clone(), value),
+        ]),
+        Line::from(vec![
+            Span::styled("Started:  ", label),
+            Span::styled(
+                target
+                    .start_time
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+                value,
+            ),
+        ]),
+    ]
+}
+
+fn target_list(targets: &[ProcessTarget]) -> Vec<Line<'static>> {
+    let pid = Style::default().fg(Color::Cyan);
+    let value = Style::default().fg(Color::White);
+
+    let mut lines: Vec<Line> = targets
+        .iter()
+        .take(MAX_LISTED_TARGETS)
+        .map(|target| {
+            Line::from(vec![
+                Span::styled(format!("{:>8}  ", target.identity.pid), pid),
+                Span::styled(target.name.clone(), value),
+                Span::styled(
+                    format!("  {}", target.command_line),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+
+    if targets.len() > MAX_LISTED_TARGETS {
+        lines.push(Line::from(Span::styled(
+            format!("... and {} more", targets.len() - MAX_LISTED_TARGETS),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines
+}
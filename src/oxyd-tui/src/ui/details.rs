@@ -0,0 +1,431 @@
+use chrono::Local;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+
+use super::widgets::{create_sparkline, format_bytes};
+use crate::app::{AppState, ProcessDetailView};
+use oxyd_domain::models::{ProcessDetails, ResourceLimit, format_capabilities, format_cpu_list};
+
+// Memory map groups listed before the rest is summarised.
+const MAX_MAP_GROUPS: usize = 15;
+
+pub fn render(f: &mut Frame, area: Rect, app: &AppState, view: &ProcessDetailView) {
+    let Some(details) = view.details.as_deref() else {
+        let text = match &view.error {
+            Some(error) => format!("Failed to load PID {}: {}", view.target.pid, error),
+            None => format!("Loading details of PID {}...", view.target.pid),
+        };
+        let paragraph = Paragraph::new(text)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(detail_block(format!(" Process {} ", view.target.pid)));
+        f.render_widget(paragraph, area);
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(10), Constraint::Min(5)])
+        .split(area);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[0]);
+
+    render_overview(f, top[0], details, view);
+    render_sparklines(f, top[1], app, view);
+    if view.show_threads {
+        render_threads(f, chunks[1], details, view);
+    } else {
+        render_sections(f, chunks[1], details, view);
+    }
+}
+
+fn detail_block(title: String) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .style(Style::default().fg(Color::White))
+}
+
+fn field(label: &'static str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(label, Style::default().fg(Color::Cyan)),
+        Span::raw(value),
+    ])
+}
+
+fn render_overview(f: &mut Frame, area: Rect, details: &ProcessDetails, view: &ProcessDetailView) {
+    let p = &details.process;
+    let command_line = std::iter::once(p.command.as_str())
+        .chain(p.arguments.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let lines = vec![
+        field("Command: ", command_line),
+        field(
+            "Exe:     ",
+            p.executable_path.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        field(
+            "Cwd:     ",
+            p.working_dir.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        field(
+            "User:    ",
+            format!(
+                "{} (uid {})  Group: {} (gid {})",
+                p.user, p.uids.effective, p.group, p.gids.effective
+            ),
+        ),
+        field(
+            "Parent:  ",
+            p.ppid
+                .map_or_else(|| "-".to_string(), |ppid| ppid.to_string()),
+        ),
+        field(
+            "Started: ",
+            p.start_time
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        ),
+        field(
+            "Sched:   ",
+            format!(
+                "{} nice {}  CPUs {}  I/O {}",
+                p.scheduling_policy.name(),
+                p.nice,
+                format_cpu_list(&p.cpu_affinity),
+                p.io_priority
+                    .map_or_else(|| "-".to_string(), |io| io.label())
+            ),
+        ),
+        field(
+            "Memory:  ",
+            format!(
+                "RSS {}  VSZ {}  OOM score {} (adj {})",
+                format_bytes(p.memory_usage_bytes),
+                format_bytes(p.virtual_memory_bytes),
+                p.oom_score
+                    .map_or_else(|| "-".to_string(), |score| score.to_string()),
+                p.oom_score_adj
+                    .map_or_else(|| "-".to_string(), |adj| adj.to_string())
+            ),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(detail_block(format!(
+            " {} (PID {}) | Esc: close ",
+            p.name, view.target.pid
+        )))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn render_sparklines(f: &mut Frame, area: Rect, app: &AppState, view: &ProcessDetailView) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let samples = app.process_history.get(&view.target);
+    let cpu_data = samples.map(|s| s.cpu_data()).unwrap_or_default();
+    let memory_data = samples.map(|s| s.memory_data()).unwrap_or_default();
+
+    let cpu_title = format!(
+        " CPU {:.1}% ",
+        samples
+            .and_then(|s| s.cpu_usage.back().copied())
+            .unwrap_or(0.0)
+    );
+    let memory_title = format!(
+        " Memory {} ",
+        format_bytes(
+            samples
+                .and_then(|s| s.memory_bytes.back().copied())
+                .unwrap_or(0)
+        )
+    );
+
+    f.render_widget(
+        create_sparkline(&cpu_data, &cpu_title, Color::Green),
+        chunks[0],
+    );
+    f.render_widget(
+        create_sparkline(&memory_data, &memory_title, Color::Magenta),
+        chunks[1],
+    );
+}
+
+fn heading(title: String) -> Line<'static> {
+    Line::from(Span::styled(
+        title,
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn unavailable() -> Line<'static> {
+    Line::from(Span::styled(
+        "  (not readable, try running as root)",
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
+fn format_limit(limit: &ResourceLimit) -> String {
+    let value = |v: Option<u64>| v.map_or_else(|| "unlimited".to_string(), |v| v.to_string());
+    format!(
+        "  {:<26} {:<10} {:>20} {:>20} {}",
+        limit.name,
+        limit.resource.map_or("", |resource| resource.name()),
+        value(limit.soft),
+        value(limit.hard),
+        limit.unit.clone().unwrap_or_default()
+    )
+}
+
+fn render_sections(f: &mut Frame, area: Rect, details: &ProcessDetails, view: &ProcessDetailView) {
+    let mut lines = Vec::new();
+
+    lines.push(heading(format!("Threads ({})", details.threads.len())));
+    lines.push(Line::from(Span::styled(
+        "  Press H to list them by CPU usage",
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines.push(Line::from(""));
+
+    match &details.open_files {
+        Some(files) => {
+            lines.push(heading(format!("Open files ({})", files.len())));
+            for file in files {
+                lines.push(Line::from(format!("  {:>5}  {}", file.fd, file.target)));
+            }
+        }
+        None => {
+            lines.push(heading("Open files".to_string()));
+            lines.push(unavailable());
+        }
+    }
+    lines.push(Line::from(""));
+
+    match &details.memory_maps {
+        Some(maps) => {
+            lines.push(heading(format!(
+                "Memory maps ({} regions, {} mapped)",
+                maps.regions,
+                format_bytes(maps.total_bytes)
+            )));
+            for group in maps.groups.iter().take(MAX_MAP_GROUPS) {
+                lines.push(Line::from(format!(
+                    "  {:>12}  {:>4} regions  {}",
+                    format_bytes(group.size_bytes),
+                    group.regions,
+                    group.name
+                )));
+            }
+            if maps.groups.len() > MAX_MAP_GROUPS {
+                lines.push(Line::from(format!(
+                    "  ... and {} more",
+                    maps.groups.len() - MAX_MAP_GROUPS
+                )));
+            }
+        }
+        None => {
+            lines.push(heading("Memory maps".to_string()));
+            lines.push(unavailable());
+        }
+    }
+    lines.push(Line::from(""));
+
+    lines.push(heading("Cgroups".to_string()));
+    for cgroup in &details.cgroups {
+        lines.push(Line::from(format!("  {}", cgroup)));
+    }
+    if let Some(container) = &details.process.container {
+        lines.push(Line::from(format!(
+            "  container: {} ({}, {})",
+            container.name.as_deref().unwrap_or("-"),
+            container.runtime.name(),
+            container.id
+        )));
+    }
+    lines.push(Line::from(""));
+
+    let security = &details.process.security;
+    lines.push(heading("Security".to_string()));
+    for (label, mask) in [
+        ("Effective", security.cap_effective),
+        ("Permitted", security.cap_permitted),
+        ("Inheritable", security.cap_inheritable),
+        ("Bounding", security.cap_bounding),
+        ("Ambient", security.cap_ambient),
+    ] {
+        let style = if label == "Effective" && mask != 0 {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {:<18} {:016x}  {}",
+                label,
+                mask,
+                format_capabilities(mask)
+            ),
+            style,
+        )));
+    }
+    lines.push(Line::from(format!(
+        "  {:<18} {}",
+        "NoNewPrivs",
+        if security.no_new_privs { "yes" } else { "no" }
+    )));
+    let seccomp = match security.seccomp_filters {
+        0 => security.seccomp.name().to_string(),
+        filters => format!("{} ({} filters)", security.seccomp.name(), filters),
+    };
+    lines.push(Line::from(format!("  {:<18} {}", "Seccomp", seccomp)));
+    lines.push(Line::from(format!(
+        "  {:<18} {}",
+        "LSM label",
+        details.security_label.as_deref().unwrap_or("-")
+    )));
+    lines.push(Line::from(""));
+
+    lines.push(heading("Namespaces".to_string()));
+    match &details.namespaces {
+        Some(namespaces) => {
+            for ns in namespaces {
+                lines.push(Line::from(format!("  {:<18} {}", ns.kind, ns.inode)));
+            }
+        }
+        None => lines.push(unavailable()),
+    }
+    lines.push(Line::from(""));
+
+    lines.push(heading("Limits".to_string()));
+    match &details.limits {
+        Some(limits) => {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {:<26} {:<10} {:>20} {:>20} {}",
+                    "Limit", "Name", "Soft", "Hard", "Units"
+                ),
+                Style::default().fg(Color::Cyan),
+            )));
+            lines.extend(limits.iter().map(|l| Line::from(format_limit(l))));
+        }
+        None => lines.push(unavailable()),
+    }
+    lines.push(Line::from(""));
+
+    match &details.environment {
+        Some(environment) => {
+            lines.push(heading(format!("Environment ({})", environment.len())));
+            for (key, value) in environment {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {}", key), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!("={}", value)),
+                ]));
+            }
+        }
+        None => {
+            lines.push(heading("Environment".to_string()));
+            lines.push(unavailable());
+        }
+    }
+
+    let max_scroll = lines.len().saturating_sub(1);
+    let scroll = view.scroll.min(max_scroll) as u16;
+    let paragraph = Paragraph::new(lines)
+        .block(detail_block(
+            " Details | ↑/↓ PgUp/PgDn: scroll | H: threads | r: reload ".to_string(),
+        ))
+        .scroll((scroll, 0));
+    f.render_widget(paragraph, area);
+}
+
+// Threads as rows, busiest first.
+fn render_threads(f: &mut Frame, area: Rect, details: &ProcessDetails, view: &ProcessDetailView) {
+    let header_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(
+        [
+            "TID", "Name", "State", "CPU%", "Last CPU", "Nice", "Priority",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(h).style(header_style)),
+    )
+    .height(1)
+    .bottom_margin(1);
+
+    let mut threads: Vec<_> = details.threads.iter().collect();
+    threads.sort_by(|a, b| {
+        b.cpu_usage_percent
+            .partial_cmp(&a.cpu_usage_percent)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.tid.cmp(&b.tid))
+    });
+
+    let scroll = view.scroll.min(threads.len().saturating_sub(1));
+    let rows: Vec<Row> = threads
+        .into_iter()
+        .skip(scroll)
+        .map(|thread| {
+            let cpu_color = if thread.cpu_usage_percent > 80.0 {
+                Color::Red
+            } else if thread.cpu_usage_percent > 50.0 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            Row::new(vec![
+                Cell::from(thread.tid.to_string()),
+                Cell::from(thread.name.clone()),
+                Cell::from(format!("{:?}", thread.state)),
+                Cell::from(format!("{:.1}", thread.cpu_usage_percent))
+                    .style(Style::default().fg(cpu_color)),
+                Cell::from(
+                    thread
+                        .last_cpu
+                        .map_or_else(|| "-".to_string(), |cpu| cpu.to_string()),
+                ),
+                Cell::from(thread.nice.to_string()),
+                Cell::from(thread.priority.to_string()),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Length(10), // TID
+            Constraint::Min(16),    // name
+            Constraint::Length(10), // state
+            Constraint::Length(8),  // CPU
+            Constraint::Length(9),  // last CPU
+            Constraint::Length(6),  // nice
+            Constraint::Length(9),  // priority
+        ],
+    )
+    .header(header)
+    .block(detail_block(format!(
+        " Threads ({}) | H: back to details | r: reload ",
+        details.threads.len()
+    )));
+    f.render_widget(table, area);
+}
@@ -66,7 +66,8 @@ fn render_controls(f: &mut Frame, area: Rect) {
             "NAVIGATION",
             vec![
                 ("Tab / Shift+Tab", "Switch between tabs"),
-                ("1-7", "Jump to specific tab"),
+                ("0-9", "Jump to specific tab (0: Cgroups)"),
+                ("-", "Jump to the Managed tab"),
                 ("↑ / k", "Move up"),
                 ("↓ / j", "Move down"),
                 ("PgUp / PgDn", "Page up / down"),
@@ -78,9 +79,39 @@ fn render_controls(f: &mut Frame, area: Rect) {
             vec![
                 ("K (Shift+k)", "Kill selected process (SIGKILL)"),
                 ("t", "Terminate selected process (SIGTERM)"),
+                ("e", "Stop selected process (SIGTERM, then SIGKILL)"),
                 ("s", "Suspend selected process (SIGSTOP)"),
                 ("c", "Continue selected process (SIGCONT)"),
+                ("N (Shift+n)", "Renice selected process"),
+                ("P (Shift+p)", "Set scheduling policy / RT priority"),
+                ("A (Shift+a)", "Set CPU affinity"),
+                ("I (Shift+i)", "Set I/O priority"),
+                ("g", "Move process to another cgroup"),
+                ("L (Shift+l)", "Set a CPU / memory / I/O limit on its cgroup"),
+                ("R (Shift+r)", "Set a resource limit (nofile, core, as, nproc, ...)"),
+                ("O (Shift+o)", "Set oom_score_adj (-1000 never kill .. 1000 kill first)"),
+                ("w", "Watch / unwatch: notify when the process exits"),
+                ("W (Shift+w)", "Watch and also send the exit to the alert channels"),
+                ("Space", "Mark / unmark selected process"),
+                ("a", "Mark all processes matching the filter"),
+                ("u", "Clear marks"),
+                ("Enter", "Open / close process details"),
+                ("H (Shift+h)", "Details: toggle thread list"),
+                ("S (Shift+s)", "Mark selected process and its subtree"),
+                ("T (Shift+t)", "Toggle tree view"),
+                ("G (Shift+g)", "Group by container"),
+                ("← / h, → / l", "Collapse / expand subtree (tree view)"),
                 ("r", "Refresh process list"),
+                ("D (Shift+d)", "Toggle dry-run (log actions, send nothing)"),
+            ],
+        ),
+        (
+            "MANAGED COMMANDS",
+            vec![
+                ("a", "Start a command (restart=, nice=, cwd=, cgroup=, NAME=value)"),
+                ("s", "Stop the selected command for good"),
+                ("r", "Restart the selected command now"),
+                ("x", "Remove an ended command from the list"),
             ],
         ),
         (
@@ -90,6 +121,8 @@ fn render_controls(f: &mut Frame, area: Rect) {
                 ("n", "Sort by Name"),
                 ("C (Shift+c)", "Sort by CPU usage"),
                 ("m", "Sort by Memory usage"),
+                ("< / >", "Previous / next sort column (incl. PSS, USS, Swap)"),
+                ("U (Shift+u)", "Toggle PSS/USS/swap accounting"),
             ],
         ),
         (
@@ -97,6 +130,19 @@ fn render_controls(f: &mut Frame, area: Rect) {
             vec![
                 ("?", "Toggle this help screen"),
                 ("f", "Open filter/search"),
+                (
+                    "user:<name>",
+                    "Search filter: only show one user's processes",
+                ),
+                (
+                    "container:<name>",
+                    "Search filter: only show one container's processes",
+                ),
+                (
+                    "cap:<name>",
+                    "Search filter: processes holding a capability (cap:any for any)",
+                ),
+                ("E (Shift+e)", "Toggle the cap:any filter (elevated processes)"),
                 ("z", "Open alerts configuration"),
                 ("n", "Open notifications"),
                 ("q / Esc", "Quit application"),
@@ -176,7 +222,7 @@ fn render_help_footer(f: &mut Frame, area: Rect) {
     f.render_widget(footer, area);
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -0,0 +1,264 @@
+use chrono::Utc;
+use oxyd_domain::{ManagedProcess, ManagedState, OutputStream};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+
+use super::widgets::format_duration;
+use crate::app::{AppState, InputMode};
+
+pub fn render(f: &mut Frame, area: Rect, app: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Min(8),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    render_managed_table(f, chunks[0], app);
+    render_output(f, chunks[1], app);
+    render_status_bar(f, chunks[2], app);
+}
+
+fn render_managed_table(f: &mut Frame, area: Rect, app: &AppState) {
+    let header_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(
+        [
+            "ID",
+            "PID",
+            "State",
+            "Restarts",
+            "Uptime",
+            "Last exit",
+            "Policy",
+            "Command",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(h).style(header_style)),
+    )
+    .height(1)
+    .bottom_margin(1);
+
+    let selected_style = Style::default()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD);
+
+    let rows: Vec<Row> = app
+        .managed_processes
+        .iter()
+        .enumerate()
+        .map(|(index, managed)| {
+            let last_exit = managed.last_exit.map(|exit| {
+                let color = if exit.success() {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                (exit.label(), color)
+            });
+            let (last_exit, exit_color) = last_exit.unwrap_or(("-".to_string(), Color::DarkGray));
+
+            let row = Row::new(vec![
+                Cell::from(format!("#{}", managed.id)),
+                Cell::from(
+                    managed
+                        .pid
+                        .map_or_else(|| "-".to_string(), |pid| pid.to_string()),
+                ),
+                Cell::from(managed.state.name())
+                    .style(Style::default().fg(state_color(managed.state))),
+                Cell::from(managed.restarts.to_string()),
+                Cell::from(uptime(managed)),
+                Cell::from(last_exit).style(Style::default().fg(exit_color)),
+                Cell::from(managed.spec.restart.name()),
+                Cell::from(managed.spec.command_line()),
+            ])
+            .height(1);
+
+            if index == app.managed_selected {
+                row.style(selected_style)
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let rows = if rows.is_empty() {
+        vec![Row::new(vec![
+            Cell::from("Nothing started yet. Press 'a' to start a command.")
+                .style(Style::default().fg(Color::DarkGray)),
+        ])]
+    } else {
+        rows
+    };
+
+    let table = Table::new(
+        rows,
+        vec![
+            Constraint::Length(5),  // iD
+            Constraint::Length(8),  // pID
+            Constraint::Length(8),  // state
+            Constraint::Length(9),  // restarts
+            Constraint::Length(16), // uptime
+            Constraint::Length(10), // last exit
+            Constraint::Length(11), // policy
+            Constraint::Min(30),    // command
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " Managed Processes ({} running) ",
+                app.managed_processes
+                    .iter()
+                    .filter(|m| m.state == ManagedState::Running)
+                    .count()
+            ))
+            .title_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().fg(Color::White)),
+    );
+
+    f.render_widget(table, area);
+}
+
+// The newest lines of the selected command's output that fit, stderr in red.
+fn render_output(f: &mut Frame, area: Rect, app: &AppState) {
+    let Some(managed) = app.managed_processes.get(app.managed_selected) else {
+        let block = Block::default().borders(Borders::ALL).title(" Output ");
+        f.render_widget(block, area);
+        return;
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(error) = &managed.error {
+        lines.push(Line::from(Span::styled(
+            format!("Last start failed: {}", error),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    let visible = (area.height as usize)
+        .saturating_sub(2)
+        .saturating_sub(lines.len());
+    let skip = managed.output.len().saturating_sub(visible);
+    lines.extend(managed.output.iter().skip(skip).map(|line| {
+        let style = match line.stream {
+            OutputStream::Stdout => Style::default().fg(Color::White),
+            OutputStream::Stderr => Style::default().fg(Color::Red),
+        };
+        Line::from(Span::styled(line.text.clone(), style))
+    }));
+
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No output yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " Output of #{} {} ",
+                managed.id, managed.spec.command
+            ))
+            .title_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+    );
+    f.render_widget(paragraph, area);
+}
+
+fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
+    if app.input_mode == InputMode::SpawnCommand {
+        let input = Paragraph::new(format!("{}_", app.input_buffer))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(
+                        " [restart=never|on-failure|always] [nice=N] [cwd=DIR] [cgroup=PATH] [NAME=value] command args | Enter: start | Esc: cancel ",
+                    )
+                    .title_style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .style(Style::default().fg(Color::White));
+
+        f.render_widget(input, area);
+        return;
+    }
+
+    let (text, style) = match &app.status_message {
+        Some(msg) if msg.starts_with("ERROR") => (msg.clone(), Style::default().fg(Color::Red)),
+        Some(msg) => (msg.clone(), Style::default().fg(Color::Green)),
+        None => (
+            "a: Start command | s: Stop | r: Restart | x: Remove | ↑/↓: Select".to_string(),
+            Style::default().fg(Color::Cyan),
+        ),
+    };
+
+    let status = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Status ")
+                .title_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+        )
+        .style(style);
+
+    f.render_widget(status, area);
+}
+
+fn state_color(state: ManagedState) -> Color {
+    match state {
+        ManagedState::Running => Color::Green,
+        ManagedState::Starting | ManagedState::BackingOff => Color::Yellow,
+        ManagedState::Exited | ManagedState::Stopped => Color::Gray,
+        ManagedState::Failed => Color::Red,
+    }
+}
+
+// How long it has been running, or when it restarts while backing off.
+fn uptime(managed: &ManagedProcess) -> String {
+    let now = Utc::now();
+    match managed.state {
+        ManagedState::Running => managed.started_at.map_or_else(
+            || "-".to_string(),
+            |started| format_duration((now - started).num_seconds().max(0) as u64),
+        ),
+        ManagedState::BackingOff => managed.next_restart.map_or_else(
+            || "-".to_string(),
+            |next| {
+                format!(
+                    "restart in {}",
+                    format_duration((next - now).num_seconds().max(0) as u64)
+                )
+            },
+        ),
+        _ => "-".to_string(),
+    }
+}
@@ -10,7 +10,12 @@ use oxyd_domain::models::SystemMetrics;
 use crate::app::AppState;
 use super::widgets::{create_gauge_bar, format_bytes};
 
-pub fn render(f: &mut Frame, area: Rect, metrics: &SystemMetrics, _app: &AppState) {
+// Processes listed in the top swap users panel.
+const TOP_SWAP_USERS: usize = 10;
+// Processes listed in the OOM candidates panel.
+const OOM_CANDIDATES: usize = 10;
+
+pub fn render(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app: &AppState) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -19,8 +24,20 @@ pub fn render(f: &mut Frame, area: Rect, metrics: &SystemMetrics, _app: &AppStat
         ])
         .split(area);
 
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[1]);
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(bottom[1]);
+
     render_ram(f, chunks[0], metrics);
-    render_swap(f, chunks[1], metrics);
+    render_swap(f, bottom[0], metrics);
+    render_top_swap(f, right[0], app);
+    render_oom_candidates(f, right[1], app);
 }
 
 fn render_ram(f: &mut Frame, area: Rect, metrics: &SystemMetrics) {
@@ -53,7 +70,10 @@ fn render_ram(f: &mut Frame, area: Rect, metrics: &SystemMetrics) {
             Span::styled("Buffers:   ", Style::default().fg(Color::Cyan)),
             Span::raw(format_bytes(mem.buffers_bytes)),
         ]),
-        Line::from(""),
+        Line::from(vec![
+            Span::styled("OOM kills: ", Style::default().fg(Color::Cyan)),
+            Span::raw(mem.oom_kills.map_or_else(|| "-".to_string(), |kills| format!("{} since boot", kills))),
+        ]),
         Line::from(vec![
             Span::styled("Usage: ", Style::default().fg(Color::Cyan)),
         ]),
@@ -99,3 +119,102 @@ fn render_swap(f: &mut Frame, area: Rect, metrics: &SystemMetrics) {
     let paragraph = Paragraph::new(lines).block(block);
     f.render_widget(paragraph, area);
 }
+
+fn render_top_swap(f: &mut Frame, area: Rect, app: &AppState) {
+    let lines = if !app.memory_accounting {
+        vec![
+            Line::from(Span::styled(
+                "Per-process swap needs memory accounting.",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(Span::styled(
+                "Press U to read PSS, USS and swap from smaps_rollup.",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ]
+    } else {
+        let mut swapping: Vec<_> = app
+            .process_list
+            .iter()
+            .filter_map(|p| p.memory_accounting.map(|a| (p, a.swap_bytes)))
+            .filter(|&(_, swap)| swap > 0)
+            .collect();
+        swapping.sort_by_key(|&(_, swap)| std::cmp::Reverse(swap));
+
+        if swapping.is_empty() {
+            vec![Line::from(Span::styled(
+                "No readable process is using swap.",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            swapping
+                .iter()
+                .take(TOP_SWAP_USERS)
+                .map(|(p, swap)| {
+                    Line::from(vec![
+                        Span::styled(format!("{:>10}  ", format_bytes(*swap)), Style::default().fg(Color::Magenta)),
+                        Span::styled(format!("{:>8}  ", p.pid), Style::default().fg(Color::Cyan)),
+                        Span::raw(p.name.clone()),
+                    ])
+                })
+                .collect()
+        }
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Top Swap Users ")
+        .style(Style::default().fg(Color::Magenta));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, area);
+}
+
+// The processes the OOM killer would pick first: highest oom_score, which
+// is the share of memory (RSS, swap and page tables) plus oom_score_adj.
+fn render_oom_candidates(f: &mut Frame, area: Rect, app: &AppState) {
+    let mut candidates: Vec<_> = app
+        .process_list
+        .iter()
+        .filter_map(|p| p.oom_score.map(|score| (p, score)))
+        .filter(|&(_, score)| score > 0)
+        .collect();
+    candidates.sort_by_key(|&(p, score)| std::cmp::Reverse((score, p.memory_usage_bytes)));
+
+    let lines = if candidates.is_empty() {
+        vec![Line::from(Span::styled(
+            "No OOM scores read yet.",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        let header = Line::from(Span::styled(
+            format!("{:>5} {:>6} {:>10} {:>8}  Name", "Score", "Adj", "RSS", "PID"),
+            Style::default().fg(Color::Cyan),
+        ));
+        std::iter::once(header)
+            .chain(candidates.iter().take(OOM_CANDIDATES).map(|(p, score)| {
+                let adj = p.oom_score_adj.unwrap_or_default();
+                let adj_style = match adj {
+                    adj if adj < 0 => Style::default().fg(Color::Green),
+                    adj if adj > 0 => Style::default().fg(Color::Yellow),
+                    _ => Style::default(),
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>5} ", score), Style::default().fg(Color::Red)),
+                    Span::styled(format!("{:>6} ", adj), adj_style),
+                    Span::raw(format!("{:>10} ", format_bytes(p.memory_usage_bytes))),
+                    Span::styled(format!("{:>8}  ", p.pid), Style::default().fg(Color::Cyan)),
+                    Span::raw(p.name.clone()),
+                ])
+            }))
+            .collect()
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" OOM Candidates | O on Processes: adjust ")
+        .style(Style::default().fg(Color::Red));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, area);
+}
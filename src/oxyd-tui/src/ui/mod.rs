@@ -1,6 +1,11 @@
+pub mod audit;
+pub mod cgroups;
+pub mod confirm;
 pub mod cpu;
+pub mod details;
 pub mod disk;
 pub mod help;
+pub mod managed;
 pub mod memory;
 pub mod network;
 pub mod notifications;
@@ -36,6 +41,10 @@ pub fn render(f: &mut Frame, app: &AppState) {
     if app.show_help {
         help::render_help(f);
     }
+
+    if let Some(ref batch) = app.confirmation {
+        confirm::render_confirmation(f, batch, app.dry_run);
+    }
 }
 
 fn render_header(f: &mut Frame, area: Rect, app: &AppState) {
@@ -68,7 +77,11 @@ fn render_header(f: &mut Frame, area: Rect, app: &AppState) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" OXYD ")
+                .title(if app.dry_run {
+                    " OXYD [DRY RUN] "
+                } else {
+                    " OXYD "
+                })
                 .style(Style::default().fg(Color::Cyan)),
         )
         .select(app.current_tab as usize)
@@ -92,6 +105,9 @@ fn render_content(f: &mut Frame, area: Rect, app: &AppState) {
             Tab::Disk => disk::render(f, area, metrics, app),
             Tab::Notifications => notifications::render(f, area, app),
             Tab::Settings => settings::render(f, area, app), // NOVO
+            Tab::Audit => audit::render(f, area, app),
+            Tab::Cgroups => cgroups::render(f, area, metrics, app),
+            Tab::Managed => managed::render(f, area, app),
         }
     } else {
         render_loading(f, area);
@@ -107,29 +123,49 @@ fn render_footer(f: &mut Frame, area: Rect, app: &AppState) {
     };
 
     let help_text = match app.current_tab {
+        Tab::Processes if app.process_details.is_some() => format!(
+            " Esc/Enter: Close details | ↑/↓: Scroll | H: Threads | r: Reload | ?: Help | q: Quit{}",
+            notif_indicator
+        ),
         Tab::Processes => {
             if !app.process_filter.is_empty() {
                 format!(
-                    " Filter: '{}' | /: Search | x: Clear | ↑/↓: Scroll | Tab: Next | 1-8: Switch Tab | ?: Help | q: Quit{}",
+                    " Filter: '{}' | /: Search | x: Clear | ↑/↓: Scroll | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
                     app.process_filter, notif_indicator
                 )
             } else {
                 format!(
-                    " /: Search | ↑/↓: Scroll | PgUp/PgDn: Page | Tab: Next | 1-8: Switch Tab | ?: Help | q: Quit{}",
+                    " /: Search | ↑/↓: Scroll | PgUp/PgDn: Page | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
                     notif_indicator
                 )
             }
         }
+        Tab::Memory => format!(
+            " U: Toggle PSS/USS/swap accounting | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
+            notif_indicator
+        ),
         Tab::Settings => format!(
-            " c/m/d: Edit thresholds | Tab: Next | 1-8: Switch Tab | ?: Help | q: Quit{}",
+            " c/m/d: Edit thresholds | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
+            notif_indicator
+        ),
+        Tab::Cgroups => format!(
+            " ↑/↓: Scroll | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
+            notif_indicator
+        ),
+        Tab::Managed => format!(
+            " a: Start | s: Stop | r: Restart | x: Remove | ↑/↓: Select | Tab: Next | 0-9/-: Switch Tab | ?: Help | q: Quit{}",
+            notif_indicator
+        ),
+        Tab::Audit => format!(
+            " r: Reload | ↑/↓: Scroll | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
             notif_indicator
         ),
         Tab::Notifications => format!(
-            " m: Mark all read | x: Clear all | Tab: Next | 1-8: Switch Tab | ?: Help | q: Quit{}",
+            " m: Mark all read | x: Clear all | Tab: Next | 0-9: Switch Tab | ?: Help | q: Quit{}",
             notif_indicator
         ),
         _ => format!(
-            " Tab: Next Tab | 1-8: Switch Tab | ?: Help | q: Quit{}",
+            " Tab: Next Tab | 0-9: Switch Tab | ?: Help | q: Quit{}",
             notif_indicator
         ),
     };
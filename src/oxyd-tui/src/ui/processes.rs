@@ -6,9 +6,11 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
 };
 
+use super::details;
 use super::widgets::format_bytes;
-use crate::app::AppState;
-use oxyd_domain::models::{ProcessState, SystemMetrics};
+use crate::app::{AppState, InputMode, SORT_COLUMNS};
+use crate::groups::HOST_GROUP;
+use oxyd_domain::models::{MemoryAccounting, ProcessState, SystemMetrics, format_cpu_list};
 
 pub fn render(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app: &AppState) {
     let chunks = Layout::default()
@@ -21,22 +23,20 @@ pub fn render(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app: &AppState
         .split(area);
 
     render_process_stats(f, chunks[0], metrics, app);
-    render_process_table(f, chunks[1], app);
+    match &app.process_details {
+        Some(view) => details::render(f, chunks[1], app, view),
+        None => render_process_table(f, chunks[1], app),
+    }
     render_status_bar(f, chunks[2], app);
 }
 
 fn render_process_stats(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app: &AppState) {
     let procs = &metrics.processes;
 
-    let sort_info = match app.sort_column {
-        0 => "PID",
-        1 => "Name",
-        2 => "CPU%",
-        3 => "Memory",
-        4 => "State",
-        5 => "User",
-        _ => "Unknown",
-    };
+    let sort_info = SORT_COLUMNS
+        .get(app.sort_column)
+        .copied()
+        .unwrap_or("Unknown");
 
     let sort_direction = if app.sort_ascending { "↑" } else { "↓" };
 
@@ -141,6 +141,23 @@ fn render_process_stats(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app:
             ),
             Span::raw(" processes"),
             Span::raw("    "),
+            Span::styled(
+                "View: ",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                if app.tree_view {
+                    "Tree"
+                } else if app.group_by_container {
+                    "Containers"
+                } else {
+                    "Flat"
+                },
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw("    "),
             Span::styled(
                 "Sort: ",
                 Style::default()
@@ -151,6 +168,21 @@ fn render_process_stats(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app:
                 format!("{} {}", sort_info, sort_direction),
                 Style::default().fg(Color::Yellow),
             ),
+            Span::raw("    "),
+            Span::styled(
+                "Marked: ",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}", app.marked_processes.len()),
+                Style::default().fg(if app.marked_processes.is_empty() {
+                    Color::White
+                } else {
+                    Color::LightMagenta
+                }),
+            ),
         ]),
         Line::from(""),
         filter_line,
@@ -171,25 +203,32 @@ fn render_process_stats(f: &mut Frame, area: Rect, metrics: &SystemMetrics, app:
 }
 
 fn render_process_table(f: &mut Frame, area: Rect, app: &AppState) {
-    let header_cells = vec![
-        "PID", "Name", "State", "CPU%", "Memory", "Mem%", "User", "Threads", "Priority",
+    let mut header_cells = vec![
+        "PID", "Name", "State", "CPU%", "Memory", "Mem%", "User", "Threads", "Priority", "I/O",
     ];
+    if app.memory_accounting {
+        header_cells.extend(["PSS", "USS", "Swap"]);
+    }
+    if app.tree_view {
+        header_cells.extend(["Tree CPU%", "Tree Mem"]);
+    }
+    if app.group_by_container {
+        header_cells.extend(["Container", "Ctr CPU%", "Ctr Mem"]);
+    }
 
     let sort_indicator = if app.sort_ascending { " ▲" } else { " ▼" };
+    let sorted_header = SORT_COLUMNS.get(app.sort_column).copied();
 
-    let header = Row::new(header_cells.iter().enumerate().map(|(i, h)| {
-        let text = if i == app.sort_column {
+    let header = Row::new(header_cells.iter().map(|&h| {
+        let sorted = sorted_header == Some(h);
+        let text = if sorted {
             format!("{}{}", h, sort_indicator)
         } else {
             h.to_string()
         };
         Cell::from(text).style(
             Style::default()
-                .fg(if i == app.sort_column {
-                    Color::Yellow
-                } else {
-                    Color::Cyan
-                })
+                .fg(if sorted { Color::Yellow } else { Color::Cyan })
                 .add_modifier(Modifier::BOLD),
         )
     }))
@@ -208,6 +247,7 @@ fn render_process_table(f: &mut Frame, area: Rect, app: &AppState) {
         .map(|(i, process)| {
             let actual_index = visible_start + i;
             let is_selected = app.selected_process == Some(actual_index);
+            let is_marked = app.marked_processes.contains(&process.identity());
 
             let state_str = match process.state {
                 ProcessState::Running => "RUN",
@@ -252,10 +292,40 @@ fn render_process_table(f: &mut Frame, area: Rect, app: &AppState) {
             } else {
                 Style::default()
             };
+            let is_watched = app.watches.contains_key(&process.identity());
+            let mark = match (is_marked, is_watched) {
+                (true, _) => "* ",
+                (false, true) => "w ",
+                (false, false) => "  ",
+            };
+            let tree_row = app.process_tree.get(actual_index);
 
-            Row::new(vec![
-                Cell::from(format!("{}", process.pid)).style(base_style),
-                Cell::from(truncate_string(&process.name, 20)).style(base_style),
+            let name = match tree_row {
+                Some(row) => {
+                    let toggle = match (row.has_children, row.collapsed) {
+                        (true, true) => "▸ ",
+                        (true, false) => "▾ ",
+                        _ => "",
+                    };
+                    format!("{}{}{}", row.prefix, toggle, process.name)
+                }
+                None => truncate_string(&process.name, 20),
+            };
+            let name_style = if tree_row.is_some_and(|row| row.context_only) {
+                base_style.fg(Color::DarkGray)
+            } else {
+                base_style
+            };
+
+            let mut cells = vec![
+                Cell::from(format!("{}{}", mark, process.pid)).style(if is_marked {
+                    base_style.fg(Color::LightMagenta)
+                } else if is_watched {
+                    base_style.fg(Color::LightCyan)
+                } else {
+                    base_style
+                }),
+                Cell::from(name).style(name_style),
                 Cell::from(state_str).style(base_style.fg(state_color)),
                 Cell::from(format!("{:.1}", process.cpu_usage_percent))
                     .style(base_style.fg(cpu_color)),
@@ -265,8 +335,56 @@ fn render_process_table(f: &mut Frame, area: Rect, app: &AppState) {
                 Cell::from(truncate_string(&process.user, 12)).style(base_style),
                 Cell::from(format!("{}", process.threads)).style(base_style),
                 Cell::from(format!("{}", process.priority)).style(base_style),
-            ])
-            .height(1)
+                Cell::from(
+                    process
+                        .io_priority
+                        .map(|io| io.label())
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style(base_style),
+            ];
+            if app.memory_accounting {
+                // Without root, other users' smaps_rollup is unreadable.
+                let accounting = |bytes: fn(&MemoryAccounting) -> u64| {
+                    process
+                        .memory_accounting
+                        .as_ref()
+                        .map_or_else(|| "-".to_string(), |a| format_bytes(bytes(a)))
+                };
+                cells.push(Cell::from(accounting(|a| a.pss_bytes)).style(base_style));
+                cells.push(Cell::from(accounting(|a| a.uss_bytes)).style(base_style));
+                cells.push(Cell::from(accounting(|a| a.swap_bytes)).style(base_style));
+            }
+            if let Some(row) = tree_row {
+                let collapsed_size = if row.collapsed {
+                    format!(" ({})", row.subtree_size)
+                } else {
+                    String::new()
+                };
+                cells.push(
+                    Cell::from(format!("{:.1}{}", row.subtree_cpu_percent, collapsed_size))
+                        .style(base_style),
+                );
+                cells.push(Cell::from(format_bytes(row.subtree_memory_bytes)).style(base_style));
+            }
+            if let Some(row) = app.process_groups.get(actual_index) {
+                // Label and totals once per group, on its first row.
+                if row.first {
+                    cells.push(
+                        Cell::from(truncate_string(&row.label, 20))
+                            .style(base_style.fg(Color::LightBlue)),
+                    );
+                    cells.push(
+                        Cell::from(format!("{:.1} ({})", row.cpu_percent, row.size))
+                            .style(base_style),
+                    );
+                    cells.push(Cell::from(format_bytes(row.memory_bytes)).style(base_style));
+                } else {
+                    cells.push(Cell::from("│").style(base_style.fg(Color::DarkGray)));
+                }
+            }
+
+            Row::new(cells).height(1)
         })
         .collect();
 
@@ -283,51 +401,109 @@ fn render_process_table(f: &mut Frame, area: Rect, app: &AppState) {
         .bg(Color::DarkGray)
         .add_modifier(Modifier::BOLD);
 
-    let table = Table::new(
-        rows,
-        vec![
-            Constraint::Length(8),  // pID
-            Constraint::Min(20),    // name
-            Constraint::Length(6),  // state
-            Constraint::Length(8),  // cPU
-            Constraint::Length(10), // memory bytes
-            Constraint::Length(8),  // memory %
-            Constraint::Length(12), // user
-            Constraint::Length(8),  // threads
-            Constraint::Length(9),  // priority
-        ],
-    )
-    .header(header)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(format!(" Process List ({} total) ", app.process_list.len()))
-            .title_style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .style(Style::default().fg(Color::White)),
-    )
-    .row_highlight_style(selected_style)
-    .highlight_symbol("▶ ");
+    let mut widths = vec![
+        Constraint::Length(10), // mark + PID
+        Constraint::Min(20),    // name
+        Constraint::Length(6),  // state
+        Constraint::Length(8),  // cPU
+        Constraint::Length(10), // memory bytes
+        Constraint::Length(8),  // memory %
+        Constraint::Length(12), // user
+        Constraint::Length(8),  // threads
+        Constraint::Length(9),  // priority
+        Constraint::Length(6),  // I/O priority
+    ];
+    if app.memory_accounting {
+        widths.extend([
+            Constraint::Length(10), // PSS
+            Constraint::Length(10), // USS
+            Constraint::Length(10), // swap
+        ]);
+    }
+    if app.tree_view {
+        widths.extend([
+            Constraint::Length(14), // subtree CPU
+            Constraint::Length(10), // subtree memory
+        ]);
+    }
+    if app.group_by_container {
+        widths.extend([
+            Constraint::Length(20), // container
+            Constraint::Length(14), // container CPU
+            Constraint::Length(10), // container memory
+        ]);
+    }
+    let title = if app.tree_view {
+        format!(" Process Tree ({} total) ", app.process_list.len())
+    } else if app.group_by_container {
+        let containers = app
+            .process_groups
+            .iter()
+            .filter(|row| row.first && row.label != HOST_GROUP)
+            .count();
+        format!(
+            " Processes by Container ({} containers, {} total) ",
+            containers,
+            app.process_list.len()
+        )
+    } else {
+        format!(" Process List ({} total) ", app.process_list.len())
+    };
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .style(Style::default().fg(Color::White)),
+        )
+        .row_highlight_style(selected_style)
+        .highlight_symbol("▶ ");
 
     f.render_widget(table, area);
 }
 
 fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
+    if let Some(prompt) = input_prompt(app.input_mode) {
+        let input = Paragraph::new(format!("{}_", app.input_buffer))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(prompt)
+                    .title_style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .style(Style::default().fg(Color::White));
+
+        f.render_widget(input, area);
+        return;
+    }
+
     let status_text = if let Some(ref msg) = app.status_message {
         msg.clone()
     } else if let Some(selected_idx) = app.selected_process {
-        if let Some(process) = app.process_list.get(selected_idx) {
+        if let Some(process) = app.filtered_process_list.get(selected_idx) {
             format!(
-                "Selected: {} (PID: {}) | CPU: {:.1}% | MEM: {} ({:.1}%) | Threads: {}",
+                "Selected: {} (PID: {}) | CPU: {:.1}% | MEM: {} ({:.1}%) | Threads: {} | Nice: {} | Policy: {} | CPUs: {}",
                 process.name,
                 process.pid,
                 process.cpu_usage_percent,
                 format_bytes(process.memory_usage_bytes),
                 process.memory_usage_percent,
-                process.threads
+                process.threads,
+                process.nice,
+                process.scheduling_policy.name(),
+                format_cpu_list(&process.cpu_affinity)
             )
         } else {
             "No process selected".to_string()
@@ -362,6 +538,32 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(status, area);
 }
 
+fn input_prompt(mode: InputMode) -> Option<&'static str> {
+    match mode {
+        InputMode::EditNice => Some(" Nice value (-20..19) | Enter: apply | Esc: cancel "),
+        InputMode::EditScheduler => Some(
+            " Policy [priority]: other | batch | idle | fifo 1-99 | rr 1-99 | Enter: apply | Esc: cancel ",
+        ),
+        InputMode::EditAffinity => Some(" CPU list (e.g. 0-3,6) | Enter: apply | Esc: cancel "),
+        InputMode::EditIoPriority => {
+            Some(" I/O class [level]: rt 0-7 | be 0-7 | idle | none | Enter: apply | Esc: cancel ")
+        }
+        InputMode::EditCgroup => Some(
+            " Move to cgroup (an existing one, e.g. /oxyd/throttled) | Enter: apply | Esc: cancel ",
+        ),
+        InputMode::EditCgroupLimit => Some(
+            " Limit on its cgroup: cpu 0.5 | high 512M | max 1G | io 8:0 rbps=10M wbps=max | Enter: apply | Esc: cancel ",
+        ),
+        InputMode::EditOomScoreAdj => Some(
+            " oom_score_adj: -1000 never kill .. 0 default .. 1000 kill first | Enter: apply | Esc: cancel ",
+        ),
+        InputMode::EditLimit => Some(
+            " Resource soft [hard]: nofile 65536 | core unlimited | as 4G | nproc 4096 (hard kept if omitted) | Enter: apply | Esc: cancel ",
+        ),
+        _ => None,
+    }
+}
+
 fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
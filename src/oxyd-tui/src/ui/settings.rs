@@ -1,3 +1,4 @@
+use oxyd_domain::{AlertEvent, AlertState, CPU_ALERT_RULE, DISK_ALERT_RULE, MEMORY_ALERT_RULE};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -43,7 +44,7 @@ fn render_title(f: &mut Frame, area: Rect) {
 }
 
 fn render_settings_list(f: &mut Frame, area: Rect, app: &AppState) {
-    let items = vec![
+    let mut items = vec![
         ListItem::new(vec![Line::from(vec![
             Span::styled(
                 "CPU Alert Threshold:      ",
@@ -104,39 +105,34 @@ fn render_settings_list(f: &mut Frame, area: Rect, app: &AppState) {
         )])]),
         ListItem::new(vec![Line::from(vec![
             Span::raw("  • CPU:    "),
-            if app.last_cpu_alert.is_some() {
-                Span::styled(
-                    "TRIGGERED",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                )
-            } else {
-                Span::styled("✓ OK", Style::default().fg(Color::Green))
-            },
+            alert_status(app.active_alerts.get(CPU_ALERT_RULE)),
         ])]),
         ListItem::new(vec![Line::from(vec![
             Span::raw("  • Memory: "),
-            if app.last_memory_alert.is_some() {
-                Span::styled(
-                    "TRIGGERED",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                )
-            } else {
-                Span::styled("✓ OK", Style::default().fg(Color::Green))
-            },
+            alert_status(app.active_alerts.get(MEMORY_ALERT_RULE)),
         ])]),
         ListItem::new(vec![Line::from(vec![
             Span::raw("  • Disk:   "),
-            if app.last_disk_alert.is_some() {
-                Span::styled(
-                    "⚠ TRIGGERED",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                )
-            } else {
-                Span::styled("✓ OK", Style::default().fg(Color::Green))
-            },
+            alert_status(app.active_alerts.get(DISK_ALERT_RULE)),
         ])]),
     ];
 
+    // Configured rules only show up while pending or firing.
+    items.extend(
+        app.active_alerts
+            .values()
+            .filter(|alert| {
+                ![CPU_ALERT_RULE, MEMORY_ALERT_RULE, DISK_ALERT_RULE]
+                    .contains(&alert.rule_id.as_str())
+            })
+            .map(|alert| {
+                ListItem::new(vec![Line::from(vec![
+                    Span::raw(format!("  • {}: ", alert.rule_name)),
+                    alert_status(Some(alert)),
+                ])])
+            }),
+    );
+
     let list = List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
@@ -152,6 +148,17 @@ fn render_settings_list(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(list, area);
 }
 
+fn alert_status(alert: Option<&AlertEvent>) -> Span<'static> {
+    match alert.map(|alert| alert.state) {
+        Some(AlertState::Firing) => Span::styled(
+            "TRIGGERED",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        Some(AlertState::Pending) => Span::styled("PENDING", Style::default().fg(Color::Yellow)),
+        _ => Span::styled("✓ OK", Style::default().fg(Color::Green)),
+    }
+}
+
 fn render_input_box(f: &mut Frame, area: Rect, app: &AppState) {
     let (title, input_text, color) = match app.input_mode {
         InputMode::EditCpuThreshold => (